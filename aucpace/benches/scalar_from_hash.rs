@@ -0,0 +1,23 @@
+use aucpace_conflux::scalar_from_wide_hash;
+use bencher::{Bencher, benchmark_group, benchmark_main, black_box};
+use curve25519_dalek::Scalar;
+
+const HASH: [u8; 64] = [0xa5; 64];
+
+/// The previous 64 byte path: a fallible conversion which copies the slice into an array
+fn scalar_from_hash_slice(bench: &mut Bencher) {
+    let hash: &[u8] = &HASH;
+    bench.iter(|| {
+        let hash = black_box(hash);
+        let arr: [u8; 64] = hash.try_into().unwrap();
+        Scalar::from_bytes_mod_order_wide(&arr)
+    })
+}
+
+/// The array fast path, no length check and no copy
+fn scalar_from_hash_array(bench: &mut Bencher) {
+    bench.iter(|| scalar_from_wide_hash(black_box(&HASH)))
+}
+
+benchmark_group!(benches, scalar_from_hash_slice, scalar_from_hash_array);
+benchmark_main!(benches);
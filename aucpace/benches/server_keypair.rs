@@ -0,0 +1,56 @@
+use aucpace_conflux::generate_server_keypair;
+use bencher::{Bencher, benchmark_group, benchmark_main, black_box};
+use curve25519_dalek::Scalar;
+use curve25519_dalek::constants::{RISTRETTO_BASEPOINT_POINT, RISTRETTO_BASEPOINT_TABLE};
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha512;
+
+const PRIVATE: [u8; 32] = [0xa5; 32];
+
+/// Cheap deterministic RNG, so that only the keypair generation itself is measured
+struct CounterRng(u64);
+
+impl RngCore for CounterRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.0 = self.0.wrapping_add(1);
+        for chunk in dst.chunks_mut(8) {
+            chunk.copy_from_slice(&self.0.to_le_bytes()[..chunk.len()]);
+        }
+    }
+}
+
+impl CryptoRng for CounterRng {}
+
+/// The previous path: a variable-base multiplication of the basepoint
+fn basepoint_mul_variable_base(bench: &mut Bencher) {
+    let x = Scalar::from_bytes_mod_order(PRIVATE);
+    bench.iter(|| RISTRETTO_BASEPOINT_POINT * black_box(x))
+}
+
+/// The fixed-base multiplication through the precomputed basepoint table
+fn basepoint_mul_table(bench: &mut Bencher) {
+    let x = Scalar::from_bytes_mod_order(PRIVATE);
+    bench.iter(|| RISTRETTO_BASEPOINT_TABLE * &black_box(x))
+}
+
+/// Repeated server keypair generation, as done for every handshake without a fixed key
+fn server_keypair(bench: &mut Bencher) {
+    let mut rng = CounterRng(0);
+    bench.iter(|| generate_server_keypair::<Sha512, _>(&mut rng).unwrap())
+}
+
+benchmark_group!(
+    benches,
+    basepoint_mul_variable_base,
+    basepoint_mul_table,
+    server_keypair
+);
+benchmark_main!(benches);
@@ -0,0 +1,225 @@
+//! Print the intermediate values of a handshake driven by a seeded RNG, for comparing against
+//! other `AuCPace` implementations. The output only depends on `SEED`, run it with
+//! `cargo run --example dump_vectors` to regenerate the vectors.
+use aucpace_conflux::{
+    AuCPaceClient, AuCPaceServer, ClientMessage, Database, Result, ServerMessage,
+    compute_authenticator_messages_secret, compute_first_session_key_ref, compute_ssid_slices,
+    cpace_generator, generate_keypair, generate_server_keypair, server_prs,
+};
+use curve25519_dalek::RistrettoPoint;
+use password_hash::{ParamsString, SaltString};
+use rand_core::{CryptoRng, RngCore};
+use scrypt::{Params, Scrypt};
+use secret_utils::wrappers::SecretScalar;
+use sha2::{Digest, Sha512};
+
+/// the seed every random value of the handshake is derived from
+const SEED: [u8; 32] = *b"aucpace-conflux test vector seed";
+
+const USERNAME: &[u8] = b"jlpicard_1701";
+const PASSWORD: &[u8] = b"g04tEd_c4pT41N";
+const CI: &[u8] = b"dump_vectors_channel_identifier";
+const K1: usize = 16;
+
+type Client = AuCPaceClient<Sha512, Scrypt, SeedRng, K1>;
+type Server = AuCPaceServer<Sha512, SeedRng, K1>;
+
+/// Deterministic RNG producing `SHA-512(SEED || label || counter)` blocks
+///
+/// Every party draws from its own labelled stream, so the value a step draws does not depend
+/// on how many values other steps drew. Never use this outside of generating test vectors.
+struct SeedRng {
+    label: &'static [u8],
+    counter: u64,
+}
+
+impl SeedRng {
+    fn new(label: &'static [u8]) -> Self {
+        Self { label, counter: 0 }
+    }
+}
+
+impl RngCore for SeedRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(64) {
+            let block = Sha512::new()
+                .chain_update(SEED)
+                .chain_update(self.label)
+                .chain_update(self.counter.to_le_bytes())
+                .finalize();
+            self.counter += 1;
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+}
+
+impl CryptoRng for SeedRng {}
+
+/// Password Verifier database which can store the info for one user
+#[derive(Debug, Default)]
+struct SingleUserDatabase {
+    user: Option<Vec<u8>>,
+    data: Option<(RistrettoPoint, SaltString, ParamsString)>,
+}
+
+impl Database for SingleUserDatabase {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = aucpace_conflux::Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        match &self.user {
+            Some(stored_username) if stored_username == username => self.data.clone(),
+            _ => None,
+        }
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        _uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.user = Some(username.to_vec());
+        self.data = Some((verifier, salt, params));
+    }
+}
+
+/// print a labelled value as lowercase hex
+fn print_hex(label: &str, bytes: impl AsRef<[u8]>) {
+    let hex: String = bytes
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    println!("{label:<10} {hex}");
+}
+
+fn main() -> Result<()> {
+    // ===== Registration =====
+    let mut client = Client::new(SeedRng::new(b"client"));
+    // weak parameters to keep the example fast, do not use them
+    let params = Params::new(8, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    let mut database = SingleUserDatabase::default();
+    let ClientMessage::Registration {
+        username,
+        salt,
+        params,
+        verifier,
+    } = client.register_alloc(USERNAME, PASSWORD, params, Scrypt)?
+    else {
+        unreachable!("register_alloc always returns a registration message");
+    };
+    database.store_verifier(username, salt.clone(), None, verifier, params);
+
+    // the server uses a fixed private key `x`, so that `PRS` can be computed here
+    let (x, x_pub) = generate_server_keypair::<Sha512, _>(&mut SeedRng::new(b"server-x"))?;
+    let mut server = Server::with_secret(SeedRng::new(b"server"), SecretScalar::new(x))?;
+
+    // ===== SSID Establishment =====
+    let (server, ServerMessage::Nonce(s)) = server.begin()? else {
+        unreachable!("begin always returns a nonce message");
+    };
+    let (client, ClientMessage::Nonce(t)) = client.begin()? else {
+        unreachable!("begin always returns a nonce message");
+    };
+    let server = server.agree_ssid(t);
+    let client = client.agree_ssid(s);
+    let ssid = compute_ssid_slices::<Sha512>(&s, &t)?;
+
+    // ===== Augmentation Layer =====
+    let (client, ClientMessage::Username { username, ssid_tag }) =
+        client.start_augmentation(USERNAME, PASSWORD)
+    else {
+        unreachable!("start_augmentation always returns a username message");
+    };
+    let (server, info) = server.generate_client_info(
+        username,
+        ssid_tag,
+        &database,
+        SeedRng::new(b"server-cpace"),
+    )?;
+    let ServerMessage::AugmentationInfo {
+        x_pub: sent_x_pub,
+        salt: sent_salt,
+        pbkdf_params,
+        ..
+    } = info
+    else {
+        panic!("Received invalid server message {:?}", info);
+    };
+    assert_eq!(sent_x_pub.as_point(), &x_pub);
+    let prs = server_prs(&verifier, &x);
+    let generator = cpace_generator::<Sha512, _>(ssid, prs, CI);
+
+    // ===== CPace substep =====
+    let (server, ServerMessage::PublicKey(yb_pub)) = server.generate_public_key(CI)? else {
+        unreachable!("generate_public_key always returns a public key message");
+    };
+    let params = {
+        let log_n = pbkdf_params.get_str("ln").unwrap().parse().unwrap();
+        let r = pbkdf_params.get_str("r").unwrap().parse().unwrap();
+        let p = pbkdf_params.get_str("p").unwrap().parse().unwrap();
+        Params::new(log_n, r, p, Params::RECOMMENDED_LEN).unwrap()
+    };
+    let client = client.generate_cpace_alloc(x_pub, &sent_salt, params, Scrypt)?;
+    let (client, ClientMessage::PublicKey(ya_pub)) =
+        client.generate_public_key(CI, &mut SeedRng::new(b"client-cpace"))?
+    else {
+        unreachable!("generate_public_key always returns a public key message");
+    };
+
+    // re-derive the server's ephemeral key from its RNG stream to compute sk1
+    let (yb, expected_yb_pub) =
+        generate_keypair::<Sha512, _, _>(&mut SeedRng::new(b"server-cpace"), ssid, prs, CI)?;
+    assert_eq!(yb_pub, expected_yb_pub);
+    let sk1 = compute_first_session_key_ref::<Sha512>(ssid, &SecretScalar::new(yb), ya_pub);
+    let (ta, tb) = compute_authenticator_messages_secret::<Sha512>(ssid, sk1);
+
+    // ===== Explicit Mutual Authentication =====
+    let (client, ClientMessage::Authenticator(client_authenticator)) =
+        client.receive_server_pubkey(yb_pub)?
+    else {
+        unreachable!("receive_server_pubkey always returns an authenticator message");
+    };
+    assert_eq!(client_authenticator, tb.expose());
+    let server = server.receive_client_pubkey(ya_pub)?;
+    let (server_key, ServerMessage::Authenticator(server_authenticator)) =
+        server.receive_client_authenticator(client_authenticator)?
+    else {
+        unreachable!("receive_client_authenticator always returns an authenticator message");
+    };
+    assert_eq!(server_authenticator, ta.expose());
+    let client_key = client.receive_server_authenticator(server_authenticator)?;
+    assert!(client_key.ct_eq(&server_key));
+
+    print_hex("seed", SEED);
+    println!("{:<10} {}", "salt", salt.as_str());
+    print_hex("W", verifier.compress().as_bytes());
+    print_hex("X", x_pub.compress().as_bytes());
+    print_hex("s", s);
+    print_hex("t", t);
+    print_hex("ssid", ssid);
+    print_hex("PRS", prs);
+    print_hex("G", generator.compress().as_bytes());
+    print_hex("Ya", ya_pub.compress().as_bytes());
+    print_hex("Yb", yb_pub.compress().as_bytes());
+    print_hex("sk1", sk1);
+    print_hex("Ta", ta.expose());
+    print_hex("Tb", tb.expose());
+    print_hex("sk", client_key.expose());
+
+    Ok(())
+}
@@ -2,9 +2,11 @@
 
 // use println and Instant only from std
 extern crate std;
-use std::{println, time::Instant};
+use std::{print, println, time::Instant};
 
-use aucpace_conflux::{Client, ClientMessage, Database, Result, Server, ServerMessage};
+use aucpace_conflux::{
+    Client, ClientMessage, Database, Result, Server, ServerMessage, ct_username_eq,
+};
 use curve25519_dalek::ristretto::RistrettoPoint;
 use password_hash::{ParamsString, SaltString};
 use rand::rngs::OsRng;
@@ -110,8 +112,8 @@ fn main() -> Result<()> {
 
     // server receives the username then looks up
     client_message = recv!(client_buf);
-    let (server, message) = if let ClientMessage::Username(username) = client_message {
-        server.generate_client_info(username, &database, OsRng)?
+    let (server, message) = if let ClientMessage::Username { username, ssid_tag } = client_message {
+        server.generate_client_info(username, ssid_tag, &database, OsRng)?
     } else {
         panic!("Received invalid client message {:?}", client_message);
     };
@@ -139,7 +141,7 @@ fn main() -> Result<()> {
 
             Params::new(log_n, r, p, scrypt::Params::RECOMMENDED_LEN).unwrap()
         };
-        client.generate_cpace::<&SaltString, 100>(x_pub, &salt, params, Scrypt)?
+        client.generate_cpace::<&SaltString, 100>(x_pub.into(), &salt, params, Scrypt)?
     } else {
         panic!("Received invalid server message {:?}", server_message);
     };
@@ -221,11 +223,12 @@ fn main() -> Result<()> {
 
     // ===== Protocol end =====
     // assert that both threads arrived at the same key
-    assert_eq!(client_key, server_key);
-    println!(
-        "Negotiation finished, both parties arrived at a key of: {:X}",
-        client_key
-    );
+    assert!(client_key.ct_eq(&server_key));
+    print!("Negotiation finished, both parties arrived at a key of: ");
+    for b in client_key.iter() {
+        print!("{b:02X}");
+    }
+    println!();
 
     println!("Client sent {} bytes total", client_bytes_sent);
     println!("Server sent {} bytes total", server_bytes_sent);
@@ -246,13 +249,16 @@ struct SingleUserDatabase<const USERSIZE: usize> {
 
 impl<const USERSIZE: usize> Database for SingleUserDatabase<USERSIZE> {
     type PasswordVerifier = RistrettoPoint;
+    type Error = aucpace_conflux::Error;
 
     fn lookup_verifier(
         &self,
         username: &[u8],
     ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
         match self.user {
-            Some((ref stored_username, len)) if &stored_username[..len] == username => {
+            Some((ref stored_username, len))
+                if ct_username_eq(&stored_username[..len], username) =>
+            {
                 self.data.clone()
             }
             _ => None,
@@ -1,13 +1,13 @@
 use aucpace_conflux::{
     Client, ClientMessage, Database, Error, PartialAugDatabase, Result, Server, ServerMessage,
+    ct_username_eq,
 };
 use curve25519_dalek::ristretto::RistrettoPoint;
 use curve25519_dalek::scalar::Scalar;
 use password_hash::{ParamsString, SaltString};
 use rand::rngs::OsRng;
 use scrypt::{Params, Scrypt};
-use sha2::Sha512;
-use sha2::digest::Output;
+use secret_utils::wrappers::SecretKey;
 use std::io::{Read, Write};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -17,7 +17,7 @@ use std::time::Instant;
 /// function like macro to wrap sending data over a tcp stream, returns the number of bytes sent
 macro_rules! send {
     ($stream:ident, $msg:ident) => {{
-        let serialised = bincode::serialize(&$msg).unwrap();
+        let serialised = bincode::serde::encode_to_vec(&$msg, bincode::config::legacy()).unwrap();
         $stream.write_all(&serialised).unwrap();
         serialised.len()
     }};
@@ -28,7 +28,9 @@ macro_rules! recv {
     ($stream:ident, $buf:ident) => {{
         let bytes_received = $stream.read(&mut $buf).unwrap();
         let received = &$buf[..bytes_received];
-        bincode::deserialize(received).unwrap()
+        bincode::serde::borrow_decode_from_slice(received, bincode::config::legacy())
+            .unwrap()
+            .0
     }};
 }
 
@@ -65,7 +67,7 @@ fn main() -> Result<()> {
     static SERVER_BYTES_SENT: AtomicUsize = AtomicUsize::new(0);
 
     // spawn a thread for the server
-    let server_thread = thread::spawn(move || -> Result<Output<Sha512>> {
+    let server_thread = thread::spawn(move || -> Result<SecretKey> {
         let start = Instant::now();
         println!("[server] Starting negotiation");
 
@@ -90,12 +92,13 @@ fn main() -> Result<()> {
 
         // ===== Augmentation Layer =====
         client_message = recv!(stream, buf);
-        let (server, message) = if let ClientMessage::Username(username) = client_message {
-            // This is the only difference from the non-augmented protocol flow
-            server.generate_client_info_partial_aug(username, &database, OsRng)?
-        } else {
-            panic!("Received invalid client message {:?}", client_message);
-        };
+        let (server, message) =
+            if let ClientMessage::Username { username, ssid_tag } = client_message {
+                // This is the only difference from the non-augmented protocol flow
+                server.generate_client_info_partial_aug(username, ssid_tag, &database, OsRng)?
+            } else {
+                panic!("Received invalid client message {:?}", client_message);
+            };
         let bytes_sent = send!(stream, message);
         SERVER_BYTES_SENT.fetch_add(bytes_sent, Ordering::SeqCst);
         println!(
@@ -143,7 +146,7 @@ fn main() -> Result<()> {
     });
 
     // spawn a thread for the client
-    let client_thread = thread::spawn(move || -> Result<Output<Sha512>> {
+    let client_thread = thread::spawn(move || -> Result<SecretKey> {
         println!("[client] Starting negotiation");
         let start = Instant::now();
 
@@ -189,7 +192,7 @@ fn main() -> Result<()> {
 
                 Params::new(log_n, r, p, scrypt::Params::RECOMMENDED_LEN).unwrap()
             };
-            client.generate_cpace_alloc(x_pub, &salt, params, Scrypt)?
+            client.generate_cpace_alloc(x_pub.into(), &salt, params, Scrypt)?
         } else {
             panic!("Received invalid server message {:?}", server_message);
         };
@@ -235,12 +238,15 @@ fn main() -> Result<()> {
     });
 
     // assert that both threads arrived at the same key
-    let client_key: Output<Sha512> = client_thread.join().unwrap().unwrap();
-    let server_key: Output<Sha512> = server_thread.join().unwrap().unwrap();
-    assert_eq!(client_key, server_key);
+    let client_key: SecretKey = client_thread.join().unwrap().unwrap();
+    let server_key: SecretKey = server_thread.join().unwrap().unwrap();
+    assert!(client_key.ct_eq(&server_key));
     println!(
-        "Negotiation finished, both parties arrived at a key of: {:X}",
+        "Negotiation finished, both parties arrived at a key of: {}",
         client_key
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect::<String>()
     );
     println!(
         "Client sent {} bytes total",
@@ -264,13 +270,14 @@ struct SingleUserDatabase {
 
 impl Database for SingleUserDatabase {
     type PasswordVerifier = RistrettoPoint;
+    type Error = Error;
 
     fn lookup_verifier(
         &self,
         username: &[u8],
     ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
         match &self.user {
-            Some(stored_username) if stored_username == username => self.data.clone(),
+            Some(stored_username) if ct_username_eq(stored_username, username) => self.data.clone(),
             _ => None,
         }
     }
@@ -297,7 +304,7 @@ impl PartialAugDatabase for SingleUserDatabase {
         username: &[u8],
     ) -> Option<(Self::PrivateKey, Self::PublicKey)> {
         match &self.user {
-            Some(stored_user) if stored_user == username => self.long_term_keypair,
+            Some(stored_user) if ct_username_eq(stored_user, username) => self.long_term_keypair,
             _ => None,
         }
     }
@@ -309,7 +316,7 @@ impl PartialAugDatabase for SingleUserDatabase {
         pub_key: Self::PublicKey,
     ) -> Result<()> {
         match &self.user {
-            Some(stored_user) if stored_user == username => {
+            Some(stored_user) if ct_username_eq(stored_user, username) => {
                 self.long_term_keypair = Some((priv_key, pub_key));
                 Ok(())
             }
@@ -1,11 +1,12 @@
-use aucpace_conflux::{Client, ClientMessage, Result, Server, ServerMessage, StrongDatabase};
+use aucpace_conflux::{
+    Client, ClientMessage, Result, Server, ServerMessage, StrongDatabase, ct_username_eq,
+};
 use curve25519_dalek::ristretto::RistrettoPoint;
 use curve25519_dalek::scalar::Scalar;
 use password_hash::ParamsString;
 use rand::rngs::OsRng;
 use scrypt::{Params, Scrypt};
-use sha2::Sha512;
-use sha2::digest::Output;
+use secret_utils::wrappers::SecretKey;
 use std::io::{Read, Write};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -15,7 +16,7 @@ use std::time::Instant;
 /// function like macro to wrap sending data over a tcp stream, returns the number of bytes sent
 macro_rules! send {
     ($stream:ident, $msg:ident) => {{
-        let serialised = bincode::serialize(&$msg).unwrap();
+        let serialised = bincode::serde::encode_to_vec(&$msg, bincode::config::legacy()).unwrap();
         $stream.write_all(&serialised).unwrap();
         serialised.len()
     }};
@@ -26,7 +27,9 @@ macro_rules! recv {
     ($stream:ident, $buf:ident) => {{
         let bytes_received = $stream.read(&mut $buf).unwrap();
         let received = &$buf[..bytes_received];
-        bincode::deserialize(received).unwrap()
+        bincode::serde::borrow_decode_from_slice(received, bincode::config::legacy())
+            .unwrap()
+            .0
     }};
 }
 
@@ -58,7 +61,7 @@ fn main() -> Result<()> {
     static SERVER_BYTES_SENT: AtomicUsize = AtomicUsize::new(0);
 
     // spawn a thread for the server
-    let server_thread = thread::spawn(move || -> Result<Output<Sha512>> {
+    let server_thread = thread::spawn(move || -> Result<SecretKey> {
         let start = Instant::now();
         println!("[server] Starting negotiation");
 
@@ -84,12 +87,16 @@ fn main() -> Result<()> {
 
         // ===== Augmentation Layer =====
         client_message = recv!(stream, buf);
-        let (server, message) =
-            if let ClientMessage::StrongUsername { username, blinded } = client_message {
-                server.generate_client_info_strong(username, blinded, &database, OsRng)?
-            } else {
-                panic!("Received invalid client message {:?}", client_message);
-            };
+        let (server, message) = if let ClientMessage::StrongUsername {
+            username,
+            ssid_tag,
+            blinded,
+        } = client_message
+        {
+            server.generate_client_info_strong(username, ssid_tag, blinded, &database, OsRng)?
+        } else {
+            panic!("Received invalid client message {:?}", client_message);
+        };
         let bytes_sent = send!(stream, message);
         SERVER_BYTES_SENT.fetch_add(bytes_sent, Ordering::SeqCst);
         println!(
@@ -137,7 +144,7 @@ fn main() -> Result<()> {
     });
 
     // spawn a thread for the client
-    let client_thread = thread::spawn(move || -> Result<Output<Sha512>> {
+    let client_thread = thread::spawn(move || -> Result<SecretKey> {
         println!("[client] Starting negotiation");
         let start = Instant::now();
 
@@ -183,7 +190,7 @@ fn main() -> Result<()> {
 
                 Params::new(log_n, r, p, scrypt::Params::RECOMMENDED_LEN).unwrap()
             };
-            client.generate_cpace_alloc(x_pub, blinded_salt, params, Scrypt)?
+            client.generate_cpace_alloc(x_pub.into(), blinded_salt, params, Scrypt)?
         } else {
             panic!("Received invalid server message {:?}", server_message);
         };
@@ -229,12 +236,15 @@ fn main() -> Result<()> {
     });
 
     // assert that both threads arrived at the same key
-    let client_key: Output<Sha512> = client_thread.join().unwrap().unwrap();
-    let server_key: Output<Sha512> = server_thread.join().unwrap().unwrap();
-    assert_eq!(client_key, server_key);
+    let client_key: SecretKey = client_thread.join().unwrap().unwrap();
+    let server_key: SecretKey = server_thread.join().unwrap().unwrap();
+    assert!(client_key.ct_eq(&server_key));
     println!(
-        "Negotiation finished, both parties arrived at a key of: {:X}",
+        "Negotiation finished, both parties arrived at a key of: {}",
         client_key
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect::<String>()
     );
     println!(
         "Client sent {} bytes total",
@@ -264,7 +274,7 @@ impl StrongDatabase for SingleUserDatabase {
         username: &[u8],
     ) -> Option<(Self::PasswordVerifier, Self::Exponent, ParamsString)> {
         match &self.user {
-            Some(stored_username) if stored_username == username => self.data.clone(),
+            Some(stored_username) if ct_username_eq(stored_username, username) => self.data.clone(),
             _ => None,
         }
     }
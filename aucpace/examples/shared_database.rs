@@ -0,0 +1,170 @@
+use aucpace_conflux::{Client, ClientMessage, Database, Result, Server, ServerMessage};
+use curve25519_dalek::ristretto::RistrettoPoint;
+use password_hash::{ParamsString, SaltString};
+use rand::rngs::OsRng;
+use scrypt::{Params, Scrypt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const CI: &[u8] = b"shared_database_example";
+
+/// Password Verifier database storing many users, indexed by their username
+///
+/// A real server should use a keyed hash of the username as the key, see `ct_username_eq`.
+#[derive(Debug, Default)]
+struct HashMapDatabase {
+    users: HashMap<Vec<u8>, (RistrettoPoint, SaltString, ParamsString)>,
+}
+
+impl Database for HashMapDatabase {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = aucpace_conflux::Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        self.users
+            .get(username)
+            .map(|(verifier, salt, params)| (*verifier, salt.clone(), params.clone()))
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        _uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.users
+            .insert(username.to_vec(), (verifier, salt, params));
+    }
+}
+
+fn main() -> Result<()> {
+    // example usernames and passwords, never use these...
+    const USERS: [(&[u8], &[u8]); 3] = [
+        (b"jlpicard", b"g04tEd_c4pT41N"),
+        (b"wriker", b"number_one"),
+        (b"data", b"sp0t_the_cat"),
+    ];
+
+    // the database and server are shared by every handler, the database implements `Database`
+    // through the `Arc<Mutex<_>>` so users can be registered through any handle
+    let mut database: Arc<Mutex<HashMapDatabase>> = Arc::default();
+    let server = Arc::new(Mutex::new(Server::new(OsRng)?));
+
+    // weak parameters to keep the example fast, do not use them
+    let params = Params::new(8, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    for (username, password) in USERS {
+        let mut client = Client::new(OsRng);
+        if let ClientMessage::Registration {
+            username,
+            salt,
+            params,
+            verifier,
+        } = client.register_alloc(username, password, params, Scrypt)?
+        {
+            database.store_verifier(username, salt, None, verifier, params);
+        }
+    }
+
+    // one handler per client, as a multi-task server would spawn one task per connection
+    let handlers: Vec<_> = USERS
+        .into_iter()
+        .map(|(username, password)| {
+            let database = Arc::clone(&database);
+            let server = Arc::clone(&server);
+            thread::spawn(move || handshake(&server, &database, username, password))
+        })
+        .collect();
+
+    for (handler, (username, _)) in handlers.into_iter().zip(USERS) {
+        let agreed = handler.join().expect("handler panicked")?;
+        println!(
+            "{}: keys agreed = {agreed}",
+            String::from_utf8_lossy(username)
+        );
+        assert!(agreed);
+    }
+
+    Ok(())
+}
+
+/// run a handshake between a new client and the shared server, returns whether the two sides
+/// derived the same key
+fn handshake(
+    server: &Mutex<Server>,
+    database: &Arc<Mutex<HashMapDatabase>>,
+    username: &'static [u8],
+    password: &'static [u8],
+) -> Result<bool> {
+    let mut client = Client::new(OsRng);
+
+    // ===== SSID Establishment =====
+    // the server is only locked to draw its nonce, every later step owns its state
+    let (server, server_message) = server.lock().unwrap().begin()?;
+    let (client, client_message) = client.begin()?;
+
+    let ClientMessage::Nonce(client_nonce) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let server = server.agree_ssid(client_nonce);
+    let ServerMessage::Nonce(server_nonce) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let client = client.agree_ssid(server_nonce);
+
+    // ===== Augmentation Layer =====
+    let (client, client_message) = client.start_augmentation(username, password);
+    let ClientMessage::Username { username, ssid_tag } = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    // the lookup locks the shared database for the duration of the call
+    let (server, server_message) =
+        server.generate_client_info(username, ssid_tag, database, OsRng)?;
+
+    let ServerMessage::AugmentationInfo {
+        x_pub,
+        salt,
+        pbkdf_params,
+        ..
+    } = server_message
+    else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let params = {
+        let log_n = pbkdf_params.get_str("ln").unwrap().parse().unwrap();
+        let r = pbkdf_params.get_str("r").unwrap().parse().unwrap();
+        let p = pbkdf_params.get_str("p").unwrap().parse().unwrap();
+        Params::new(log_n, r, p, Params::RECOMMENDED_LEN).unwrap()
+    };
+    let client = client.generate_cpace_alloc(x_pub.into(), &salt, params, Scrypt)?;
+
+    // ===== CPace substep =====
+    let (server, server_message) = server.generate_public_key(CI)?;
+    let (client, client_message) = client.generate_public_key(CI, &mut OsRng)?;
+
+    let ServerMessage::PublicKey(server_pubkey) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let (client, client_authenticator) = client.receive_server_pubkey(server_pubkey)?;
+    let ClientMessage::PublicKey(client_pubkey) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let server = server.receive_client_pubkey(client_pubkey)?;
+
+    // ===== Explicit Mutual Authentication =====
+    let ClientMessage::Authenticator(client_authenticator) = client_authenticator else {
+        panic!("Received invalid client message {:?}", client_authenticator);
+    };
+    let (server_key, server_message) = server.receive_client_authenticator(client_authenticator)?;
+    let ServerMessage::Authenticator(server_authenticator) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let client_key = client.receive_server_authenticator(server_authenticator)?;
+
+    Ok(client_key.ct_eq(&server_key))
+}
@@ -1,14 +1,15 @@
 use crate::{
     errors::{Error, Result},
     utils::{
-        H0, compute_authenticator_messages, compute_first_session_key, compute_session_key,
-        compute_ssid, generate_keypair, generate_nonce, scalar_from_hash,
+        H0, HashScheme, LeU32HashScheme, client_prs, compute_authenticator_messages_with_scheme,
+        compute_first_session_key_with_scheme, compute_session_key_with_scheme, compute_ssid_tag,
+        compute_ssid_with_scheme, generate_keypair_with_scheme, generate_nonce, generate_salt,
+        scalar_from_hash, verifier_point_from_hash,
     },
 };
 
-use crate::constants::MIN_SSID_LEN;
+use crate::Ssid;
 use core::marker::PhantomData;
-use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
 use curve25519_dalek::traits::IsIdentity;
 use curve25519_dalek::{
     digest::consts::U64,
@@ -21,11 +22,23 @@ use rand_core::{TryCryptoRng, TryRngCore};
 use subtle::ConstantTimeEq;
 
 #[cfg(feature = "strong_aucpace")]
-use crate::utils::H1;
+use crate::utils::{H1, blind_salt, generate_blinding_scalar, invert_blinding, ristretto_cofactor};
+
+#[cfg(feature = "strong_aucpace")]
+use secret_utils::wrappers::SecretScalar;
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "alloc")]
+use secret_utils::Zeroizing;
+
+#[cfg(feature = "rayon")]
+extern crate std;
+
+#[cfg(feature = "rayon")]
+use secret_utils::wrappers::SecretString;
+
 #[cfg(feature = "serde")]
 use crate::utils::{serde_paramsstring, serde_saltstring};
 
@@ -33,22 +46,25 @@ use crate::utils::{serde_paramsstring, serde_saltstring};
 use serde::{Deserialize, Serialize};
 
 /// Implementation of the client side of the `AuCPace` protocol
-pub struct AuCPaceClient<D, H, CSPRNG, const K1: usize>
+pub struct AuCPaceClient<D, H, CSPRNG, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
     H: PasswordHasher,
     CSPRNG: TryRngCore + TryCryptoRng,
+    S: HashScheme,
 {
     rng: CSPRNG,
     d: PhantomData<D>,
     h: PhantomData<H>,
+    scheme: PhantomData<S>,
 }
 
-impl<D, H, CSPRNG, const K1: usize> AuCPaceClient<D, H, CSPRNG, K1>
+impl<D, H, CSPRNG, const K1: usize, S> AuCPaceClient<D, H, CSPRNG, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
     H: PasswordHasher,
     CSPRNG: TryRngCore + TryCryptoRng,
+    S: HashScheme,
 {
     /// Create a new client
     pub const fn new(rng: CSPRNG) -> Self {
@@ -56,19 +72,23 @@ where
             rng,
             d: PhantomData,
             h: PhantomData,
+            scheme: PhantomData,
         }
     }
 
     /// Create a new client in the SSID agreement phase
     ///
     /// # Return:
-    /// ([`next_step`](AuCPaceClientSsidEstablish<D, H, K1>), [`message`](ClientMessage::Nonce))
+    /// ([`next_step`](AuCPaceClientSsidEstablish<D, H, K1, S>), [`message`](ClientMessage::Nonce))
     /// - [`next_step`](AuCPaceClientSsidEstablish): the client in the SSID establishment stage
     /// - [`message`](ClientMessage::Nonce): the message to send to the server
     ///
     pub fn begin(
         &mut self,
-    ) -> Result<(AuCPaceClientSsidEstablish<D, H, K1>, ClientMessage<'_, K1>)> {
+    ) -> Result<(
+        AuCPaceClientSsidEstablish<D, H, K1, S>,
+        ClientMessage<'_, K1>,
+    )> {
         let next_step = AuCPaceClientSsidEstablish::new(&mut self.rng)?;
         let message = ClientMessage::Nonce(next_step.nonce);
 
@@ -81,24 +101,15 @@ where
     /// - `ssid`: Some data to be hashed and act as the sub-session ID
     ///
     /// # Return:
-    /// - Ok([`next_step`](AuCPaceClientSsidEstablish)): the server in the SSID establishment stage
-    /// - Err([`Error::InsecureSsid`](Error::InsecureSsid)): the SSID provided was not long enough to be secure
+    /// [`next_step`](AuCPaceClientPreAug): the client in the pre-augmentation stage
     ///
-    pub fn begin_prestablished_ssid<S>(&mut self, ssid: S) -> Result<AuCPaceClientPreAug<D, H, K1>>
-    where
-        S: AsRef<[u8]>,
-    {
-        // if the SSID isn't long enough return an error
-        if ssid.as_ref().len() < MIN_SSID_LEN {
-            return Err(Error::InsecureSsid);
-        }
-
+    #[must_use]
+    pub fn begin_prestablished_ssid(&mut self, ssid: Ssid<'_>) -> AuCPaceClientPreAug<D, H, K1, S> {
         // hash the SSID and begin the next step
-        let mut hasher: D = H0();
+        let mut hasher: D = H0::<D, S>();
         hasher.update(ssid);
         let ssid_hash = hasher.finalize();
-        let next_step = AuCPaceClientPreAug::new(ssid_hash);
-        Ok(next_step)
+        AuCPaceClientPreAug::new(ssid_hash)
     }
 
     /// Register a username/password
@@ -129,11 +140,7 @@ where
     where
         P: AsRef<[u8]>,
     {
-        let mut bytes = [0u8; Salt::RECOMMENDED_LENGTH];
-        self.rng
-            .try_fill_bytes(&mut bytes)
-            .map_err(|_| Error::Rng)?;
-        let salt_string = SaltString::encode_b64(&bytes).expect("Salt length invariant broken.");
+        let salt_string = generate_salt(&mut self.rng)?;
 
         // compute the verifier W
         let pw_hash = hash_password::<&[u8], P, &SaltString, H, BUFSIZ>(
@@ -144,9 +151,7 @@ where
             &hasher,
         )?;
 
-        let cofactor = Scalar::ONE;
-        let w = scalar_from_hash(&pw_hash)?;
-        let verifier = RISTRETTO_BASEPOINT_POINT * (w * cofactor);
+        let verifier = verifier_point_from_hash(&pw_hash)?;
 
         // attempt to convert the parameters to a ParamsString
         let params_string = params.try_into().map_err(Error::PasswordHashing)?;
@@ -200,9 +205,7 @@ where
             params.clone(),
             &hasher,
         )?;
-        let cofactor = Scalar::ONE;
-        let w = scalar_from_hash(&pw_hash)?;
-        let verifier = RISTRETTO_BASEPOINT_POINT * (w * cofactor);
+        let verifier = verifier_point_from_hash(&pw_hash)?;
 
         // attempt to convert the parameters to a ParamsString
         let params_string = params.try_into().map_err(Error::PasswordHashing)?;
@@ -241,19 +244,13 @@ where
     where
         P: AsRef<[u8]>,
     {
-        // adapted from SaltString::generate, which we cannot use due to curve25519 versions of rand_core
-        let mut bytes = [0u8; Salt::RECOMMENDED_LENGTH];
-        self.rng
-            .try_fill_bytes(&mut bytes)
-            .map_err(|_| Error::Rng)?;
-        let salt_string = SaltString::encode_b64(&bytes).expect("Salt length invariant broken.");
+        // SaltString::generate cannot be used due to curve25519 versions of rand_core
+        let salt_string = generate_salt(&mut self.rng)?;
 
         // compute the verifier W
         let pw_hash =
             hash_password_alloc(username, password, &salt_string, params.clone(), &hasher)?;
-        let cofactor = Scalar::ONE;
-        let w = scalar_from_hash(&pw_hash)?;
-        let verifier = RISTRETTO_BASEPOINT_POINT * (w * cofactor);
+        let verifier = verifier_point_from_hash(&pw_hash)?;
 
         // attempt to convert the parameters to a ParamsString
         let params_string = params.try_into().map_err(Error::PasswordHashing)?;
@@ -309,9 +306,7 @@ where
             params.clone(),
             &hasher,
         )?;
-        let cofactor = Scalar::ONE;
-        let w = scalar_from_hash(&pw_hash)?;
-        let verifier = RISTRETTO_BASEPOINT_POINT * (w * cofactor);
+        let verifier = verifier_point_from_hash(&pw_hash)?;
 
         // attempt to convert the parameters to a ParamsString
         let params_string = params.try_into().map_err(Error::PasswordHashing)?;
@@ -335,18 +330,18 @@ where
         let mut rand_bytes = [0u8; 64];
         rng.try_fill_bytes(&mut rand_bytes)
             .map_err(|_| Error::Rng)?;
-        let mut hasher_q: D = H1();
-        hasher_q.update(&rand_bytes);
+        let mut hasher_q: D = H1::<D, S>();
+        hasher_q.update(rand_bytes);
         let q = Scalar::from_hash(hasher_q);
 
         // compute z
-        let mut hasher: D = H1();
+        let mut hasher: D = H1::<D, S>();
         hasher.update(user);
         hasher.update(pass);
         let z = RistrettoPoint::from_hash(hasher);
 
         // compute the salt value
-        let cofactor = Scalar::ONE;
+        let cofactor = ristretto_cofactor();
         let salt_point = z * (q * cofactor);
         let salt = salt_point.compress().to_bytes();
         let salt_string = SaltString::encode_b64(&salt).map_err(Error::PasswordHashing)?;
@@ -356,20 +351,23 @@ where
 }
 
 /// Client in the SSID agreement phase
-pub struct AuCPaceClientSsidEstablish<D, H, const K1: usize>
+pub struct AuCPaceClientSsidEstablish<D, H, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
     H: PasswordHasher,
+    S: HashScheme,
 {
     nonce: [u8; K1],
     d: PhantomData<D>,
     h: PhantomData<H>,
+    scheme: PhantomData<S>,
 }
 
-impl<D, H, const K1: usize> AuCPaceClientSsidEstablish<D, H, K1>
+impl<D, H, const K1: usize, S> AuCPaceClientSsidEstablish<D, H, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
     H: PasswordHasher,
+    S: HashScheme,
 {
     fn new<CSPRNG>(rng: &mut CSPRNG) -> Result<Self>
     where
@@ -379,6 +377,7 @@ where
             nonce: generate_nonce(rng)?,
             d: PhantomData,
             h: PhantomData,
+            scheme: PhantomData,
         })
     }
 
@@ -391,31 +390,35 @@ where
     /// [`next_step`](AuCPaceClientPreAug): the client in the pre-augmentation stage
     ///
     #[must_use]
-    pub fn agree_ssid(self, server_nonce: [u8; K1]) -> AuCPaceClientPreAug<D, H, K1> {
-        let ssid = compute_ssid::<D, K1>(server_nonce, self.nonce);
+    pub fn agree_ssid(self, server_nonce: [u8; K1]) -> AuCPaceClientPreAug<D, H, K1, S> {
+        let ssid = compute_ssid_with_scheme::<D, S, K1>(server_nonce, self.nonce);
         AuCPaceClientPreAug::new(ssid)
     }
 }
 
 /// Client in the pre-augmentation phase
-pub struct AuCPaceClientPreAug<D, H, const K1: usize>
+pub struct AuCPaceClientPreAug<D, H, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
     H: PasswordHasher,
+    S: HashScheme,
 {
     ssid: Output<D>,
     h: PhantomData<H>,
+    scheme: PhantomData<S>,
 }
 
-impl<D, H, const K1: usize> AuCPaceClientPreAug<D, H, K1>
+impl<D, H, const K1: usize, S> AuCPaceClientPreAug<D, H, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
     H: PasswordHasher,
+    S: HashScheme,
 {
     const fn new(ssid: Output<D>) -> Self {
         Self {
             ssid,
             h: PhantomData,
+            scheme: PhantomData,
         }
     }
 
@@ -427,16 +430,21 @@ where
     /// # Return:
     /// ([`next_step`](AuCPaceClientAugLayer), [`message`](ClientMessage::Username))
     /// - [`next_step`](AuCPaceClientAugLayer): the client in the augmentation layer
-    /// - [`message`](ClientMessage::Username): the message to send to the server
+    /// - [`message`](ClientMessage::Username): the message to send to the server, with the
+    ///   tag confirming the client's SSID
     ///
     #[must_use]
-    pub const fn start_augmentation<'a>(
+    pub fn start_augmentation<'a>(
         self,
         username: &'a [u8],
         password: &'a [u8],
-    ) -> (AuCPaceClientAugLayer<'a, D, H, K1>, ClientMessage<'a, K1>) {
+    ) -> (
+        AuCPaceClientAugLayer<'a, D, H, K1, S>,
+        ClientMessage<'a, K1>,
+    ) {
+        let ssid_tag: [u8; 64] = compute_ssid_tag::<D, S>(self.ssid).into();
         let next_step = AuCPaceClientAugLayer::new(self.ssid, username, password);
-        let message = ClientMessage::Username(username);
+        let message = ClientMessage::Username { username, ssid_tag };
 
         (next_step, message)
     }
@@ -459,56 +467,53 @@ where
         password: &'a [u8],
         rng: &mut CSPRNG,
     ) -> Result<(
-        StrongAuCPaceClientAugLayer<'a, D, H, K1>,
+        StrongAuCPaceClientAugLayer<'a, D, H, K1, S>,
         ClientMessage<'a, K1>,
     )>
     where
         CSPRNG: TryRngCore + TryCryptoRng,
     {
         // compute the blinding value and blind the hash of the username and password
-        // ensuring that it is non-zero as required by `invert`
-        let blinding_value = loop {
-            let mut rand_bytes = [0u8; 64];
-            rng.try_fill_bytes(&mut rand_bytes)
-                .map_err(|_| Error::Rng)?;
-            let mut hasher_bv: D = H1();
-            hasher_bv.update(&rand_bytes);
-            let val = Scalar::from_hash(hasher_bv);
-            if val != Scalar::ZERO {
-                break val;
-            }
-        };
-        let mut hasher: D = H1();
+        // the blinding value is non-zero as required by `invert`
+        let blinding_value = generate_blinding_scalar(rng)?;
+        let mut hasher: D = H1::<D, S>();
         hasher.update(username);
         hasher.update(password);
         let z = RistrettoPoint::from_hash(hasher);
-        let cofactor = Scalar::ONE;
-        let blinded = z * (blinding_value * cofactor);
+        let blinded = blind_salt(z, blinding_value.expose());
 
+        let ssid_tag: [u8; 64] = compute_ssid_tag::<D, S>(self.ssid).into();
         let next_step =
             StrongAuCPaceClientAugLayer::new(self.ssid, username, password, blinding_value);
-        let message = ClientMessage::StrongUsername { username, blinded };
+        let message = ClientMessage::StrongUsername {
+            username,
+            ssid_tag,
+            blinded,
+        };
 
         Ok((next_step, message))
     }
 }
 
 /// Client in the augmentation layer
-pub struct AuCPaceClientAugLayer<'a, D, H, const K1: usize>
+pub struct AuCPaceClientAugLayer<'a, D, H, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
     H: PasswordHasher,
+    S: HashScheme,
 {
     ssid: Output<D>,
     username: &'a [u8],
     password: &'a [u8],
     h: PhantomData<H>,
+    scheme: PhantomData<S>,
 }
 
-impl<'a, D, H, const K1: usize> AuCPaceClientAugLayer<'a, D, H, K1>
+impl<'a, D, H, const K1: usize, S> AuCPaceClientAugLayer<'a, D, H, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
     H: PasswordHasher,
+    S: HashScheme,
 {
     const fn new(ssid: Output<D>, username: &'a [u8], password: &'a [u8]) -> Self {
         Self {
@@ -516,6 +521,7 @@ where
             username,
             password,
             h: PhantomData,
+            scheme: PhantomData,
         }
     }
 
@@ -541,23 +547,22 @@ where
     /// - Err([`Error::PasswordHashing`](Error::PasswordHashing) | [`Error::HashEmpty`](Error::HashEmpty) | [`Error::HashSizeInvalid`](Error::HashSizeInvalid)):
     ///   one of the three error variants that can result from the password hashing process
     ///
-    pub fn generate_cpace<'salt, S, const BUFSIZ: usize>(
+    pub fn generate_cpace<'salt, SALT, const BUFSIZ: usize>(
         self,
         x_pub: RistrettoPoint,
-        salt: S,
+        salt: SALT,
         params: H::Params,
         hasher: H,
-    ) -> Result<AuCPaceClientCPaceSubstep<D, K1>>
+    ) -> Result<AuCPaceClientCPaceSubstep<D, K1, S>>
     where
-        S: Into<Salt<'salt>>,
+        SALT: Into<Salt<'salt>>,
     {
         // check for the identity point
         if x_pub.is_identity() {
             return Err(Error::IllegalPointError);
         }
 
-        let cofactor = Scalar::ONE;
-        let pw_hash = hash_password::<&[u8], &[u8], S, H, BUFSIZ>(
+        let pw_hash = hash_password::<&[u8], &[u8], SALT, H, BUFSIZ>(
             self.username,
             self.password,
             salt,
@@ -566,7 +571,7 @@ where
         )?;
         let w = scalar_from_hash(&pw_hash)?;
 
-        let prs = (x_pub * (w * cofactor)).compress().to_bytes();
+        let prs = client_prs(&x_pub, &w);
 
         Ok(AuCPaceClientCPaceSubstep::new(self.ssid, prs))
     }
@@ -589,26 +594,25 @@ where
     ///   one of the three error variants that can result from the password hashing process
     ///
     #[cfg(feature = "alloc")]
-    pub fn generate_cpace_alloc<'salt, S>(
+    pub fn generate_cpace_alloc<'salt, SALT>(
         self,
         x_pub: RistrettoPoint,
-        salt: S,
+        salt: SALT,
         params: H::Params,
         hasher: H,
-    ) -> Result<AuCPaceClientCPaceSubstep<D, K1>>
+    ) -> Result<AuCPaceClientCPaceSubstep<D, K1, S>>
     where
-        S: Into<Salt<'a>>,
+        SALT: Into<Salt<'a>>,
     {
         // check for the identity point
         if x_pub.is_identity() {
             return Err(Error::IllegalPointError);
         }
 
-        let cofactor = Scalar::ONE;
         let pw_hash = hash_password_alloc(self.username, self.password, salt, params, &hasher)?;
         let w = scalar_from_hash(&pw_hash)?;
 
-        let prs = (x_pub * (w * cofactor)).compress().to_bytes();
+        let prs = client_prs(&x_pub, &w);
 
         Ok(AuCPaceClientCPaceSubstep::new(self.ssid, prs))
     }
@@ -616,29 +620,32 @@ where
 
 /// Client in the augmentation layer - strong version
 #[cfg(feature = "strong_aucpace")]
-pub struct StrongAuCPaceClientAugLayer<'a, D, H, const K1: usize>
+pub struct StrongAuCPaceClientAugLayer<'a, D, H, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
     H: PasswordHasher,
+    S: HashScheme,
 {
     ssid: Output<D>,
     username: &'a [u8],
     password: &'a [u8],
-    blinding_value: Scalar,
+    blinding_value: SecretScalar,
     h: PhantomData<H>,
+    scheme: PhantomData<S>,
 }
 
 #[cfg(feature = "strong_aucpace")]
-impl<'a, D, H, const K1: usize> StrongAuCPaceClientAugLayer<'a, D, H, K1>
+impl<'a, D, H, const K1: usize, S> StrongAuCPaceClientAugLayer<'a, D, H, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
     H: PasswordHasher,
+    S: HashScheme,
 {
     const fn new(
         ssid: Output<D>,
         username: &'a [u8],
         password: &'a [u8],
-        blinding_value: Scalar,
+        blinding_value: SecretScalar,
     ) -> Self {
         Self {
             ssid,
@@ -646,6 +653,7 @@ where
             password,
             blinding_value,
             h: PhantomData,
+            scheme: PhantomData,
         }
     }
 
@@ -677,19 +685,20 @@ where
         blinded_salt: RistrettoPoint,
         params: H::Params,
         hasher: H,
-    ) -> Result<AuCPaceClientCPaceSubstep<D, K1>> {
+    ) -> Result<AuCPaceClientCPaceSubstep<D, K1, S>> {
         // check for the identity point
         if x_pub.is_identity() {
             return Err(Error::IllegalPointError);
         }
 
         // first recover the salt
-        let cofactor = Scalar::ONE;
+        let cofactor = ristretto_cofactor();
 
         // this is a tad funky, in the paper they write (1/(r * cj^2))*cj
         // I have interpreted this as the multiplicative inverse of (r * cj^2)
         // then multiplied by cj again.
-        let exponent = (self.blinding_value * cofactor * cofactor).invert() * cofactor;
+        let exponent =
+            invert_blinding(&(self.blinding_value.expose() * cofactor * cofactor))? * cofactor;
         let salt = (blinded_salt * exponent).compress().to_bytes();
         let salt_string = SaltString::encode_b64(&salt).map_err(Error::PasswordHashing)?;
 
@@ -702,7 +711,7 @@ where
             &hasher,
         )?;
         let w = scalar_from_hash(&pw_hash)?;
-        let prs = (x_pub * (w * cofactor)).compress().to_bytes();
+        let prs = client_prs(&x_pub, &w);
 
         Ok(AuCPaceClientCPaceSubstep::new(self.ssid, prs))
     }
@@ -732,19 +741,20 @@ where
         blinded_salt: RistrettoPoint,
         params: H::Params,
         hasher: H,
-    ) -> Result<AuCPaceClientCPaceSubstep<D, K1>> {
+    ) -> Result<AuCPaceClientCPaceSubstep<D, K1, S>> {
         // check for the identity point
         if x_pub.is_identity() {
             return Err(Error::IllegalPointError);
         }
 
         // first recover the salt
-        let cofactor = Scalar::ONE;
+        let cofactor = ristretto_cofactor();
 
         // this is a tad funky, in the paper they write (1/(r * cj^2))*cj
         // I have interpreted this as the multiplicative inverse of (r * cj^2)
         // then multiplied by cj again.
-        let exponent = (self.blinding_value * cofactor * cofactor).invert() * cofactor;
+        let exponent =
+            invert_blinding(&(self.blinding_value.expose() * cofactor * cofactor))? * cofactor;
 
         // check if the salt point is the neutral element
         let salt_point = blinded_salt * exponent;
@@ -763,7 +773,7 @@ where
             &hasher,
         )?;
         let w = scalar_from_hash(&pw_hash)?;
-        let prs = (x_pub * (w * cofactor)).compress().to_bytes();
+        let prs = client_prs(&x_pub, &w);
 
         Ok(AuCPaceClientCPaceSubstep::new(self.ssid, prs))
     }
@@ -771,21 +781,28 @@ where
 
 /// Client in the `CPace` substep
 #[derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
-pub struct AuCPaceClientCPaceSubstep<D, const K1: usize>
+pub struct AuCPaceClientCPaceSubstep<D, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     #[zeroize(skip)]
     ssid: Output<D>,
     prs: [u8; 32],
+    scheme: PhantomData<S>,
 }
 
-impl<D, const K1: usize> AuCPaceClientCPaceSubstep<D, K1>
+impl<D, const K1: usize, S> AuCPaceClientCPaceSubstep<D, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     const fn new(ssid: Output<D>, prs: [u8; 32]) -> Self {
-        Self { ssid, prs }
+        Self {
+            ssid,
+            prs,
+            scheme: PhantomData,
+        }
     }
 
     /// Generate a public key
@@ -793,8 +810,8 @@ where
     ///
     /// # Arguments:
     /// - `channel_identifier` - `CI` from the protocol definition, in the context of TCP/IP this
-    ///     is usually some combination of the server and client's IP address and TCP port numbers.
-    ///     It's purpose is to prevent relay attacks.
+    ///   is usually some combination of the server and client's IP address and TCP port numbers.
+    ///   It's purpose is to prevent relay attacks.
     /// - `rng` - the CSPRNG used when generating the public/private keypair
     ///
     /// # Return:
@@ -807,15 +824,19 @@ where
         channel_identifier: CI,
         rng: &mut CSPRNG,
     ) -> Result<(
-        AuCPaceClientRecvServerKey<D, K1>,
+        AuCPaceClientRecvServerKey<D, K1, S>,
         ClientMessage<'static, K1>,
     )>
     where
         CI: AsRef<[u8]>,
         CSPRNG: TryRngCore + TryCryptoRng,
     {
-        let (priv_key, pub_key) =
-            generate_keypair::<D, CSPRNG, CI>(rng, self.ssid, self.prs, channel_identifier)?;
+        let (priv_key, pub_key) = generate_keypair_with_scheme::<D, S, CSPRNG, CI>(
+            rng,
+            self.ssid,
+            &self.prs,
+            channel_identifier,
+        )?;
 
         let next_step = AuCPaceClientRecvServerKey::new(self.ssid, priv_key);
         let message = ClientMessage::PublicKey(pub_key);
@@ -826,21 +847,28 @@ where
 
 /// Client waiting to receive the server's public key
 #[derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
-pub struct AuCPaceClientRecvServerKey<D, const K1: usize>
+pub struct AuCPaceClientRecvServerKey<D, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     #[zeroize(skip)]
     ssid: Output<D>,
     priv_key: Scalar,
+    scheme: PhantomData<S>,
 }
 
-impl<D, const K1: usize> AuCPaceClientRecvServerKey<D, K1>
+impl<D, const K1: usize, S> AuCPaceClientRecvServerKey<D, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     const fn new(ssid: Output<D>, priv_key: Scalar) -> Self {
-        Self { ssid, priv_key }
+        Self {
+            ssid,
+            priv_key,
+            scheme: PhantomData,
+        }
     }
 
     /// Receive the server's public key
@@ -857,18 +885,23 @@ where
     pub fn receive_server_pubkey(
         self,
         server_pubkey: RistrettoPoint,
-    ) -> Result<(AuCPaceClientExpMutAuth<D, K1>, ClientMessage<'static, K1>)> {
+    ) -> Result<(
+        AuCPaceClientExpMutAuth<D, K1, S>,
+        ClientMessage<'static, K1>,
+    )> {
         if server_pubkey.is_identity() {
             return Err(Error::IllegalPointError);
         }
 
-        let sk1 = compute_first_session_key::<D>(self.ssid, self.priv_key, server_pubkey);
-        let (ta, tb) = compute_authenticator_messages::<D>(self.ssid, sk1);
+        let sk1 =
+            compute_first_session_key_with_scheme::<D, S>(self.ssid, self.priv_key, server_pubkey);
+        let (ta, tb) = compute_authenticator_messages_with_scheme::<D, S>(self.ssid, sk1);
         let next_step = AuCPaceClientExpMutAuth::new(self.ssid, sk1, ta);
+        // unreachable: `D::OutputSize` is `U64`, so the slice is always 64 bytes long
         let tb_arr = tb
             .as_slice()
             .try_into()
-            .map_err(|_| Error::HashSizeInvalid)?;
+            .map_err(|_| Error::InternalInvariant)?;
         let message = ClientMessage::Authenticator(tb_arr);
         Ok((next_step, message))
     }
@@ -891,18 +924,22 @@ where
             return Err(Error::IllegalPointError);
         }
 
-        let sk1 = compute_first_session_key::<D>(self.ssid, self.priv_key, server_pubkey);
+        let sk1 =
+            compute_first_session_key_with_scheme::<D, S>(self.ssid, self.priv_key, server_pubkey);
         Ok(secret_utils::wrappers::SecretKey::from(
-            compute_session_key::<D>(self.ssid, sk1).as_slice().to_vec(),
+            compute_session_key_with_scheme::<D, S>(self.ssid, sk1)
+                .as_slice()
+                .to_vec(),
         ))
     }
 }
 
 /// Client in the Explicit Mutual Authenticaton phase
 #[derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
-pub struct AuCPaceClientExpMutAuth<D, const K1: usize>
+pub struct AuCPaceClientExpMutAuth<D, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     #[zeroize(skip)]
     ssid: Output<D>,
@@ -910,17 +947,20 @@ where
     sk1: Output<D>,
     #[zeroize(skip)]
     server_authenticator: Output<D>,
+    scheme: PhantomData<S>,
 }
 
-impl<D, const K1: usize> AuCPaceClientExpMutAuth<D, K1>
+impl<D, const K1: usize, S> AuCPaceClientExpMutAuth<D, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     const fn new(ssid: Output<D>, sk1: Output<D>, server_authenticator: Output<D>) -> Self {
         Self {
             ssid,
             sk1,
             server_authenticator,
+            scheme: PhantomData,
         }
     }
 
@@ -933,8 +973,9 @@ where
     /// # Return:
     /// either:
     /// - Ok(`sk`): the session key reached by the `AuCPace` protocol
-    /// - Err([`Error::MutualAuthFail`](Error::MutualAuthFail)): an error if the authenticator we computed doesn't match
-    ///     the server's authenticator, compared in constant time.
+    /// - Err([`Error::KeyConfirmationFailed`](Error::KeyConfirmationFailed)): an error if the
+    ///   authenticator we computed doesn't match the server's authenticator, compared in
+    ///   constant time.
     ///
     pub fn receive_server_authenticator(
         self,
@@ -946,12 +987,12 @@ where
             .into()
         {
             Ok(secret_utils::wrappers::SecretKey::from(
-                compute_session_key::<D>(self.ssid, self.sk1)
+                compute_session_key_with_scheme::<D, S>(self.ssid, self.sk1)
                     .as_slice()
                     .to_vec(),
             ))
         } else {
-            Err(Error::MutualAuthFail)
+            Err(Error::KeyConfirmationFailed)
         }
     }
 }
@@ -984,11 +1025,9 @@ where
     buf[u] = b':';
     buf[(u + 1)..=(u + p)].copy_from_slice(pass);
 
-    let hash = hasher
+    hasher
         .hash_password_customized(&buf[0..=(u + p)], None, None, params, salt)
-        .map_err(Error::PasswordHashing);
-
-    hash
+        .map_err(Error::PasswordHashing)
 }
 
 /// Hash a username and password with the given password hasher
@@ -1009,8 +1048,8 @@ where
     let user = username.as_ref();
     let pass = password.as_ref();
 
-    // hash "{username}:{password}"
-    let mut v = alloc::vec::Vec::with_capacity(user.len() + pass.len() + 1);
+    // hash "{username}:{password}", zeroizing the buffer once it is hashed
+    let mut v = Zeroizing::new(alloc::vec::Vec::with_capacity(user.len() + pass.len() + 1));
     v.extend_from_slice(user);
     v.push(b':');
     v.extend_from_slice(pass);
@@ -1020,6 +1059,267 @@ where
         .map_err(Error::PasswordHashing)
 }
 
+/// Enrol a user with `Argon2id`, returning everything [`Database::store_verifier`] needs
+///
+/// A fresh salt is generated with [`OsRng`](rand::rngs::OsRng), then the verifier `W` is
+/// computed exactly as [`AuCPaceClient::register_alloc`] does with [`argon2::Argon2`] as the
+/// hasher, so a client using `Argon2` can later authenticate against the stored values.
+/// Empty passwords are rejected, use [`Argon2Enrollment`] to permit them.
+///
+/// # Arguments:
+/// - `password` - the password for the user
+/// - `username` - the username to register with
+/// - `params` - the `Argon2` parameters to hash with
+///
+/// # Return:
+/// - Ok((`salt`, `verifier`, `params`)): the values to store for the user
+/// - Err([`Error::EmptyPassword`](Error::EmptyPassword)): the password is empty
+/// - Err([`Error::Rng`](Error::Rng)): the OS RNG failed to generate a salt
+/// - Err([`Error::PasswordHashing`](Error::PasswordHashing) | [`Error::HashEmpty`](Error::HashEmpty) | [`Error::HashSizeInvalid`](Error::HashSizeInvalid)):
+///   one of the three error variants that can result from the password hashing process
+///
+/// [`Database::store_verifier`]: crate::Database::store_verifier
+#[cfg(all(feature = "argon2", feature = "getrandom"))]
+pub fn enroll_argon2(
+    password: &[u8],
+    username: &[u8],
+    params: argon2::Params,
+) -> Result<(SaltString, RistrettoPoint, ParamsString)> {
+    Argon2Enrollment::new(params).enroll(password, username)
+}
+
+/// Configurable enrolment of users with `Argon2id`, see [`enroll_argon2`]
+///
+/// By default empty passwords are rejected with [`Error::EmptyPassword`], deployments which
+/// permit them can opt in with [`allow_empty_password`](Self::allow_empty_password). With
+/// the `argon2-pepper` feature a server side secret can be mixed into every password with
+/// `pepper`.
+#[cfg(all(feature = "argon2", feature = "getrandom"))]
+#[derive(Debug)]
+pub struct Argon2Enrollment {
+    params: argon2::Params,
+    allow_empty_password: bool,
+    #[cfg(feature = "argon2-pepper")]
+    pepper: Option<secret_utils::wrappers::SecretKey>,
+}
+
+#[cfg(all(feature = "argon2", feature = "getrandom"))]
+impl Clone for Argon2Enrollment {
+    fn clone(&self) -> Self {
+        Self {
+            params: self.params.clone(),
+            allow_empty_password: self.allow_empty_password,
+            #[cfg(feature = "argon2-pepper")]
+            pepper: self
+                .pepper
+                .as_ref()
+                .map(|pepper| secret_utils::wrappers::SecretKey::new(pepper.expose().to_vec())),
+        }
+    }
+}
+
+#[cfg(all(feature = "argon2", feature = "getrandom"))]
+impl Argon2Enrollment {
+    /// Create an enrolment helper hashing with the given `Argon2` parameters
+    pub const fn new(params: argon2::Params) -> Self {
+        Self {
+            params,
+            allow_empty_password: false,
+            #[cfg(feature = "argon2-pepper")]
+            pepper: None,
+        }
+    }
+
+    /// Create an enrolment helper from `Argon2` parameters encoded as a [`ParamsString`]
+    ///
+    /// This lets an operator pick the parameters per user, e.g. stronger ones for admins, in
+    /// the same `m=..,t=..,p=..` form that [`enroll`](Self::enroll) returns to be stored and
+    /// that the server then sends to the client in the augmentation info.
+    ///
+    /// # Return:
+    /// - Ok(`enrollment`): the enrolment helper
+    /// - Err([`Error::PasswordHashing`](Error::PasswordHashing)): one of `m`, `t` or `p` is
+    ///   missing or is not accepted by `Argon2`
+    ///
+    pub fn from_params_string(params: &ParamsString) -> Result<Self> {
+        let decimal = |name| {
+            params.get_decimal(name).ok_or(Error::PasswordHashing(
+                password_hash::Error::ParamNameInvalid,
+            ))
+        };
+        let params = argon2::Params::new(decimal("m")?, decimal("t")?, decimal("p")?, None)
+            .map_err(|e| Error::PasswordHashing(e.into()))?;
+        Ok(Self::new(params))
+    }
+
+    /// Set whether empty passwords may be enrolled, they are rejected by default
+    #[must_use]
+    pub const fn allow_empty_password(mut self, allow: bool) -> Self {
+        self.allow_empty_password = allow;
+        self
+    }
+
+    /// Mix a server side secret, a pepper, into every password before it is hashed
+    ///
+    /// The password is replaced with `HMAC-SHA512(pepper, password)`, see [`pepper_password`],
+    /// so that a leak of the verifier database alone is not enough to attack the passwords. The
+    /// pepper must therefore be stored separately from the verifier database, e.g. in a secrets
+    /// manager or HSM. Clients authenticating against a peppered verifier must use the peppered
+    /// password, so this only suits deployments where the pepper is available to the component
+    /// which runs the client side of the protocol.
+    #[cfg(feature = "argon2-pepper")]
+    #[must_use]
+    pub fn pepper(mut self, pepper: secret_utils::wrappers::SecretKey) -> Self {
+        self.pepper = Some(pepper);
+        self
+    }
+
+    /// Enrol a user, returning everything [`Database::store_verifier`] needs
+    ///
+    /// # Arguments:
+    /// - `password` - the password for the user
+    /// - `username` - the username to register with
+    ///
+    /// # Return:
+    /// - Ok((`salt`, `verifier`, `params`)): the values to store for the user
+    /// - Err([`Error::EmptyPassword`](Error::EmptyPassword)): the password is empty and empty
+    ///   passwords have not been allowed
+    /// - Err([`Error::Rng`](Error::Rng)): the OS RNG failed to generate a salt
+    /// - Err([`Error::PasswordHashing`](Error::PasswordHashing) | [`Error::HashEmpty`](Error::HashEmpty) | [`Error::HashSizeInvalid`](Error::HashSizeInvalid)):
+    ///   one of the three error variants that can result from the password hashing process
+    ///
+    /// [`Database::store_verifier`]: crate::Database::store_verifier
+    pub fn enroll(
+        &self,
+        password: &[u8],
+        username: &[u8],
+    ) -> Result<(SaltString, RistrettoPoint, ParamsString)> {
+        if password.is_empty() && !self.allow_empty_password {
+            return Err(Error::EmptyPassword);
+        }
+
+        let salt = generate_salt(&mut rand::rngs::OsRng)?;
+
+        #[cfg(feature = "argon2-pepper")]
+        let peppered = self
+            .pepper
+            .as_ref()
+            .map(|pepper| pepper_password(pepper, password));
+        #[cfg(feature = "argon2-pepper")]
+        let password = peppered.as_ref().map_or(password, |p| p.expose());
+
+        let params_string = ParamsString::try_from(&self.params).map_err(Error::PasswordHashing)?;
+        let pw_hash = hash_password_alloc(
+            username,
+            password,
+            &salt,
+            self.params.clone(),
+            &argon2::Argon2::default(),
+        )?;
+
+        let verifier = verifier_point_from_hash(&pw_hash)?;
+
+        Ok((salt, verifier, params_string))
+    }
+}
+
+/// Mix a pepper into a password, computing `HMAC-SHA512(pepper, password)`
+///
+/// This is the transformation [`Argon2Enrollment::pepper`] applies before hashing, clients
+/// authenticating against a peppered verifier pass the result to the protocol in place of the
+/// password.
+#[cfg(feature = "argon2-pepper")]
+pub fn pepper_password(
+    pepper: &secret_utils::wrappers::SecretKey,
+    password: &[u8],
+) -> secret_utils::wrappers::SecretKey {
+    use hmac::{Hmac, Mac};
+
+    let mut mac = <Hmac<sha2::Sha512> as Mac>::new_from_slice(pepper.expose())
+        .expect("HMAC accepts keys of any length");
+    mac.update(password);
+    secret_utils::wrappers::SecretKey::from(mac.finalize().into_bytes())
+}
+
+/// The values [`enroll_batch`] returns for each user, as [`Database::store_verifier`] takes them
+///
+/// [`Database::store_verifier`]: crate::Database::store_verifier
+#[cfg(feature = "rayon")]
+pub type Enrollment = (
+    alloc::vec::Vec<u8>,
+    SaltString,
+    RistrettoPoint,
+    ParamsString,
+);
+
+/// Enrol many users in parallel, e.g. for a bulk import
+///
+/// Each user is enrolled exactly as [`AuCPaceClient::register_alloc`] would: a fresh salt from
+/// [`OsRng`](rand::rngs::OsRng) and the verifier `W` computed with `hasher` and `params`. The
+/// expensive password hashing runs on the `rayon` thread pool, one user per task.
+///
+/// The `username:password` buffers are zeroized once hashed, and the passwords stay in their
+/// [`SecretString`]s. A panic in `hasher` is caught on the thread it happened on and reported
+/// as an error for that user, its payload is dropped rather than carried to the caller.
+///
+/// # Arguments:
+/// - `entries` - the `(username, password)` of each user
+/// - `params` - the parameters of the PBKDF used
+/// - `hasher` - the hasher to use for hashing the usernames and passwords
+///
+/// # Return:
+/// The result for each entry, in the same order as `entries`, either:
+/// - Ok((`username`, `salt`, `verifier`, `params`)): the values to store for the user
+/// - Err([`Error::Rng`](Error::Rng)): the OS RNG failed to generate a salt
+/// - Err([`Error::PasswordHashing`](Error::PasswordHashing) | [`Error::HashEmpty`](Error::HashEmpty) | [`Error::HashSizeInvalid`](Error::HashSizeInvalid)):
+///   one of the three error variants that can result from the password hashing process, a
+///   panic while hashing is reported as `Error::PasswordHashing(password_hash::Error::Crypto)`
+///
+#[cfg(feature = "rayon")]
+pub fn enroll_batch<H>(
+    entries: &[(alloc::vec::Vec<u8>, SecretString)],
+    params: H::Params,
+    hasher: &H,
+) -> alloc::vec::Vec<Result<Enrollment>>
+where
+    H: PasswordHasher + Sync,
+    H::Params: Sync,
+{
+    use rayon::prelude::*;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    entries
+        .par_iter()
+        .map(|(username, password)| {
+            catch_unwind(AssertUnwindSafe(|| {
+                enroll_one(username, password, params.clone(), hasher)
+            }))
+            .unwrap_or(Err(Error::PasswordHashing(password_hash::Error::Crypto)))
+        })
+        .collect()
+}
+
+/// Enrol a single user for [`enroll_batch`]
+#[cfg(feature = "rayon")]
+fn enroll_one<H>(
+    username: &[u8],
+    password: &SecretString,
+    params: H::Params,
+    hasher: &H,
+) -> Result<Enrollment>
+where
+    H: PasswordHasher,
+{
+    let salt = generate_salt(&mut rand::rngs::OsRng)?;
+    let pw_hash =
+        hash_password_alloc(username, password.as_bytes(), &salt, params.clone(), hasher)?;
+
+    let verifier = verifier_point_from_hash(&pw_hash)?;
+    let params_string = params.try_into().map_err(Error::PasswordHashing)?;
+
+    Ok((username.to_vec(), salt, verifier, params_string))
+}
+
 /// An enum representing the different messages the client can send to the server
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -1028,7 +1328,13 @@ pub enum ClientMessage<'a, const K1: usize> {
     Nonce(#[cfg_attr(feature = "serde", serde(with = "serde_byte_array"))] [u8; K1]),
 
     /// Username - the client's username
-    Username(&'a [u8]),
+    Username {
+        /// The client's username
+        username: &'a [u8],
+        /// The tag `H6(ssid)` confirming the SSID the client computed
+        #[cfg_attr(feature = "serde", serde(with = "serde_byte_array"))]
+        ssid_tag: [u8; 64],
+    },
 
     /// StrongUsername - the strong `AuCPace` username message
     /// also contains the blinded point `U`
@@ -1036,6 +1342,9 @@ pub enum ClientMessage<'a, const K1: usize> {
     StrongUsername {
         /// The client's username
         username: &'a [u8],
+        /// The tag `H6(ssid)` confirming the SSID the client computed
+        #[cfg_attr(feature = "serde", serde(with = "serde_byte_array"))]
+        ssid_tag: [u8; 64],
         /// The blinded point `U`
         blinded: RistrettoPoint,
     },
@@ -1113,23 +1422,12 @@ mod tests {
         assert_eq!(alloc_res, no_std_res);
     }
 
-    #[test]
-    #[cfg(all(feature = "getrandom", feature = "sha2"))]
-    fn test_client_doesnt_accept_insecure_ssid() {
-        use crate::Client;
-        use rand::rngs::OsRng;
-
-        let mut client = Client::new(OsRng);
-        let res = client.begin_prestablished_ssid("bad ssid");
-        assert!(matches!(res, Err(Error::InsecureSsid)));
-    }
-
     #[test]
     #[cfg(all(feature = "sha2", feature = "scrypt"))]
     fn test_client_doesnt_accept_invalid_x_pub() {
         use crate::utils::H0;
         use curve25519_dalek::traits::Identity;
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_client: AuCPaceClientAugLayer<'_, sha2::Sha512, scrypt::Scrypt, 16> =
             AuCPaceClientAugLayer::new(
                 ssid,
@@ -1155,7 +1453,7 @@ mod tests {
     fn test_alloc_client_doesnt_accept_invalid_x_pub() {
         use crate::utils::H0;
         use curve25519_dalek::traits::Identity;
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_client: AuCPaceClientAugLayer<'_, sha2::Sha512, scrypt::Scrypt, 16> =
             AuCPaceClientAugLayer::new(
                 ssid,
@@ -1183,13 +1481,13 @@ mod tests {
         use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
         use curve25519_dalek::traits::Identity;
 
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_client: StrongAuCPaceClientAugLayer<'_, sha2::Sha512, scrypt::Scrypt, 16> =
             StrongAuCPaceClientAugLayer::new(
                 ssid,
                 b"bob",
                 b"bob's very secure password that nobody knows about, honest",
-                Scalar::from(69u32),
+                SecretScalar::new(Scalar::from(69u32)),
             );
         let res = aug_client.generate_cpace::<100>(
             RistrettoPoint::identity(),
@@ -1217,13 +1515,13 @@ mod tests {
         use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
         use curve25519_dalek::traits::Identity;
 
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_client: StrongAuCPaceClientAugLayer<'_, sha2::Sha512, scrypt::Scrypt, 16> =
             StrongAuCPaceClientAugLayer::new(
                 ssid,
                 b"bob",
                 b"bob's very secure password that nobody knows about, honest",
-                Scalar::from(69u32),
+                SecretScalar::new(Scalar::from(69u32)),
             );
         let res = aug_client.generate_cpace_alloc(
             RistrettoPoint::identity(),
@@ -1244,7 +1542,7 @@ mod tests {
     fn test_client_doesnt_accept_invalid_pubkey() {
         use crate::utils::H0;
         use curve25519_dalek::traits::Identity;
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_client: AuCPaceClientRecvServerKey<sha2::Sha512, 16> =
             AuCPaceClientRecvServerKey::new(ssid, Scalar::from(420u32));
         let res = aug_client.receive_server_pubkey(RistrettoPoint::identity());
@@ -1261,7 +1559,7 @@ mod tests {
     fn test_client_doesnt_accept_invalid_pubkey_implicit_auth() {
         use crate::utils::H0;
         use curve25519_dalek::traits::Identity;
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_client: AuCPaceClientRecvServerKey<sha2::Sha512, 16> =
             AuCPaceClientRecvServerKey::new(ssid, Scalar::from(420u32));
         let res = aug_client.implicit_auth(RistrettoPoint::identity());
@@ -1285,13 +1583,13 @@ mod tests {
         use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
         use curve25519_dalek::traits::Identity;
 
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_client: StrongAuCPaceClientAugLayer<'_, sha2::Sha512, scrypt::Scrypt, 16> =
             StrongAuCPaceClientAugLayer::new(
                 ssid,
                 b"bob",
                 b"bob's very secure password that nobody knows about, honest",
-                Scalar::from(69u32),
+                SecretScalar::new(Scalar::from(69u32)),
             );
         let res = aug_client.generate_cpace_alloc(
             RISTRETTO_BASEPOINT_POINT,
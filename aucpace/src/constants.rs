@@ -1,2 +1,21 @@
 /// The minimum length an SSID must be in order to bypass the SSID establishment phase
 pub const MIN_SSID_LEN: usize = 16;
+
+/// The maximum length of a PHC parameter string, such as the PBKDF parameters `sigma`
+///
+/// This is the capacity of `password_hash`'s `ParamsString`, size buffers for stored or
+/// transmitted parameters with it. Longer strings are rejected with
+/// [`Error::ParamsTooLong`](crate::Error::ParamsTooLong), see
+/// [`parse_params`](crate::parse_params).
+pub const MAX_PARAMS_LEN: usize = 127;
+
+/// The maximum number of PBKDF parameter templates a server sends to unknown users, see
+/// [`AuCPaceServer::with_fallback_params`](crate::AuCPaceServer::with_fallback_params)
+pub const MAX_FALLBACK_PARAMS: usize = 4;
+
+/// The number of entries a [`ReplayGuard`](crate::ReplayGuard) remembers by default, see
+/// [`ReplayGuard::with_max_entries`](crate::ReplayGuard::with_max_entries)
+pub const DEFAULT_REPLAY_GUARD_ENTRIES: usize = 1 << 16;
+
+/// The longest username, in bytes, a [`FixedDatabase`](crate::FixedDatabase) stores
+pub const MAX_FIXED_USERNAME_LEN: usize = 64;
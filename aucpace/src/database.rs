@@ -1,12 +1,113 @@
-#[cfg(feature = "partial_augmentation")]
-use crate::Result;
+use crate::constants::{MAX_FIXED_USERNAME_LEN, MAX_PARAMS_LEN};
+use crate::{Error, Result};
+use core::fmt;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::traits::IsIdentity;
+use password_hash::Salt;
 use password_hash::{ParamsString, SaltString};
+use subtle::ConstantTimeEq;
+use subtle::{Choice, ConditionallySelectable};
+
+#[cfg(feature = "std")]
+extern crate std;
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Compare two usernames in constant time
+///
+/// A store which finds a user by comparing usernames with `==`, e.g. by scanning a `Vec`,
+/// takes longer the more of a username matches a stored one, which can help an attacker guess
+/// registered usernames. This compares every byte whenever the lengths are equal, only the
+/// lengths themselves are compared in variable time. Stores too large to scan could instead
+/// index users by a keyed hash of their username.
+#[inline]
+pub fn ct_username_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// A password verifier `W` which has been checked to be a valid point
+///
+/// Verifiers are typically stored as their 32 byte compressed encoding, [`from_compressed`]
+/// decodes them without the `Option` returned by [`CompressedRistretto::decompress`], which is
+/// all too easy to unwrap.
+///
+/// [`from_compressed`]: Self::from_compressed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Verifier(RistrettoPoint);
+
+impl Verifier {
+    /// Decode a verifier from its 32 byte compressed encoding
+    ///
+    /// # Return:
+    /// - Ok(`verifier`): the decoded verifier
+    /// - Err([`Error::IllegalPointError`](Error::IllegalPointError)): `bytes` is not the
+    ///   canonical encoding of a point, or it is the encoding of the identity
+    pub fn from_compressed(bytes: &[u8]) -> Result<Self> {
+        let point = CompressedRistretto::from_slice(bytes)
+            .ok()
+            .and_then(|compressed| compressed.decompress())
+            .ok_or(Error::IllegalPointError)?;
+        if point.is_identity() {
+            return Err(Error::IllegalPointError);
+        }
+        Ok(Self(point))
+    }
+
+    /// The verifier's 32 byte compressed encoding, as read by [`from_compressed`](Self::from_compressed)
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.compress().to_bytes()
+    }
+
+    /// The verifier as a point, e.g. for [`Database::PasswordVerifier`]
+    pub const fn as_point(&self) -> &RistrettoPoint {
+        &self.0
+    }
+}
+
+impl TryFrom<&[u8]> for Verifier {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        Self::from_compressed(bytes)
+    }
+}
+
+impl From<Verifier> for RistrettoPoint {
+    fn from(verifier: Verifier) -> Self {
+        verifier.0
+    }
+}
+
+/// The result of [`Database::try_lookup_verifier`]: `(password verifier, salt, sigma)` if the
+/// user exists, or the store's [`Error`](Database::Error) if the lookup failed
+pub type LookupResult<DB> = core::result::Result<
+    Option<(<DB as Database>::PasswordVerifier, SaltString, ParamsString)>,
+    <DB as Database>::Error,
+>;
 
 /// trait for `AuCPace` to use to abstract over the storage and retrieval of verifiers
+///
+/// In-memory stores should look users up with [`ct_username_eq`] rather than `==`.
+///
+/// # Thread safety
+/// The trait has no `Send` or `Sync` bounds, a single threaded server can use any store. The
+/// server only needs `&DB` to look users up, so a store shared between threads or async tasks
+/// must be `Sync`, and is usually held in an `Arc`. With the `std` feature `Database` is
+/// implemented for `Mutex<DB>` and `Arc<Mutex<DB>>`, this serialises the lookups and allows
+/// users to be stored through a shared handle. Do not hold the lock across an `.await`, each
+/// method locks it only for the duration of the call.
 pub trait Database {
     /// The type of password verifier stored in the database
     type PasswordVerifier;
 
+    /// The error the store reports when a lookup fails, see
+    /// [`try_lookup_verifier`](Database::try_lookup_verifier)
+    ///
+    /// Stores which cannot fail, or have nothing to add, use [`Error`](crate::Error). The
+    /// server methods which take a store return this type, so that a store's own error reaches
+    /// the caller intact; protocol errors are converted into it with `From`.
+    type Error: From<Error>;
+
     /// perform `LookupW`, returning the password verifier `W`, if it exists.
     ///
     /// # Arguments:
@@ -22,6 +123,27 @@ pub trait Database {
         username: &[u8],
     ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)>;
 
+    /// perform `LookupW`, distinguishing a failure of the store from a missing user.
+    ///
+    /// The server uses this method rather than [`lookup_verifier`](Database::lookup_verifier).
+    /// Stores which can fail (e.g. a networked database) should override it and return their
+    /// [`Error`](Database::Error) on failure, e.g.
+    /// [`Error::DatabaseLookup`](crate::Error::DatabaseLookup), so that an outage is reported
+    /// to the operator instead of being handled like an unknown user.
+    /// By default it wraps [`lookup_verifier`](Database::lookup_verifier) and never fails.
+    ///
+    /// # Arguments:
+    /// `username`: the user the lookup the verifier for
+    ///
+    /// # Return:
+    /// - Ok(Some(`(password verifier, salt, sigma)`)): the user's verifier, as for
+    ///   [`lookup_verifier`](Database::lookup_verifier)
+    /// - Ok(None): the user does not exist
+    /// - Err(`error`): the store failed to perform the lookup
+    fn try_lookup_verifier(&self, username: &[u8]) -> LookupResult<Self> {
+        Ok(self.lookup_verifier(username))
+    }
+
     /// store a username, salt, verifier and hash parameters to the database.
     /// This function should allow for overwriting users credentials if they exist.
     /// This is required for password changes and should only be performed once the user has
@@ -31,11 +153,11 @@ pub trait Database {
     /// - `username`: The name of the user who is storing a verifier
     /// - `salt`: The salt used when creating the verifier
     /// - `uad`: Optional - User Attached Data - "represents application data associated with
-    ///          this specific user account, e.g. specifying the granted authorization level
-    ///          on the server."
+    ///   this specific user account, e.g. specifying the granted authorization level
+    ///   on the server."
     /// - `verifier`: The password verifier for the given user
     /// - `params`: The parameters used when hashing the password into the verifier -
-    ///             It is called sigma in the protocol defionition
+    ///   It is called sigma in the protocol defionition
     fn store_verifier(
         &mut self,
         username: &[u8],
@@ -44,6 +166,487 @@ pub trait Database {
         verifier: Self::PasswordVerifier,
         params: ParamsString,
     );
+
+    /// re-encode every stored password verifier, e.g. after changing how verifiers are stored.
+    ///
+    /// Stores which support bulk migration should override this to call `f` once for every
+    /// user, with the username and its current verifier, and store the verifier `f` returns
+    /// in its place. The salt, parameters and user attached data are left unchanged.
+    /// By default the store does not support migration and nothing is changed.
+    ///
+    /// # Arguments:
+    /// `f`: maps a user's current verifier to the verifier to store for them
+    ///
+    /// # Return:
+    /// - Ok(()): every stored verifier was re-encoded
+    /// - Err([`Error::MigrationUnsupported`](crate::Error::MigrationUnsupported)): the store
+    ///   does not implement migration
+    fn migrate<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnMut(&[u8], Self::PasswordVerifier) -> Self::PasswordVerifier,
+    {
+        let _ = f;
+        Err(Error::MigrationUnsupported)
+    }
+
+    /// check the entry stored for a user is usable, e.g. to detect storage corruption before
+    /// a login attempt uses it.
+    ///
+    /// The entry is looked up with [`try_lookup_verifier`](Database::try_lookup_verifier) and
+    /// checked with [`validate_encoded_verifier`](Database::validate_encoded_verifier). A lookup
+    /// has already decoded the entry however, so this default can only catch a verifier which
+    /// is the identity. Stores which keep the encoded verifier and parameters, e.g. in database
+    /// columns, should override it to pass the bytes they read to
+    /// [`validate_encoded_verifier`](Database::validate_encoded_verifier).
+    ///
+    /// # Arguments:
+    /// `username`: the user whose entry to check
+    ///
+    /// # Return:
+    /// - Ok(()): the stored entry is valid
+    /// - Err([`Error::UnknownUser`](crate::Error::UnknownUser)): no verifier is stored for
+    ///   `username`
+    /// - Err(`error`): the store failed to perform the lookup
+    /// - Err(`error`): the entry is invalid, see
+    ///   [`validate_encoded_verifier`](Database::validate_encoded_verifier)
+    fn validate_stored_verifier(&self, username: &[u8]) -> core::result::Result<(), Self::Error>
+    where
+        Self::PasswordVerifier: Into<RistrettoPoint>,
+    {
+        let (verifier, _salt, params) = self
+            .try_lookup_verifier(username)?
+            .ok_or(Error::UnknownUser)?;
+        self.validate_encoded_verifier(verifier.into().compress().as_bytes(), params.as_str())
+    }
+
+    /// check an entry's verifier and parameters, as they are stored, are usable
+    ///
+    /// The verifier must be the canonical 32 byte encoding of a point other than the
+    /// identity, and the parameters must parse as a PHC parameter string of at most
+    /// [`MAX_PARAMS_LEN`](crate::constants::MAX_PARAMS_LEN) bytes.
+    ///
+    /// # Arguments:
+    /// - `verifier`: the stored compressed verifier
+    /// - `params`: the stored parameter string
+    ///
+    /// # Return:
+    /// - Ok(()): the entry is valid
+    /// - Err([`Error::IllegalPointError`](crate::Error::IllegalPointError)): the verifier is
+    ///   not a canonical encoding, or it encodes the identity
+    /// - Err([`Error::ParamsTooLong`](crate::Error::ParamsTooLong) |
+    ///   [`Error::PasswordHashing`](crate::Error::PasswordHashing)): the parameters are not a
+    ///   valid parameter string
+    fn validate_encoded_verifier(
+        &self,
+        verifier: &[u8],
+        params: &str,
+    ) -> core::result::Result<(), Self::Error> {
+        Verifier::from_compressed(verifier)?;
+        crate::utils::parse_params(params)?;
+        Ok(())
+    }
+}
+
+/// A username held in a [`FixedDatabase`] slot, zero padded to a fixed width and tagged with
+/// its length, so that comparing two of them always compares every byte
+#[derive(Clone, Copy)]
+struct FixedUsername {
+    len: u8,
+    bytes: [u8; MAX_FIXED_USERNAME_LEN],
+}
+
+impl FixedUsername {
+    const EMPTY: Self = Self {
+        len: 0,
+        bytes: [0u8; MAX_FIXED_USERNAME_LEN],
+    };
+
+    /// Pad `username`, `None` if it is longer than [`MAX_FIXED_USERNAME_LEN`]
+    fn new(username: &[u8]) -> Option<Self> {
+        if username.len() > MAX_FIXED_USERNAME_LEN {
+            return None;
+        }
+        let mut padded = Self::EMPTY;
+        padded.len = username.len() as u8;
+        padded.bytes[..username.len()].copy_from_slice(username);
+        Some(padded)
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..usize::from(self.len)]
+    }
+}
+
+// only the length, the username itself stays out of logs
+impl fmt::Debug for FixedUsername {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedUsername")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConstantTimeEq for FixedUsername {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.len.ct_eq(&other.len) & self.bytes.ct_eq(&other.bytes)
+    }
+}
+
+/// A [`FixedDatabase`] slot, with the salt and parameters stored as fixed width strings so that
+/// a slot can be selected without branching on its contents
+#[derive(Clone, Copy)]
+struct FixedEntry {
+    occupied: u8,
+    username: FixedUsername,
+    verifier: RistrettoPoint,
+    salt_len: u8,
+    salt: [u8; Salt::MAX_LENGTH],
+    params_len: u8,
+    params: [u8; MAX_PARAMS_LEN],
+}
+
+impl FixedEntry {
+    /// An empty slot
+    ///
+    /// It holds a valid salt of the recommended length and empty parameters, so that a lookup
+    /// of an unknown user builds its result from it like from a stored user.
+    fn empty() -> Self {
+        // the Base64 encoding of `Salt::RECOMMENDED_LENGTH` zero bytes
+        const EMPTY_SALT_LEN: usize = 22;
+        let mut salt = [0u8; Salt::MAX_LENGTH];
+        salt[..EMPTY_SALT_LEN].fill(b'A');
+        Self {
+            occupied: 0,
+            username: FixedUsername::EMPTY,
+            verifier: RistrettoPoint::default(),
+            salt_len: EMPTY_SALT_LEN as u8,
+            salt,
+            params_len: 0,
+            params: [0u8; MAX_PARAMS_LEN],
+        }
+    }
+
+    fn new(
+        username: FixedUsername,
+        verifier: RistrettoPoint,
+        salt: &SaltString,
+        params: &ParamsString,
+    ) -> Self {
+        let mut entry = Self::empty();
+        entry.occupied = 1;
+        entry.username = username;
+        entry.verifier = verifier;
+        // both lengths are bounded by the string types' own capacities
+        let salt = salt.as_str().as_bytes();
+        entry.salt_len = salt.len() as u8;
+        entry.salt[..salt.len()].copy_from_slice(salt);
+        let params = params.as_str().as_bytes();
+        entry.params_len = params.len() as u8;
+        entry.params[..params.len()].copy_from_slice(params);
+        entry
+    }
+
+    fn is_occupied(&self) -> bool {
+        self.occupied == 1
+    }
+
+    /// The stored verifier, salt and parameters
+    fn into_tuple(self) -> Option<(RistrettoPoint, SaltString, ParamsString)> {
+        let salt = core::str::from_utf8(&self.salt[..usize::from(self.salt_len)]).ok()?;
+        let params = core::str::from_utf8(&self.params[..usize::from(self.params_len)]).ok()?;
+        Some((
+            self.verifier,
+            SaltString::from_b64(salt).ok()?,
+            params.parse().ok()?,
+        ))
+    }
+}
+
+// the verifier, salt and parameters are redacted, leaving the slot's state and lengths
+impl fmt::Debug for FixedEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedEntry")
+            .field("occupied", &self.is_occupied())
+            .field("username", &self.username)
+            .field("verifier", &format_args!("[redacted]"))
+            .field("salt", &format_args!("[redacted], len={}", self.salt_len))
+            .field(
+                "params",
+                &format_args!("[redacted], len={}", self.params_len),
+            )
+            .finish()
+    }
+}
+
+impl ConditionallySelectable for FixedEntry {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut selected = *a;
+        selected.occupied.conditional_assign(&b.occupied, choice);
+        selected
+            .username
+            .len
+            .conditional_assign(&b.username.len, choice);
+        for (byte, other) in selected.username.bytes.iter_mut().zip(b.username.bytes) {
+            byte.conditional_assign(&other, choice);
+        }
+        selected.verifier.conditional_assign(&b.verifier, choice);
+        selected.salt_len.conditional_assign(&b.salt_len, choice);
+        for (byte, other) in selected.salt.iter_mut().zip(b.salt) {
+            byte.conditional_assign(&other, choice);
+        }
+        selected
+            .params_len
+            .conditional_assign(&b.params_len, choice);
+        for (byte, other) in selected.params.iter_mut().zip(b.params) {
+            byte.conditional_assign(&other, choice);
+        }
+        selected
+    }
+}
+
+/// An in-memory password verifier store for a small, fixed set of at most `N` users
+///
+/// Suited to e.g. an appliance with a handful of accounts. Usernames of up to
+/// [`MAX_FIXED_USERNAME_LEN`] bytes are stored zero padded to that width and tagged with their
+/// length, and [`lookup_verifier`] compares the padded username against every one of the `N`
+/// slots over the full width, empty slots included. The matching slot is then copied out of
+/// every slot with `subtle`, and the returned salt and parameters are built whether or not the
+/// user was found, from a placeholder entry if not. So the scan and the selection do not reveal
+/// where, or whether, the user is stored; only building the result takes time depending on the
+/// lengths of the selected salt and parameters.
+///
+/// [`lookup_verifier`]: Database::lookup_verifier
+#[derive(Clone)]
+pub struct FixedDatabase<const N: usize> {
+    slots: [FixedEntry; N],
+}
+
+impl<const N: usize> FixedDatabase<N> {
+    /// Create an empty store with `N` slots
+    pub fn new() -> Self {
+        Self {
+            slots: [FixedEntry::empty(); N],
+        }
+    }
+
+    /// The number of stored users
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_occupied()).count()
+    }
+
+    /// Whether no users are stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Store a user, like [`store_verifier`](Database::store_verifier), reporting a full store
+    ///
+    /// An existing user's entry is overwritten, a new user takes the first free slot.
+    ///
+    /// # Return:
+    /// - Ok(()): the user was stored
+    /// - Err([`Error::UsernameOrPasswordTooLong`](Error::UsernameOrPasswordTooLong)):
+    ///   `username` is longer than [`MAX_FIXED_USERNAME_LEN`] bytes
+    /// - Err([`Error::DatabaseFull`](Error::DatabaseFull)): `username` is not stored and all
+    ///   `N` slots are taken
+    pub fn try_store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        verifier: RistrettoPoint,
+        params: ParamsString,
+    ) -> Result<()> {
+        let username = FixedUsername::new(username).ok_or(Error::UsernameOrPasswordTooLong)?;
+        let index = match self.find(&username, |a, b| a.ct_eq(b)).into_option() {
+            Some(index) => index as usize,
+            None => self
+                .slots
+                .iter()
+                .position(|slot| !slot.is_occupied())
+                .ok_or(Error::DatabaseFull)?,
+        };
+        self.slots[index] = FixedEntry::new(username, verifier, &salt, &params);
+        Ok(())
+    }
+
+    /// Find the slot holding `username`, comparing it against every slot with `eq`
+    fn find<F>(&self, username: &FixedUsername, mut eq: F) -> subtle::CtOption<u64>
+    where
+        F: FnMut(&FixedUsername, &FixedUsername) -> Choice,
+    {
+        let mut found = Choice::from(0);
+        let mut index = 0u64;
+        for (i, slot) in self.slots.iter().enumerate() {
+            // empty slots are compared against too, so that they take as long as a stored user
+            let matched = Choice::from(slot.occupied) & eq(&slot.username, username) & !found;
+            index.conditional_assign(&(i as u64), matched);
+            found |= matched;
+        }
+        subtle::CtOption::new(index, found)
+    }
+
+    /// Copy the entry at `index` out of the store, reading every slot
+    fn select(&self, index: subtle::CtOption<u64>) -> (FixedEntry, Choice) {
+        let found = index.is_some();
+        let index = index.unwrap_or(0);
+        let mut entry = FixedEntry::empty();
+        for (i, slot) in self.slots.iter().enumerate() {
+            entry.conditional_assign(slot, (i as u64).ct_eq(&index) & found);
+        }
+        (entry, found)
+    }
+}
+
+// only the number of slots and users, not their entries
+impl<const N: usize> fmt::Debug for FixedDatabase<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedDatabase")
+            .field("slots", &N)
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const N: usize> Default for FixedDatabase<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Database for FixedDatabase<N> {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = Error;
+
+    /// Look up a user, see [`FixedDatabase`]
+    ///
+    /// A username longer than [`MAX_FIXED_USERNAME_LEN`] bytes is never stored, so it returns
+    /// `None` without a scan.
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        let username = FixedUsername::new(username)?;
+        let (entry, found) = self.select(self.find(&username, |a, b| a.ct_eq(b)));
+        // built on both paths, so that an unknown user costs as much as a stored one
+        let result = entry.into_tuple();
+        if bool::from(found) { result } else { None }
+    }
+
+    /// Store a user, see [`try_store_verifier`](FixedDatabase::try_store_verifier)
+    ///
+    /// The user attached data is not stored. If `username` is too long, or new and all `N`
+    /// slots are taken, the user is not stored, use
+    /// [`try_store_verifier`](FixedDatabase::try_store_verifier) to be told about this.
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        _uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        let _ = self.try_store_verifier(username, salt, verifier, params);
+    }
+
+    fn migrate<F>(&mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&[u8], Self::PasswordVerifier) -> Self::PasswordVerifier,
+    {
+        for entry in self.slots.iter_mut().filter(|slot| slot.is_occupied()) {
+            entry.verifier = f(entry.username.as_bytes(), entry.verifier);
+        }
+        Ok(())
+    }
+}
+
+/// Share a store between threads, every method locks the mutex for the duration of the call
+///
+/// A lookup against a poisoned mutex fails, with
+/// [`Error::DatabaseLookup`](crate::Error::DatabaseLookup), converted into the store's error,
+/// from [`try_lookup_verifier`](Database::try_lookup_verifier), as the store may have been left
+/// half-updated. Stores and migrations still go ahead, so that the users can be re-registered.
+#[cfg(feature = "std")]
+impl<DB: Database> Database for Mutex<DB> {
+    type PasswordVerifier = DB::PasswordVerifier;
+    type Error = DB::Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        self.lock().ok()?.lookup_verifier(username)
+    }
+
+    fn try_lookup_verifier(&self, username: &[u8]) -> LookupResult<Self> {
+        self.lock()
+            .map_err(|_| Error::DatabaseLookup)?
+            .try_lookup_verifier(username)
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .store_verifier(username, salt, uad, verifier, params);
+    }
+
+    fn migrate<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnMut(&[u8], Self::PasswordVerifier) -> Self::PasswordVerifier,
+    {
+        self.get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .migrate(f)
+    }
+}
+
+/// A shared handle to a store, see the implementation for [`Mutex`]
+///
+/// Storing a user through the handle locks the mutex, so a user can be registered while other
+/// threads hold clones of the handle.
+#[cfg(feature = "std")]
+impl<DB: Database> Database for Arc<Mutex<DB>> {
+    type PasswordVerifier = DB::PasswordVerifier;
+    type Error = DB::Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        Mutex::lookup_verifier(self, username)
+    }
+
+    fn try_lookup_verifier(&self, username: &[u8]) -> LookupResult<Self> {
+        Mutex::try_lookup_verifier(self, username)
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .store_verifier(username, salt, uad, verifier, params);
+    }
+
+    fn migrate<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnMut(&[u8], Self::PasswordVerifier) -> Self::PasswordVerifier,
+    {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .migrate(f)
+    }
 }
 
 /// trait for `AuCPace` to use to abstract over the storage and retrieval of long-term keypairs
@@ -83,7 +686,7 @@ pub trait PartialAugDatabase {
     /// # Return:
     /// - Ok(()): success - the keypair was stored correctly
     /// - Err([`Error::UserNotRegistered`](crate::Error::UserNotRegistered)): failure -
-    ///    `username` is not registered and thus we cannot store a keypair for them
+    ///   `username` is not registered and thus we cannot store a keypair for them
     ///
     fn store_long_term_keypair(
         &mut self,
@@ -125,12 +728,12 @@ pub trait StrongDatabase {
     /// # Arguments:
     /// - `username`: The name of the user who is storing a verifier
     /// - `uad`: Optional - User Attached Data - "represents application data associated with
-    ///          this specific user account, e.g. specifying the granted authorization level
-    ///          on the server."
+    ///   this specific user account, e.g. specifying the granted authorization level
+    ///   on the server."
     /// - `verifier`: The password verifier for the given user
     /// - `secret exponent`: the value of `q` stored for the given user
     /// - `params`: The parameters used when hashing the password into the verifier -
-    ///             It is called sigma in the protocol definition
+    ///   It is called sigma in the protocol definition
     fn store_verifier_strong(
         &mut self,
         username: &[u8],
@@ -140,3 +743,304 @@ pub trait StrongDatabase {
         params: ParamsString,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_database() -> FixedDatabase<4> {
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+        use curve25519_dalek::scalar::Scalar;
+
+        let mut database = FixedDatabase::new();
+        for (i, username) in [&b"jlpicard"[..], b"wriker", b"data"]
+            .into_iter()
+            .enumerate()
+        {
+            let verifier = RISTRETTO_BASEPOINT_POINT * Scalar::from(i as u64 + 1);
+            let salt = SaltString::encode_b64(username).unwrap();
+            database.store_verifier(username, salt, None, verifier, ParamsString::new());
+        }
+        database
+    }
+
+    #[test]
+    fn test_fixed_database_lookup() {
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+        use curve25519_dalek::scalar::Scalar;
+
+        let mut database = fixed_database();
+        assert_eq!(database.len(), 3);
+
+        let (verifier, salt, _) = database.lookup_verifier(b"wriker").unwrap();
+        assert_eq!(verifier, RISTRETTO_BASEPOINT_POINT * Scalar::from(2u64));
+        assert_eq!(salt, SaltString::encode_b64(b"wriker").unwrap());
+        assert!(database.lookup_verifier(b"wrike").is_none());
+        assert!(database.lookup_verifier(b"").is_none());
+
+        // an existing user is overwritten in place, a new one takes the free slot
+        let salt = SaltString::encode_b64(b"new salt").unwrap();
+        database
+            .try_store_verifier(
+                b"data",
+                salt.clone(),
+                RISTRETTO_BASEPOINT_POINT,
+                ParamsString::new(),
+            )
+            .unwrap();
+        assert_eq!(database.lookup_verifier(b"data").unwrap().1, salt);
+        database
+            .try_store_verifier(
+                b"worf",
+                salt.clone(),
+                RISTRETTO_BASEPOINT_POINT,
+                ParamsString::new(),
+            )
+            .unwrap();
+        assert_eq!(database.len(), 4);
+
+        assert_eq!(
+            database.try_store_verifier(
+                b"troi",
+                salt,
+                RISTRETTO_BASEPOINT_POINT,
+                ParamsString::new()
+            ),
+            Err(Error::DatabaseFull)
+        );
+        assert!(database.lookup_verifier(b"troi").is_none());
+    }
+
+    #[test]
+    fn test_fixed_database_debug_is_redacted() {
+        extern crate std;
+
+        let database = fixed_database();
+        let debug = std::format!("{database:?}");
+        assert_eq!(debug, "FixedDatabase { slots: 4, len: 3, .. }");
+
+        let entry = database.slots[1];
+        let debug = std::format!("{entry:?}");
+        assert_eq!(
+            debug,
+            "FixedEntry { occupied: true, username: FixedUsername { len: 6, .. }, \
+             verifier: [redacted], salt: [redacted], len=8, params: [redacted], len=0 }"
+        );
+        assert!(!debug.contains("wriker"));
+    }
+
+    #[test]
+    fn test_fixed_database_scans_every_slot() {
+        let database = fixed_database();
+
+        // whether the user is in the first slot, the last occupied one or not stored at all,
+        // the username is compared against all 4 slots, including the empty one
+        for (username, expected) in [
+            (&b"jlpicard"[..], Some(0)),
+            (b"data", Some(2)),
+            (b"q", None),
+        ] {
+            let mut comparisons = 0;
+            let username = FixedUsername::new(username).unwrap();
+            let index = database.find(&username, |a, b| {
+                comparisons += 1;
+                a.ct_eq(b)
+            });
+            assert_eq!(index.into_option(), expected);
+            assert_eq!(comparisons, 4);
+        }
+    }
+
+    #[test]
+    fn test_fixed_database_usernames_are_length_tagged() {
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+
+        let mut database = fixed_database();
+
+        // zero padding alone would make these equal to the stored "data"
+        assert!(database.lookup_verifier(b"data\0").is_none());
+        assert!(database.lookup_verifier(b"data\0\0\0").is_none());
+
+        let salt = SaltString::encode_b64(b"saltyboi").unwrap();
+        let longest = [b'u'; MAX_FIXED_USERNAME_LEN];
+        database
+            .try_store_verifier(
+                &longest,
+                salt.clone(),
+                RISTRETTO_BASEPOINT_POINT,
+                ParamsString::new(),
+            )
+            .unwrap();
+        assert!(database.lookup_verifier(&longest).is_some());
+
+        let too_long = [b'u'; MAX_FIXED_USERNAME_LEN + 1];
+        assert!(database.lookup_verifier(&too_long).is_none());
+        let mut database = fixed_database();
+        assert_eq!(
+            database.try_store_verifier(
+                &too_long,
+                salt,
+                RISTRETTO_BASEPOINT_POINT,
+                ParamsString::new()
+            ),
+            Err(Error::UsernameOrPasswordTooLong)
+        );
+        assert_eq!(database.len(), 3);
+    }
+
+    #[test]
+    fn test_fixed_database_builds_result_for_unknown_users() {
+        let database = fixed_database();
+        let username = FixedUsername::new(b"q").unwrap();
+
+        // an unknown user selects the placeholder entry, which builds a result like a stored one
+        let (entry, found) = database.select(database.find(&username, |a, b| a.ct_eq(b)));
+        assert!(!bool::from(found));
+        let (_, salt, params) = entry.into_tuple().unwrap();
+        assert_eq!(salt.len(), 22);
+        assert!(params.is_empty());
+
+        let username = FixedUsername::new(b"wriker").unwrap();
+        let (entry, found) = database.select(database.find(&username, |a, b| a.ct_eq(b)));
+        assert!(bool::from(found));
+        assert_eq!(
+            entry.into_tuple().unwrap().1,
+            SaltString::encode_b64(b"wriker").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_validate_stored_verifier() {
+        use curve25519_dalek::traits::Identity;
+
+        let mut database = fixed_database();
+        assert_eq!(database.validate_stored_verifier(b"wriker"), Ok(()));
+        assert_eq!(
+            database.validate_stored_verifier(b"q"),
+            Err(Error::UnknownUser)
+        );
+
+        // a verifier corrupted to the identity is caught before it is used in a login
+        let (_, salt, params) = database.lookup_verifier(b"data").unwrap();
+        database.store_verifier(b"data", salt, None, RistrettoPoint::identity(), params);
+        assert_eq!(
+            database.validate_stored_verifier(b"data"),
+            Err(Error::IllegalPointError)
+        );
+        assert_eq!(database.validate_stored_verifier(b"jlpicard"), Ok(()));
+    }
+
+    /// A single user store which keeps the entry as it was encoded, like a database row
+    struct EncodedDatabase<'a> {
+        verifier: [u8; 32],
+        params: &'a str,
+    }
+
+    impl Database for EncodedDatabase<'_> {
+        type PasswordVerifier = RistrettoPoint;
+        type Error = Error;
+
+        fn lookup_verifier(
+            &self,
+            _username: &[u8],
+        ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+            let verifier = Verifier::from_compressed(&self.verifier).ok()?;
+            let salt = SaltString::encode_b64(b"saltyboi").ok()?;
+            Some((verifier.into(), salt, self.params.parse().ok()?))
+        }
+
+        fn store_verifier(
+            &mut self,
+            _username: &[u8],
+            _salt: SaltString,
+            _uad: Option<&[u8]>,
+            verifier: Self::PasswordVerifier,
+            _params: ParamsString,
+        ) {
+            self.verifier = verifier.compress().to_bytes();
+        }
+
+        fn validate_stored_verifier(&self, _username: &[u8]) -> Result<()> {
+            self.validate_encoded_verifier(&self.verifier, self.params)
+        }
+    }
+
+    #[test]
+    fn test_validate_encoded_verifier() {
+        use crate::constants::MAX_PARAMS_LEN;
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+
+        let mut database = EncodedDatabase {
+            verifier: RISTRETTO_BASEPOINT_POINT.compress().to_bytes(),
+            params: "ln=15,r=8,p=1",
+        };
+        assert_eq!(database.validate_stored_verifier(b"jlpicard"), Ok(()));
+
+        // all ones is not a canonical field element, a lookup could only report a missing user
+        database.verifier = [0xff; 32];
+        assert!(database.lookup_verifier(b"jlpicard").is_none());
+        assert_eq!(
+            database.validate_stored_verifier(b"jlpicard"),
+            Err(Error::IllegalPointError)
+        );
+
+        // a parameter string longer than a ParamsString can hold
+        let long_params = [b'a'; MAX_PARAMS_LEN + 1];
+        database.verifier = RISTRETTO_BASEPOINT_POINT.compress().to_bytes();
+        database.params = core::str::from_utf8(&long_params).unwrap();
+        assert_eq!(
+            database.validate_stored_verifier(b"jlpicard"),
+            Err(Error::ParamsTooLong)
+        );
+        database.params = "ln=15,r";
+        assert!(matches!(
+            database.validate_stored_verifier(b"jlpicard"),
+            Err(Error::PasswordHashing(_))
+        ));
+    }
+
+    #[test]
+    fn test_verifier_from_compressed() {
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+        use curve25519_dalek::scalar::Scalar;
+
+        let point = RISTRETTO_BASEPOINT_POINT * Scalar::from(0x1701u32);
+        let bytes = point.compress().to_bytes();
+        let verifier = Verifier::from_compressed(&bytes).unwrap();
+        assert_eq!(verifier.as_point(), &point);
+        assert_eq!(verifier.to_bytes(), bytes);
+        assert_eq!(Verifier::try_from(&bytes[..]), Ok(verifier));
+    }
+
+    #[test]
+    fn test_verifier_rejects_invalid_encodings() {
+        // the identity has a canonical encoding, but is no verifier
+        assert_eq!(
+            Verifier::from_compressed(&[0u8; 32]),
+            Err(Error::IllegalPointError)
+        );
+        // all ones is not a canonical field element
+        assert_eq!(
+            Verifier::from_compressed(&[0xffu8; 32]),
+            Err(Error::IllegalPointError)
+        );
+        assert_eq!(
+            Verifier::from_compressed(&[1u8; 31]),
+            Err(Error::IllegalPointError)
+        );
+    }
+
+    #[test]
+    fn test_ct_username_eq() {
+        assert!(ct_username_eq(b"jlpicard", b"jlpicard"));
+        assert!(ct_username_eq(b"", b""));
+
+        // a mismatch in the first or the last byte is the same answer
+        assert!(!ct_username_eq(b"jlpicard", b"xlpicard"));
+        assert!(!ct_username_eq(b"jlpicard", b"jlpicarx"));
+
+        // a prefix is not a match
+        assert!(!ct_username_eq(b"jlpicard", b"jlpic"));
+        assert!(!ct_username_eq(b"", b"jlpicard"));
+    }
+}
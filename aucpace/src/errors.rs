@@ -1,6 +1,61 @@
 use core::fmt;
 
 /// Errors that can occur during the protocol
+///
+/// This enum is `#[non_exhaustive]`, matches outside this crate must include a wildcard arm
+/// so that new error variants are not a breaking change:
+///
+/// ```compile_fail,E0004
+/// use aucpace_conflux::Error;
+///
+/// // every variant is listed, so only `#[non_exhaustive]` makes the wildcard arm required
+/// #[allow(deprecated)]
+/// fn is_retryable(error: Error) -> bool {
+///     match error {
+///         Error::Rng => true,
+///         Error::IllegalPointError => false,
+///         Error::PasswordHashing(_) => false,
+///         Error::RngInit => false,
+///         Error::HashEmpty => false,
+///         Error::HashSizeInvalid => false,
+///         Error::MutualAuthFail => false,
+///         Error::KeyConfirmationFailed => false,
+///         Error::UsernameOrPasswordTooLong => false,
+///         Error::ParamsTooLong => false,
+///         Error::InvalidFallbackParams => false,
+///         Error::SsidMismatch => false,
+///         Error::InsecureSsid => false,
+///         Error::SsidTooShort { .. } => false,
+///         Error::NonceLengthMismatch => false,
+///         Error::DatabaseLookup => false,
+///         Error::UnknownUser => false,
+///         Error::InternalInvariant => false,
+///         Error::ZeroScalar => false,
+///         Error::MigrationUnsupported => false,
+///         #[cfg(feature = "alloc")]
+///         Error::SessionLimitReached => false,
+///         #[cfg(feature = "alloc")]
+///         Error::UnknownSession => false,
+///         #[cfg(feature = "alloc")]
+///         Error::UnexpectedMessage => false,
+///         Error::DatabaseFull => false,
+///         #[cfg(feature = "std")]
+///         Error::ReplayDetected => false,
+///         #[cfg(feature = "std")]
+///         Error::EphemeralReuse => false,
+///         #[cfg(feature = "std")]
+///         Error::ReplayGuardFull => false,
+///         #[cfg(feature = "partial_augmentation")]
+///         Error::UserNotRegistered => false,
+///         #[cfg(feature = "stringprep")]
+///         Error::PasswordNormalization => false,
+///         #[cfg(all(feature = "argon2", feature = "getrandom"))]
+///         Error::EmptyPassword => false,
+///         #[cfg(feature = "postcard")]
+///         Error::Codec => false,
+///     }
+/// }
+/// ```
 #[non_exhaustive]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Error {
@@ -8,51 +63,261 @@ pub enum Error {
     IllegalPointError,
     /// Wrapper around `password_hash`'s error type, for propagating errors should they occur
     PasswordHashing(password_hash::Error),
-    /// Failure in random number generation (entropy unavailable or RNG error)
+    /// Failure drawing from the RNG during the protocol, e.g. while generating a nonce or key
     Rng,
+    /// Failure drawing from the RNG while constructing a server, before any handshake, e.g.
+    /// because the OS entropy source is unavailable
+    RngInit,
     /// PasswordHasher produced an empty hash.
     HashEmpty,
     /// PasswordHasher produced a hash of an invalid size (size was not 32 or 64 bytes)
     HashSizeInvalid,
     /// Failure during Explicit Mutual Authentication
+    #[deprecated(note = "superseded by `Error::KeyConfirmationFailed`, which is returned instead")]
     MutualAuthFail,
+    /// The peer's key confirmation authenticator does not match the one computed locally, so
+    /// the two sides derived different session keys, e.g. because the password was wrong
+    KeyConfirmationFailed,
     /// The username:password string would overflow the buffer size allocated for hashing the password
-    /// Note: this error can only occur when using the *_alloc APIs
+    /// Note: this error can only occur when using the *_alloc APIs, or when storing a username
+    /// longer than [`MAX_FIXED_USERNAME_LEN`](crate::constants::MAX_FIXED_USERNAME_LEN) in a
+    /// [`FixedDatabase`](crate::FixedDatabase)
     UsernameOrPasswordTooLong,
+    /// A PBKDF parameter string is longer than
+    /// [`MAX_PARAMS_LEN`](crate::constants::MAX_PARAMS_LEN)
+    ParamsTooLong,
+    /// The PBKDF parameters to send to unknown users are empty or there are too many of them,
+    /// see [`with_fallback_params`](crate::AuCPaceServer::with_fallback_params)
+    InvalidFallbackParams,
+    /// The client and server computed different SSIDs, e.g. because a nonce was corrupted in
+    /// transit: the tag the client sent with its username does not match the server's SSID
+    SsidMismatch,
     /// The SSID provided is too short to be secure, SSIDs must be at least 16 bytes long
     /// Note: this error can only occur if the SSID establishment phase is bypassed
+    #[deprecated(note = "superseded by `Error::SsidTooShort`, which is returned instead")]
     InsecureSsid,
+    /// The SSID provided is too short to be secure
+    /// Note: this error can only occur if the SSID establishment phase is bypassed
+    SsidTooShort {
+        /// The minimum SSID length, [`MIN_SSID_LEN`](crate::constants::MIN_SSID_LEN)
+        min: usize,
+        /// The length of the SSID that was provided
+        got: usize,
+    },
+    /// The two nonces an SSID is computed from have different lengths
+    NonceLengthMismatch,
+    /// The password verifier database failed to perform a lookup.
+    /// Unlike a missing user this does not take the failed lookup path, so outages are visible.
+    DatabaseLookup,
+    /// The password verifier database has no verifier for the user, see
+    /// [`validate_stored_verifier`](crate::Database::validate_stored_verifier)
+    UnknownUser,
+    /// A conversion which cannot fail did, e.g. a 64 byte digest output not fitting a 64 byte
+    /// array. This indicates a bug in this crate rather than bad input, please report it.
+    InternalInvariant,
+    /// A secret scalar was zero, e.g. a caller provided private key
+    ZeroScalar,
+    /// The password verifier database does not support [`migrate`](crate::Database::migrate)
+    MigrationUnsupported,
+    /// A [`SessionStore`](crate::SessionStore) already holds its maximum number of sessions
+    #[cfg(feature = "alloc")]
+    SessionLimitReached,
+    /// A [`SessionStore`](crate::SessionStore) has no session for the given SSID
+    #[cfg(feature = "alloc")]
+    UnknownSession,
+    /// A [`SessionStore`](crate::SessionStore) session received a message it was not waiting for
+    #[cfg(feature = "alloc")]
+    UnexpectedMessage,
+    /// A [`FixedDatabase`](crate::FixedDatabase) has no free slot for a new user
+    DatabaseFull,
+    /// A [`ReplayGuard`](crate::ReplayGuard) has seen the SSID within its window, the
+    /// handshake is a replay
+    #[cfg(feature = "std")]
+    ReplayDetected,
+    /// A [`ReplayGuard`](crate::ReplayGuard) has seen the client's ephemeral public key within
+    /// its window, the client reuses its `CPace` keys
+    #[cfg(feature = "std")]
+    EphemeralReuse,
+    /// A [`ReplayGuard`](crate::ReplayGuard) remembers its maximum number of entries, none of
+    /// which has left the window yet, so it cannot record another one
+    #[cfg(feature = "std")]
+    ReplayGuardFull,
     /// This error happens when a long term keypair for a user is stored in a [`PartialAugDatabase`](crate::PartialAugDatabase)
     /// but the user doesn't exist, this operation has no meaning and as such is an error.
     #[cfg(feature = "partial_augmentation")]
     UserNotRegistered,
+    /// The password could not be normalized: it contains prohibited code points, or mixes
+    /// bidirectional text in a way the SASLprep profile forbids.
+    #[cfg(feature = "stringprep")]
+    PasswordNormalization,
+    /// An empty password was enrolled without empty passwords being allowed, see
+    /// [`Argon2Enrollment::allow_empty_password`](crate::Argon2Enrollment::allow_empty_password)
+    #[cfg(all(feature = "argon2", feature = "getrandom"))]
+    EmptyPassword,
+    /// A message could not be encoded with `postcard`, e.g. the buffer was too small, or the
+    /// bytes received are not a valid encoding of a message
+    #[cfg(feature = "postcard")]
+    Codec,
 }
 
+#[allow(deprecated)]
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::IllegalPointError => write!(f, "illegal point encountered"),
             Self::PasswordHashing(error) => write!(f, "error while hashing password: {error}"),
             Self::Rng => write!(f, "random number generator failure"),
+            Self::RngInit => write!(f, "random number generator failed during initialization"),
             Self::HashEmpty => write!(f, "password hash empty"),
             Self::HashSizeInvalid => write!(f, "password hash invalid, should be 32 or 64 bytes"),
             Self::MutualAuthFail => write!(
                 f,
                 "explicit mutual authentication failed, authenticators didn't match"
             ),
+            Self::KeyConfirmationFailed => write!(
+                f,
+                "key confirmation failed, the peer derived a different session key"
+            ),
             Self::UsernameOrPasswordTooLong => write!(f, "username or password too long"),
+            Self::ParamsTooLong => write!(
+                f,
+                "PBKDF parameter string is longer than {} bytes",
+                crate::constants::MAX_PARAMS_LEN
+            ),
+            Self::InvalidFallbackParams => write!(
+                f,
+                "between 1 and {} non-empty fallback PBKDF parameter strings are required",
+                crate::constants::MAX_FALLBACK_PARAMS
+            ),
+            Self::SsidMismatch => write!(f, "the client and server computed different SSIDs"),
             Self::InsecureSsid => write!(
                 f,
                 "provided SSID is insecure - SSIDs must be at least 16 bytes long"
             ),
+            Self::SsidTooShort { min, got } => write!(
+                f,
+                "provided SSID is insecure - SSIDs must be at least {min} bytes long, got {got}"
+            ),
+            Self::NonceLengthMismatch => write!(f, "the SSID nonces have different lengths"),
+            Self::DatabaseLookup => write!(f, "password verifier database lookup failed"),
+            Self::UnknownUser => write!(f, "no password verifier is stored for the user"),
+            Self::InternalInvariant => write!(f, "internal invariant violated"),
+            Self::ZeroScalar => write!(f, "secret scalar is zero"),
+            Self::MigrationUnsupported => {
+                write!(f, "password verifier database does not support migration")
+            }
+            #[cfg(feature = "alloc")]
+            Self::SessionLimitReached => write!(f, "too many concurrent sessions"),
+            #[cfg(feature = "alloc")]
+            Self::UnknownSession => write!(f, "no session exists for the given SSID"),
+            #[cfg(feature = "alloc")]
+            Self::UnexpectedMessage => write!(f, "unexpected message for the session's state"),
+            Self::DatabaseFull => write!(f, "password verifier database is full"),
+            #[cfg(feature = "std")]
+            Self::ReplayDetected => write!(f, "SSID was already used within the replay window"),
+            #[cfg(feature = "std")]
+            Self::EphemeralReuse => write!(
+                f,
+                "client public key was already used within the replay window"
+            ),
+            #[cfg(feature = "std")]
+            Self::ReplayGuardFull => write!(f, "replay guard has no room for another entry"),
             #[cfg(feature = "partial_augmentation")]
             Self::UserNotRegistered => write!(
                 f,
                 "user must be registered before a long-term keypair can be stored"
             ),
+            #[cfg(feature = "stringprep")]
+            Self::PasswordNormalization => write!(
+                f,
+                "password contains characters prohibited by the SASLprep profile"
+            ),
+            #[cfg(all(feature = "argon2", feature = "getrandom"))]
+            Self::EmptyPassword => write!(f, "empty passwords are not allowed"),
+            #[cfg(feature = "postcard")]
+            Self::Codec => write!(f, "failed to encode or decode message"),
+        }
+    }
+}
+
+// Available in `no_std`; the underlying `password_hash` error is only exposed as a source with
+// `std`, where it implements the error trait.
+impl core::error::Error for Error {
+    #[cfg(feature = "std")]
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::PasswordHashing(error) => Some(error),
+            _ => None,
         }
     }
 }
 
 /// Result type
 pub type Result<T> = core::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn assert_copy<T: Copy>() {}
+    const _: () = assert_copy::<Error>();
+
+    /// Every variant is listed without a wildcard arm, so adding a variant fails to compile
+    /// here until it is given a description; this also checks the enum is usable in `no_std`.
+    #[allow(deprecated)]
+    fn describe(error: Error) -> &'static str {
+        match error {
+            Error::IllegalPointError => "illegal point",
+            Error::PasswordHashing(_) => "password hashing",
+            Error::Rng => "rng",
+            Error::RngInit => "rng init",
+            Error::HashEmpty => "hash empty",
+            Error::HashSizeInvalid => "hash size invalid",
+            Error::MutualAuthFail => "mutual auth fail",
+            Error::KeyConfirmationFailed => "key confirmation failed",
+            Error::UsernameOrPasswordTooLong => "username or password too long",
+            Error::ParamsTooLong => "params too long",
+            Error::InvalidFallbackParams => "invalid fallback params",
+            Error::SsidMismatch => "ssid mismatch",
+            Error::InsecureSsid => "insecure ssid",
+            Error::SsidTooShort { .. } => "ssid too short",
+            Error::NonceLengthMismatch => "nonce length mismatch",
+            Error::DatabaseLookup => "database lookup",
+            Error::UnknownUser => "unknown user",
+            Error::InternalInvariant => "internal invariant",
+            Error::ZeroScalar => "zero scalar",
+            Error::MigrationUnsupported => "migration unsupported",
+            #[cfg(feature = "alloc")]
+            Error::SessionLimitReached => "session limit reached",
+            #[cfg(feature = "alloc")]
+            Error::UnknownSession => "unknown session",
+            #[cfg(feature = "alloc")]
+            Error::UnexpectedMessage => "unexpected message",
+            Error::DatabaseFull => "database full",
+            #[cfg(feature = "std")]
+            Error::ReplayDetected => "replay detected",
+            #[cfg(feature = "std")]
+            Error::EphemeralReuse => "ephemeral reuse",
+            #[cfg(feature = "std")]
+            Error::ReplayGuardFull => "replay guard full",
+            #[cfg(feature = "partial_augmentation")]
+            Error::UserNotRegistered => "user not registered",
+            #[cfg(feature = "stringprep")]
+            Error::PasswordNormalization => "password normalization",
+            #[cfg(all(feature = "argon2", feature = "getrandom"))]
+            Error::EmptyPassword => "empty password",
+            #[cfg(feature = "postcard")]
+            Error::Codec => "codec",
+        }
+    }
+
+    #[test]
+    fn test_error_matches_exhaustively() {
+        let error = Error::SsidTooShort { min: 16, got: 3 };
+        let copy = error;
+        assert_eq!(error, copy);
+        assert_eq!(describe(error), "ssid too short");
+        assert_eq!(describe(Error::Rng), "rng");
+        assert_eq!(describe(Error::IllegalPointError), "illegal point");
+    }
+}
@@ -5,7 +5,9 @@
     html_logo_url = "https://raw.githubusercontent.com/RustCrypto/meta/master/logo.svg",
     html_favicon_url = "https://raw.githubusercontent.com/RustCrypto/meta/master/logo.svg"
 )]
-#![forbid(unsafe_code)]
+#![cfg_attr(not(test), forbid(unsafe_code))]
+// tests read the memory a dropped step leaves behind, the library itself stays free of `unsafe`
+#![cfg_attr(test, deny(unsafe_code))]
 #![warn(missing_docs, rust_2018_idioms, unused_qualifications)]
 #![allow(clippy::large_enum_variant)]
 
@@ -82,8 +84,15 @@
 //!
 //! [1]: https://eprint.iacr.org/2018/286.pdf
 
+#[cfg(feature = "blake3")]
+mod blake3_digest;
 mod database;
 mod errors;
+#[cfg(feature = "std")]
+mod replay;
+#[cfg(feature = "alloc")]
+mod session;
+mod ssid;
 mod utils;
 
 /// Module containing the implementation of the client for the `AuCPace` protocol
@@ -97,11 +106,53 @@ pub mod constants;
 
 pub use self::{
     client::{AuCPaceClient, ClientMessage},
-    database::Database,
+    database::{Database, FixedDatabase, LookupResult, Verifier, ct_username_eq},
     errors::{Error, Result},
-    server::{AuCPaceServer, ServerMessage},
+    server::{AuCPaceServer, CachedPoint, ServerMessage},
+    ssid::Ssid,
 };
 
+pub use self::utils::{
+    DigestName, HashScheme, LeU32HashScheme, SingleByteHashScheme, SsidHasher, client_prs,
+    compute_authenticator_messages_secret, compute_first_session_key_ref,
+    compute_session_key_bound, compute_ssid_secret, compute_ssid_slices, cpace_generator,
+    cpace_generator_with_scheme, generate_keypair, generate_keypair_into, generate_keypair_ref,
+    generate_nonce_retry, generate_nonce_secret, generate_salt, generate_server_keypair,
+    generate_server_keypair_with_cofactor, hash_oracle, keypair_from_scalar, parse_params,
+    point_is_valid_ct, scalar_from_wide_hash, server_prs, verifier_bytes_from_hash,
+    verifier_point_from_hash, verify_session_key_confirmation,
+};
+
+#[cfg(feature = "secret-wrappers")]
+pub use self::utils::{
+    compute_first_session_key_secret, compute_session_key_secret, generate_keypair_secret,
+    generate_server_keypair_secret,
+};
+
+#[cfg(all(feature = "argon2", feature = "getrandom"))]
+pub use self::client::{Argon2Enrollment, enroll_argon2};
+
+#[cfg(feature = "argon2-pepper")]
+pub use self::client::pepper_password;
+
+#[cfg(feature = "rayon")]
+pub use self::client::{Enrollment, enroll_batch};
+
+#[cfg(feature = "strong_aucpace")]
+pub use self::utils::{blind_salt, generate_blinding_scalar, invert_blinding};
+
+#[cfg(feature = "stringprep")]
+pub use self::utils::normalize_password;
+
+#[cfg(feature = "alloc")]
+pub use self::session::SessionStore;
+
+#[cfg(feature = "std")]
+pub use self::replay::{ReplayGuard, SharedReplayGuard};
+
+#[cfg(feature = "blake3")]
+pub use self::blake3_digest::Blake3Digest;
+
 #[cfg(feature = "partial_augmentation")]
 pub use self::database::PartialAugDatabase;
 
@@ -0,0 +1,177 @@
+use crate::constants::DEFAULT_REPLAY_GUARD_ENTRIES;
+use crate::{Error, Result};
+use curve25519_dalek::digest::{Digest, Output};
+
+extern crate std;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Remembers recently used SSIDs, so that a replayed handshake can be rejected
+///
+/// Every handshake should use a fresh SSID, and the SSID establishment phase guarantees this.
+/// A pre-established SSID, see
+/// [`begin_prestablished_ssid_guarded`](crate::AuCPaceServer::begin_prestablished_ssid_guarded),
+/// comes from outside the protocol however, so a replayed handshake would be processed as a new
+/// one. A `ReplayGuard` records the hash of each SSID it is shown and rejects it if it is shown
+/// again within `ttl`. Entries are kept in the order they were recorded, and those older than
+/// `ttl` are pruned from the front whenever an SSID is checked, so the guard only holds the
+/// SSIDs of the last `ttl` and a check costs amortised constant time.
+///
+/// The guard remembers at most `max_entries` SSIDs. Once that many are within the window it
+/// rejects new SSIDs with [`Error::ReplayGuardFull`] rather than forgetting one early, which
+/// would let it be replayed.
+///
+/// A `ReplayGuard` is `Send` and `Sync`, but its methods take `&mut self`. To share one guard
+/// between threads or async tasks use a [`SharedReplayGuard`].
+pub struct ReplayGuard<D>
+where
+    D: Digest,
+{
+    seen: HashMap<Output<D>, Instant>,
+    order: VecDeque<(Instant, Output<D>)>,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl<D> ReplayGuard<D>
+where
+    D: Digest,
+{
+    /// Create an empty guard which rejects SSIDs seen within the last `ttl`
+    ///
+    /// The guard remembers at most [`DEFAULT_REPLAY_GUARD_ENTRIES`] SSIDs.
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_max_entries(ttl, DEFAULT_REPLAY_GUARD_ENTRIES)
+    }
+
+    /// Create an empty guard which rejects SSIDs seen within the last `ttl`, and remembers at
+    /// most `max_entries` of them
+    pub fn with_max_entries(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+            ttl,
+            max_entries,
+        }
+    }
+
+    /// The window within which a repeated SSID is rejected
+    pub const fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The maximum number of SSIDs remembered at once
+    pub const fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    /// The number of SSIDs currently remembered
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Whether no SSIDs are currently remembered
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// Check that the SSID hash `ssid` was not seen within the window, and record it
+    ///
+    /// # Return:
+    /// - Ok(()): the SSID is fresh, and has been recorded
+    /// - Err([`Error::ReplayDetected`](Error::ReplayDetected)): the SSID was seen within `ttl`
+    /// - Err([`Error::ReplayGuardFull`](Error::ReplayGuardFull)): `max_entries` SSIDs are
+    ///   within the window, the SSID has not been recorded
+    ///
+    pub fn check_and_record(&mut self, ssid: &Output<D>) -> Result<()> {
+        let now = Instant::now();
+        self.prune(now);
+
+        if self.seen.contains_key(ssid) {
+            return Err(Error::ReplayDetected);
+        }
+        if self.ttl.is_zero() {
+            return Ok(());
+        }
+        if self.seen.len() >= self.max_entries {
+            return Err(Error::ReplayGuardFull);
+        }
+        self.seen.insert(ssid.clone(), now);
+        self.order.push_back((now, ssid.clone()));
+        Ok(())
+    }
+
+    /// Forget the entries recorded `ttl` or longer before `now`, oldest first
+    fn prune(&mut self, now: Instant) {
+        while let Some((seen_at, _)) = self.order.front() {
+            if now.duration_since(*seen_at) < self.ttl {
+                break;
+            }
+            if let Some((_, ssid)) = self.order.pop_front() {
+                self.seen.remove(&ssid);
+            }
+        }
+    }
+}
+
+/// A cloneable, thread-safe handle to a [`ReplayGuard`]
+///
+/// Every clone refers to the same guard, so a replay is detected whichever thread or task
+/// handles it. The guard is locked only while an SSID is checked, which never blocks on I/O,
+/// so it is safe to use from async tasks as long as the [`lock`](Self::lock) guard is not held
+/// across an `.await`.
+pub struct SharedReplayGuard<D>
+where
+    D: Digest,
+{
+    inner: Arc<Mutex<ReplayGuard<D>>>,
+}
+
+impl<D> SharedReplayGuard<D>
+where
+    D: Digest,
+{
+    /// Create an empty guard which rejects SSIDs seen within the last `ttl`
+    ///
+    /// See [`ReplayGuard::new`].
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_max_entries(ttl, DEFAULT_REPLAY_GUARD_ENTRIES)
+    }
+
+    /// Create an empty guard which rejects SSIDs seen within the last `ttl`, and remembers at
+    /// most `max_entries` of them
+    ///
+    /// See [`ReplayGuard::with_max_entries`].
+    pub fn with_max_entries(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(ReplayGuard::with_max_entries(ttl, max_entries))),
+        }
+    }
+
+    /// Check that the SSID hash `ssid` was not seen within the window, and record it
+    ///
+    /// See [`ReplayGuard::check_and_record`].
+    pub fn check_and_record(&self, ssid: &Output<D>) -> Result<()> {
+        self.lock().check_and_record(ssid)
+    }
+
+    /// Lock the guard, e.g. to pass it to
+    /// [`begin_prestablished_ssid_guarded`](crate::AuCPaceServer::begin_prestablished_ssid_guarded)
+    ///
+    /// A guard poisoned by a panicking thread is still used, every update leaves it consistent.
+    pub fn lock(&self) -> MutexGuard<'_, ReplayGuard<D>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<D> Clone for SharedReplayGuard<D>
+where
+    D: Digest,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
@@ -1,21 +1,28 @@
 use crate::Database;
-use crate::constants::MIN_SSID_LEN;
+use crate::Ssid;
+use crate::constants::MAX_FALLBACK_PARAMS;
 use crate::utils::{
-    H0, compute_authenticator_messages, compute_first_session_key, compute_session_key,
-    compute_ssid, generate_keypair, generate_nonce, generate_server_keypair,
+    DigestName, H0, HashScheme, LeU32HashScheme, basepoint_mul,
+    compute_first_session_key_with_scheme, compute_session_key_with_scheme, compute_ssid_tag,
+    compute_ssid_with_scheme, generate_keypair_with_scheme, generate_nonce,
+    generate_server_keypair, ristretto_cofactor, server_prs,
+    verify_session_key_confirmation_with_scheme,
 };
 use crate::{Error, Result};
+use core::fmt;
 use core::marker::PhantomData;
 use curve25519_dalek::traits::IsIdentity;
 use curve25519_dalek::{
     digest::consts::U64,
     digest::{Digest, Output},
-    ristretto::RistrettoPoint,
+    ristretto::{CompressedRistretto, RistrettoPoint},
     scalar::Scalar,
 };
-use password_hash::{ParamsString, SaltString};
+use password_hash::{ParamsString, Salt, SaltString};
 use rand_core::{TryCryptoRng, TryRngCore};
+use secret_utils::wrappers::SecretScalar;
 use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
 
 #[cfg(feature = "partial_augmentation")]
 use crate::database::PartialAugDatabase;
@@ -29,21 +36,118 @@ use crate::utils::{serde_paramsstring, serde_saltstring};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-/// A non-copy wrapper around u64
+/// The secret used to obscure when a password lookup failed, along with the PBKDF parameters
+/// sent in place of a stored `sigma` when it does
 #[derive(Clone)]
-struct ServerSecret(u64);
+struct ServerSecret {
+    key: u64,
+    fallback_params: FallbackParams,
+}
 
 impl ServerSecret {
     fn new<CSPRNG: TryRngCore + TryCryptoRng>(rng: &mut CSPRNG) -> Result<Self> {
-        Ok(Self(rng.try_next_u64().map_err(|_| Error::Rng)?))
+        let key = rng.try_next_u64().map_err(|_| Error::RngInit)?;
+        Self::from_key(key)
+    }
+
+    fn from_key(key: u64) -> Result<Self> {
+        Ok(Self {
+            key,
+            fallback_params: FallbackParams::recommended()?,
+        })
+    }
+
+    /// Hash the server secret and the user's name, an unknown user's salt (or `q` with strong
+    /// `AuCPace`) is derived from this so that repeated probes of one username get the same
+    /// response
+    fn fallback_hasher<D: Digest + Default>(&self, username: &[u8]) -> D {
+        let mut hasher: D = Default::default();
+        hasher.update(self.key.to_le_bytes());
+        hasher.update(username);
+        hasher
+    }
+
+    /// The PBKDF parameters to send for an unknown user, chosen by a hash of the server secret
+    /// and the user's name which is separate from [`fallback_hasher`](Self::fallback_hasher)
+    fn fallback_params<D: Digest + Default>(&self, username: &[u8]) -> ParamsString {
+        let mut hasher: D = Default::default();
+        hasher.update(FALLBACK_PARAMS_LABEL);
+        hasher.update(self.key.to_le_bytes());
+        hasher.update(username);
+        let selector = hasher
+            .finalize()
+            .as_slice()
+            .first()
+            .copied()
+            .unwrap_or_default();
+        self.fallback_params.select(selector).clone()
     }
 }
 
+/// The PBKDF parameter templates sent in place of a stored `sigma` when the user lookup fails
+///
+/// An empty `ParamsString` would reveal that the username is not registered, so the server
+/// sends one of these templates instead, chosen by a hash of the server secret and the
+/// username. A deployment should configure the parameters its users are enrolled with, see
+/// [`AuCPaceServer::with_fallback_params`].
+#[derive(Clone)]
+struct FallbackParams {
+    templates: [ParamsString; MAX_FALLBACK_PARAMS],
+    len: usize,
+}
+
+impl FallbackParams {
+    fn new(templates: &[ParamsString]) -> Result<Self> {
+        if templates.is_empty()
+            || templates.len() > MAX_FALLBACK_PARAMS
+            || templates.iter().any(ParamsString::is_empty)
+        {
+            return Err(Error::InvalidFallbackParams);
+        }
+
+        let mut fallback = Self {
+            templates: core::array::from_fn(|_| ParamsString::default()),
+            len: templates.len(),
+        };
+        fallback.templates[..templates.len()].clone_from_slice(templates);
+        Ok(fallback)
+    }
+
+    /// The template at `selector` modulo the number of templates
+    fn select(&self, selector: u8) -> &ParamsString {
+        &self.templates[usize::from(selector) % self.len]
+    }
+
+    /// The recommended `scrypt` parameters, `scrypt::Params::recommended()`, which the default
+    /// `Client` registers with
+    fn recommended() -> Result<Self> {
+        let mut params = ParamsString::new();
+        for (name, value) in [("ln", 17), ("r", 8), ("p", 1)] {
+            params
+                .add_decimal(name, value)
+                .map_err(|_| Error::InternalInvariant)?;
+        }
+        Self::new(&[params])
+    }
+}
+
+/// The label the server secret is hashed with to choose an unknown user's PBKDF parameters
+const FALLBACK_PARAMS_LABEL: &[u8] = b"AuCPace fallback params";
+
+/// The name of the group the protocol runs in, sent in the augmentation info
+const GROUP: &str = "ristretto255";
+
+/// The label client public keys are hashed with before they are recorded in a
+/// [`ReplayGuard`](crate::ReplayGuard)
+#[cfg(feature = "std")]
+const CLIENT_PUBKEY_LABEL: &[u8] = b"AuCPace client public key";
+
 /// Implementation of the server side of the `AuCPace` protocol
-pub struct AuCPaceServer<D, CSPRNG, const K1: usize>
+pub struct AuCPaceServer<D, CSPRNG, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest + Default,
     CSPRNG: TryRngCore + TryCryptoRng,
+    S: HashScheme,
 {
     /// The CSPRNG used to generate random values where needed
     rng: CSPRNG,
@@ -51,24 +155,111 @@ where
     /// the secret used to obscure when a password lookup failed
     secret: ServerSecret,
 
+    /// the caller provided private key `x` to use in place of ephemeral keys, if any
+    augmentation_key: Option<SecretScalar>,
+
     d: PhantomData<D>,
+    scheme: PhantomData<S>,
 }
 
-impl<D, CSPRNG, const K1: usize> AuCPaceServer<D, CSPRNG, K1>
+impl<D, CSPRNG, const K1: usize, S> AuCPaceServer<D, CSPRNG, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
     CSPRNG: TryRngCore + TryCryptoRng,
+    S: HashScheme,
 {
     /// Create a new server
+    ///
+    /// Fails with [`Error::RngInit`](Error::RngInit) if the RNG fails to generate the server's
+    /// secret, draws in later steps fail with [`Error::Rng`](Error::Rng) instead.
     pub fn new(mut rng: CSPRNG) -> Result<Self> {
         let secret = ServerSecret::new(&mut rng)?;
         Ok(Self {
             rng,
             secret,
+            augmentation_key: None,
             d: PhantomData,
+            scheme: PhantomData,
         })
     }
 
+    /// Create a new server which uses the given private key `x` in the augmentation layer
+    ///
+    /// Instead of generating an ephemeral keypair `(x, X)` for every session, every session
+    /// uses `x` and `X = B^(x * c_J)`, e.g. for deterministic tests or when `x` comes from
+    /// secure hardware. This gives up the forward secrecy of ephemeral keys, like partial
+    /// augmentation. The partial augmentation methods still use the keys from the database.
+    ///
+    /// # Arguments:
+    /// - `rng`: the CSPRNG used to generate the nonces and other random values
+    /// - `x`: the private key to use in every session
+    ///
+    /// # Return:
+    /// - Ok(`server`): the server
+    /// - Err([`Error::ZeroScalar`](Error::ZeroScalar)): `x` is zero, so `X` would be the identity
+    /// - Err([`Error::RngInit`](Error::RngInit)): the RNG failed
+    ///
+    pub fn with_secret(mut rng: CSPRNG, x: SecretScalar) -> Result<Self> {
+        if bool::from(x.expose().ct_eq(&Scalar::ZERO)) {
+            return Err(Error::ZeroScalar);
+        }
+
+        let secret = ServerSecret::new(&mut rng)?;
+        Ok(Self {
+            rng,
+            secret,
+            augmentation_key: Some(x),
+            d: PhantomData,
+            scheme: PhantomData,
+        })
+    }
+
+    /// Set the PBKDF parameters sent in place of a stored `sigma` when the user lookup fails
+    ///
+    /// By default the server sends the recommended `scrypt` parameters to unknown users. A
+    /// deployment whose users are enrolled with other parameters, e.g. with `argon2`, should
+    /// set those here, otherwise the parameters reveal that a username is not registered. With
+    /// several templates, e.g. while users are migrated to new parameters, each unknown
+    /// username is consistently sent one of them, chosen by a hash of the server secret and
+    /// the username.
+    ///
+    /// # Arguments:
+    /// - `templates`: the parameters users are enrolled with, at most
+    ///   [`MAX_FALLBACK_PARAMS`](crate::constants::MAX_FALLBACK_PARAMS) of them
+    ///
+    /// # Return:
+    /// - Ok(`server`): the server, sending one of `templates` to unknown users
+    /// - Err([`Error::InvalidFallbackParams`](Error::InvalidFallbackParams)): `templates` is
+    ///   empty, holds more than `MAX_FALLBACK_PARAMS` templates or holds an empty template
+    ///
+    pub fn with_fallback_params(mut self, templates: &[ParamsString]) -> Result<Self> {
+        self.secret.fallback_params = FallbackParams::new(templates)?;
+        Ok(self)
+    }
+
+    /// The name of the group the server runs the protocol in, e.g. for logging
+    ///
+    /// This is the group sent to clients in [`AugmentationInfo`](ServerMessage::AugmentationInfo),
+    /// currently always `"ristretto255"`.
+    pub const fn group(&self) -> &'static str {
+        GROUP
+    }
+
+    /// The name of the digest the server's hash oracles use, e.g. `"SHA-512"`
+    pub const fn digest_name(&self) -> &'static str
+    where
+        D: DigestName,
+    {
+        D::DIGEST_NAME
+    }
+
+    /// A per-session copy of the caller provided private key, if any
+    fn session_augmentation_key(&self) -> Option<SecretScalar> {
+        self.augmentation_key
+            .as_ref()
+            .map(|x| SecretScalar::new(*x.expose()))
+    }
+
     /// Create a new server in the SSID agreement phase
     ///
     /// # Return:
@@ -79,10 +270,14 @@ where
     pub fn begin(
         &mut self,
     ) -> Result<(
-        AuCPaceServerSsidEstablish<D, K1>,
+        AuCPaceServerSsidEstablish<D, K1, S>,
         ServerMessage<'static, K1>,
     )> {
-        let next_step = AuCPaceServerSsidEstablish::new(self.secret.clone(), &mut self.rng)?;
+        let next_step = AuCPaceServerSsidEstablish::new(
+            self.secret.clone(),
+            self.session_augmentation_key(),
+            &mut self.rng,
+        )?;
         let message = ServerMessage::Nonce(next_step.nonce);
         Ok((next_step, message))
     }
@@ -93,23 +288,45 @@ where
     /// `ssid`: Some data to be hashed and act as the sub-session ID
     ///
     /// # Return:
-    /// - Ok([`next_step`](AuCPaceServerAugLayer)): the server in the SSID establishment stage
-    /// - Err([`Error::InsecureSsid`](Error::InsecureSsid)): the SSID provided was not long enough to be secure
+    /// [`next_step`](AuCPaceServerAugLayer): the server in the augmentation layer
     ///
-    pub fn begin_prestablished_ssid<S>(&mut self, ssid: S) -> Result<AuCPaceServerAugLayer<D, K1>>
-    where
-        S: AsRef<[u8]>,
-    {
-        // if the SSID isn't long enough return an error
-        if ssid.as_ref().len() < MIN_SSID_LEN {
-            return Err(Error::InsecureSsid);
-        }
-
+    #[must_use]
+    pub fn begin_prestablished_ssid(&mut self, ssid: Ssid<'_>) -> AuCPaceServerAugLayer<D, K1, S> {
         // hash the SSID and begin the next step
-        let mut hasher: D = H0();
+        let mut hasher: D = H0::<D, S>();
         hasher.update(ssid);
         let ssid_hash = hasher.finalize();
-        let next_step = AuCPaceServerAugLayer::new(self.secret.clone(), ssid_hash);
+        AuCPaceServerAugLayer::new(
+            self.secret.clone(),
+            self.session_augmentation_key(),
+            ssid_hash,
+        )
+    }
+
+    /// Create a new server in the Augmentation layer phase, provided an SSID, rejecting an
+    /// SSID which was used recently
+    ///
+    /// Like [`begin_prestablished_ssid`](Self::begin_prestablished_ssid), but the SSID is first
+    /// checked against and recorded in `guard`, so that a replayed handshake is rejected.
+    ///
+    /// # Arguments:
+    /// - `ssid`: Some data to be hashed and act as the sub-session ID
+    /// - `guard`: the SSIDs used recently
+    ///
+    /// # Return:
+    /// - Ok([`next_step`](AuCPaceServerAugLayer)): the server in the augmentation layer
+    /// - Err([`Error::ReplayDetected`](Error::ReplayDetected)): the SSID was used within the
+    ///   guard's window
+    /// - Err([`Error::ReplayGuardFull`](Error::ReplayGuardFull)): the guard cannot record the SSID
+    ///
+    #[cfg(feature = "std")]
+    pub fn begin_prestablished_ssid_guarded(
+        &mut self,
+        ssid: Ssid<'_>,
+        guard: &mut crate::ReplayGuard<D>,
+    ) -> Result<AuCPaceServerAugLayer<D, K1, S>> {
+        let next_step = self.begin_prestablished_ssid(ssid);
+        guard.check_and_record(&next_step.ssid)?;
         Ok(next_step)
     }
 
@@ -131,31 +348,48 @@ where
 
 /// Server in the SSID agreement phase
 #[derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
-pub struct AuCPaceServerSsidEstablish<D, const K1: usize>
+pub struct AuCPaceServerSsidEstablish<D, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     #[zeroize(skip)]
     secret: ServerSecret,
+    augmentation_key: Option<SecretScalar>,
     nonce: [u8; K1],
     _d: PhantomData<D>,
+    scheme: PhantomData<S>,
 }
 
-impl<D, const K1: usize> AuCPaceServerSsidEstablish<D, K1>
+impl<D, const K1: usize, S> AuCPaceServerSsidEstablish<D, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
-    fn new<CSPRNG>(secret: ServerSecret, rng: &mut CSPRNG) -> Result<Self>
+    fn new<CSPRNG>(
+        secret: ServerSecret,
+        augmentation_key: Option<SecretScalar>,
+        rng: &mut CSPRNG,
+    ) -> Result<Self>
     where
         CSPRNG: TryRngCore + TryCryptoRng,
     {
         Ok(Self {
             secret,
+            augmentation_key,
             nonce: generate_nonce(rng)?,
             _d: PhantomData,
+            scheme: PhantomData,
         })
     }
 
+    /// Abandon the handshake, e.g. because the client disconnected
+    ///
+    /// This zeroizes the step's secrets now rather than whenever it would otherwise be dropped.
+    pub fn abort(mut self) {
+        self.zeroize();
+    }
+
     /// Consume the client's nonce - `t` and progress to the augmentation layer
     ///
     /// # arguments:
@@ -165,30 +399,75 @@ where
     /// [`next_step`](AuCPaceServerAugLayer): the server in the augmentation layer
     ///
     #[must_use]
-    pub fn agree_ssid(self, client_nonce: [u8; K1]) -> AuCPaceServerAugLayer<D, K1> {
-        let ssid = compute_ssid::<D, K1>(self.nonce, client_nonce);
-        AuCPaceServerAugLayer::new(self.secret.clone(), ssid)
+    pub fn agree_ssid(mut self, client_nonce: [u8; K1]) -> AuCPaceServerAugLayer<D, K1, S> {
+        let ssid = compute_ssid_with_scheme::<D, S, K1>(self.nonce, client_nonce);
+        AuCPaceServerAugLayer::new(self.secret.clone(), self.augmentation_key.take(), ssid)
     }
 }
 
 /// Server in the Augmentation layer phase
 #[derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
-pub struct AuCPaceServerAugLayer<D, const K1: usize>
+pub struct AuCPaceServerAugLayer<D, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     #[zeroize(skip)]
     secret: ServerSecret,
+    augmentation_key: Option<SecretScalar>,
     #[zeroize(skip)]
     ssid: Output<D>,
+    scheme: PhantomData<S>,
 }
 
-impl<D, const K1: usize> AuCPaceServerAugLayer<D, K1>
+impl<D, const K1: usize, S> AuCPaceServerAugLayer<D, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
-    const fn new(secret: ServerSecret, ssid: Output<D>) -> Self {
-        Self { secret, ssid }
+    const fn new(
+        secret: ServerSecret,
+        augmentation_key: Option<SecretScalar>,
+        ssid: Output<D>,
+    ) -> Self {
+        Self {
+            secret,
+            augmentation_key,
+            ssid,
+            scheme: PhantomData,
+        }
+    }
+
+    /// Abandon the handshake, e.g. because the client disconnected
+    ///
+    /// This zeroizes the step's secrets now rather than whenever it would otherwise be dropped.
+    pub fn abort(mut self) {
+        self.zeroize();
+    }
+
+    /// Check the tag confirming the client's SSID against the server's SSID, in constant time
+    fn verify_ssid_tag(&self, ssid_tag: &[u8; 64]) -> Result<()> {
+        let expected = compute_ssid_tag::<D, S>(self.ssid);
+        if bool::from(expected.as_slice().ct_eq(ssid_tag)) {
+            Ok(())
+        } else {
+            Err(Error::SsidMismatch)
+        }
+    }
+
+    /// The keypair `(x, X)` for this session: the server's fixed key if it was given one,
+    /// otherwise a fresh ephemeral keypair
+    fn augmentation_keypair<CSPRNG>(&self, rng: &mut CSPRNG) -> Result<(Scalar, RistrettoPoint)>
+    where
+        CSPRNG: TryRngCore + TryCryptoRng,
+    {
+        match &self.augmentation_key {
+            Some(x) => {
+                let x = *x.expose();
+                Ok((x, basepoint_mul(&(x * ristretto_cofactor()))))
+            }
+            None => generate_server_keypair::<D, _>(rng),
+        }
     }
 
     /// Accept the user's username and generate the `ClientInfo` for the response.
@@ -196,6 +475,7 @@ where
     ///
     /// # Arguments:
     /// - `username`: the client's username
+    /// - `ssid_tag`: the tag confirming the client's SSID, sent with its username
     /// - `database`: the password verifier database to retrieve the client's information from
     ///
     /// # Return:
@@ -203,27 +483,87 @@ where
     /// - [`next_step`](AuCPaceServerCPaceSubstep): the server in the `CPace` substep stage
     /// - [`message`](ServerMessage::AugmentationInfo): the message to send to the client
     ///
+    /// Errors are returned as the store's [`Error`](Database::Error): an error reported by
+    /// [`Database::try_lookup_verifier`] is returned as is, so that an outage can be told
+    /// apart from the protocol's own errors. An unknown user is not an error, while a tag
+    /// which does not match the server's SSID is [`Error::SsidMismatch`], checked before the
+    /// database is consulted.
+    ///
+    #[allow(clippy::type_complexity)]
     pub fn generate_client_info<U, DB, CSPRNG>(
         self,
         username: U,
+        ssid_tag: [u8; 64],
+        database: &DB,
+        rng: CSPRNG,
+    ) -> core::result::Result<
+        (
+            AuCPaceServerCPaceSubstep<D, CSPRNG, K1, S>,
+            ServerMessage<'static, K1>,
+        ),
+        DB::Error,
+    >
+    where
+        U: AsRef<[u8]>,
+        DB: Database<PasswordVerifier = RistrettoPoint>,
+        CSPRNG: TryRngCore + TryCryptoRng,
+    {
+        let (next_step, message, _) =
+            self.generate_client_info_metered(username, ssid_tag, database, rng)?;
+        Ok((next_step, message))
+    }
+
+    /// Accept the user's username and generate the `ClientInfo` for the response, also
+    /// reporting whether the user was unknown.
+    /// Moves the protocol into the `CPace` substep phase
+    ///
+    /// This is [`generate_client_info`](Self::generate_client_info) for servers which meter how
+    /// often the enumeration resistant fallback response is sent. The message is the same
+    /// either way, the flag is for the server's own metrics and must never be sent to the client.
+    ///
+    /// # Arguments:
+    /// - `username`: the client's username
+    /// - `ssid_tag`: the tag confirming the client's SSID, sent with its username
+    /// - `database`: the password verifier database to retrieve the client's information from
+    ///
+    /// # Return:
+    /// ([`next_step`](AuCPaceServerCPaceSubstep), [`message`](ServerMessage::AugmentationInfo), `used_fallback`)
+    /// - [`next_step`](AuCPaceServerCPaceSubstep): the server in the `CPace` substep stage
+    /// - [`message`](ServerMessage::AugmentationInfo): the message to send to the client
+    /// - `used_fallback`: `true` if the user was not found and the fallback response was sent
+    ///
+    /// Errors are returned as the store's [`Error`](Database::Error), as for
+    /// [`generate_client_info`](Self::generate_client_info).
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn generate_client_info_metered<U, DB, CSPRNG>(
+        self,
+        username: U,
+        ssid_tag: [u8; 64],
         database: &DB,
         mut rng: CSPRNG,
-    ) -> Result<(
-        AuCPaceServerCPaceSubstep<D, CSPRNG, K1>,
-        ServerMessage<'static, K1>,
-    )>
+    ) -> core::result::Result<
+        (
+            AuCPaceServerCPaceSubstep<D, CSPRNG, K1, S>,
+            ServerMessage<'static, K1>,
+            bool,
+        ),
+        DB::Error,
+    >
     where
         U: AsRef<[u8]>,
         DB: Database<PasswordVerifier = RistrettoPoint>,
         CSPRNG: TryRngCore + TryCryptoRng,
     {
-        let (x, x_pub) = generate_server_keypair::<D, _>(&mut rng)?;
+        self.verify_ssid_tag(&ssid_tag)?;
+        let (x, x_pub) = self.augmentation_keypair(&mut rng)?;
 
         // generate the prs and client message
-        let (prs, message) = self.generate_prs(username.as_ref(), database, &mut rng, x, x_pub)?;
+        let (prs, message, used_fallback) =
+            self.generate_prs(username.as_ref(), database, &mut rng, x, x_pub)?;
         let next_step = AuCPaceServerCPaceSubstep::new(self.ssid, prs, rng);
 
-        Ok((next_step, message))
+        Ok((next_step, message, used_fallback))
     }
 
     /// Accept the user's username and generate the `ClientInfo` for the response.
@@ -238,42 +578,53 @@ where
     ///
     /// # Arguments:
     /// - `username`: the client's username
+    /// - `ssid_tag`: the tag confirming the client's SSID, sent with its username
     /// - `database`: the password verifier database to retrieve the client's information from
-    ///    This is a `PartialAugDatabase` so we can lookup the server's long term keypair.
+    ///   This is a `PartialAugDatabase` so we can lookup the server's long term keypair.
     ///
     /// # Return:
     /// ([`next_step`](AuCPaceServerCPaceSubstep), [`message`](ServerMessage::AugmentationInfo))
     /// - [`next_step`](AuCPaceServerCPaceSubstep): the server in the `CPace` substep stage
     /// - [`message`](ServerMessage::AugmentationInfo): the message to send to the client
     ///
+    /// Errors are returned as the store's [`Error`](Database::Error), as for
+    /// [`generate_client_info`](Self::generate_client_info).
+    ///
     #[cfg(feature = "partial_augmentation")]
+    #[allow(clippy::type_complexity)]
     pub fn generate_client_info_partial_aug<U, DB, CSPRNG>(
         self,
         username: U,
+        ssid_tag: [u8; 64],
         database: &DB,
         mut rng: CSPRNG,
-    ) -> Result<(
-        AuCPaceServerCPaceSubstep<D, CSPRNG, K1>,
-        ServerMessage<'static, K1>,
-    )>
+    ) -> core::result::Result<
+        (
+            AuCPaceServerCPaceSubstep<D, CSPRNG, K1, S>,
+            ServerMessage<'static, K1>,
+        ),
+        <DB as Database>::Error,
+    >
     where
         U: AsRef<[u8]>,
         DB: Database<PasswordVerifier = RistrettoPoint>
             + PartialAugDatabase<PrivateKey = Scalar, PublicKey = RistrettoPoint>,
         CSPRNG: TryRngCore + TryCryptoRng,
     {
+        self.verify_ssid_tag(&ssid_tag)?;
         let user = username.as_ref();
         let (prs, message) = if let Some((x, x_pub)) = database.lookup_long_term_keypair(user) {
             // generate the prs and client message
-            self.generate_prs(user, database, &mut rng, x, x_pub)?
+            let (prs, message, _) = self.generate_prs(user, database, &mut rng, x, x_pub)?;
+            (prs, message)
         } else {
             // if the user does not have a keypair stored then we generate a random point on the
             // curve to be the public key, and handle the failed lookup as normal
             let x_pub = {
                 let mut seed = [0u8; 32];
                 rng.try_fill_bytes(&mut seed).map_err(|_| Error::Rng)?;
-                let mut hasher: D = crate::utils::H1();
-                hasher.update(&seed);
+                let mut hasher: D = crate::utils::H1::<D, S>();
+                hasher.update(seed);
                 RistrettoPoint::from_hash(hasher)
             };
             self.lookup_failed(user, x_pub, &mut rng)?
@@ -292,9 +643,10 @@ where
     ///
     /// # Arguments:
     /// - `username`: the client's username
+    /// - `ssid_tag`: the tag confirming the client's SSID, sent with its username
     /// - `blinded`: the client's blinded point `U`
     /// - `database`: the password verifier database to retrieve the client's information from
-    ///    This is a `PartialAugDatabase` so we can lookup the server's long term keypair.
+    ///   This is a `PartialAugDatabase` so we can lookup the server's long term keypair.
     ///
     /// # Return:
     /// ([`next_step`](AuCPaceServerCPaceSubstep), [`message`](ServerMessage::AugmentationInfo))
@@ -305,11 +657,12 @@ where
     pub fn generate_client_info_strong<U, DB, CSPRNG>(
         self,
         username: U,
+        ssid_tag: [u8; 64],
         blinded: RistrettoPoint,
         database: &DB,
         mut rng: CSPRNG,
     ) -> Result<(
-        AuCPaceServerCPaceSubstep<D, CSPRNG, K1>,
+        AuCPaceServerCPaceSubstep<D, CSPRNG, K1, S>,
         ServerMessage<'static, K1>,
     )>
     where
@@ -317,7 +670,13 @@ where
         DB: StrongDatabase<PasswordVerifier = RistrettoPoint, Exponent = Scalar>,
         CSPRNG: TryRngCore + TryCryptoRng,
     {
-        let (x, x_pub) = generate_server_keypair::<D, _>(&mut rng)?;
+        // reject malformed input before touching the database
+        self.verify_ssid_tag(&ssid_tag)?;
+        if blinded.is_identity() {
+            return Err(Error::IllegalPointError);
+        }
+
+        let (x, x_pub) = self.augmentation_keypair(&mut rng)?;
 
         // generate the prs and client message
         let (prs, message) =
@@ -337,9 +696,10 @@ where
     ///
     /// # Arguments:
     /// - `username`: the client's username
+    /// - `ssid_tag`: the tag confirming the client's SSID, sent with its username
     /// - `blinded`: the client's blinded point `U`
     /// - `database`: the password verifier database to retrieve the client's information from
-    ///    This is a `PartialAugDatabase` so we can lookup the server's long term keypair.
+    ///   This is a `PartialAugDatabase` so we can lookup the server's long term keypair.
     ///
     /// # Return:
     /// ([`next_step`](AuCPaceServerCPaceSubstep), [`message`](ServerMessage::AugmentationInfo))
@@ -350,11 +710,12 @@ where
     pub fn generate_client_info_partial_strong<U, DB, CSPRNG>(
         self,
         username: U,
+        ssid_tag: [u8; 64],
         blinded: RistrettoPoint,
         database: &DB,
         mut rng: CSPRNG,
     ) -> Result<(
-        AuCPaceServerCPaceSubstep<D, CSPRNG, K1>,
+        AuCPaceServerCPaceSubstep<D, CSPRNG, K1, S>,
         ServerMessage<'static, K1>,
     )>
     where
@@ -363,6 +724,12 @@ where
             + PartialAugDatabase<PrivateKey = Scalar, PublicKey = RistrettoPoint>,
         CSPRNG: TryRngCore + TryCryptoRng,
     {
+        // reject malformed input before touching the database
+        self.verify_ssid_tag(&ssid_tag)?;
+        if blinded.is_identity() {
+            return Err(Error::IllegalPointError);
+        }
+
         let user = username.as_ref();
         let (prs, message) = if let Some((x, x_pub)) = database.lookup_long_term_keypair(user) {
             // generate the prs and client message
@@ -373,8 +740,8 @@ where
             let x_pub = {
                 let mut seed = [0u8; 32];
                 rng.try_fill_bytes(&mut seed).map_err(|_| Error::Rng)?;
-                let mut hasher: D = crate::utils::H1();
-                hasher.update(&seed);
+                let mut hasher: D = crate::utils::H1::<D, S>();
+                hasher.update(seed);
                 RistrettoPoint::from_hash(hasher)
             };
             self.lookup_failed_strong(user, blinded, x_pub, &mut rng)?
@@ -384,7 +751,8 @@ where
         Ok((next_step, message))
     }
 
-    /// Generate the Password Related String (PRS) and the message to be sent to the user.
+    /// Generate the Password Related String (PRS) and the message to be sent to the user,
+    /// and whether the fallback for an unknown user was used.
     fn generate_prs<DB, CSPRNG>(
         &self,
         username: &[u8],
@@ -392,25 +760,24 @@ where
         rng: &mut CSPRNG,
         x: Scalar,
         x_pub: RistrettoPoint,
-    ) -> Result<([u8; 32], ServerMessage<'static, K1>)>
+    ) -> core::result::Result<([u8; 32], ServerMessage<'static, K1>, bool), DB::Error>
     where
         DB: Database<PasswordVerifier = RistrettoPoint>,
         CSPRNG: TryRngCore + TryCryptoRng,
     {
-        if let Some((w, salt, sigma)) = database.lookup_verifier(username.as_ref()) {
-            let cofactor = Scalar::ONE;
-            let prs = (w * x * cofactor).compress().to_bytes();
+        if let Some((w, salt, sigma)) = database.try_lookup_verifier(username.as_ref())? {
+            let prs = server_prs(&w, &x);
             let message = ServerMessage::AugmentationInfo {
-                // this will have to be provided by the trait in future
-                group: "ristretto255",
-                x_pub,
+                group: GROUP,
+                x_pub: CachedPoint::new(x_pub),
                 salt,
                 pbkdf_params: sigma,
             };
-            Ok((prs, message))
+            Ok((prs, message, false))
         } else {
             // handle the failure case
-            self.lookup_failed(username, x_pub, rng)
+            let (prs, message) = self.lookup_failed(username, x_pub, rng)?;
+            Ok((prs, message, true))
         }
     }
 
@@ -431,16 +798,15 @@ where
         CSPRNG: TryRngCore + TryCryptoRng,
     {
         if let Some((w, q, sigma)) = database.lookup_verifier_strong(username.as_ref()) {
-            let cofactor = Scalar::ONE;
-            let prs = (w * (x * cofactor)).compress().to_bytes();
+            let cofactor = ristretto_cofactor();
+            let prs = server_prs(&w, &x);
             let uq = blinded * (q * cofactor);
             if uq.is_identity() {
                 return Err(Error::IllegalPointError);
             }
             let message = ServerMessage::StrongAugmentationInfo {
-                // this will have to be provided by the trait in future
-                group: "ristretto255",
-                x_pub,
+                group: GROUP,
+                x_pub: CachedPoint::new(x_pub),
                 blinded_salt: uq,
                 pbkdf_params: sigma,
             };
@@ -467,23 +833,19 @@ where
             tmp
         };
 
-        // generate the salt from the hash of the server secret and the user's name
-        let mut hasher: D = Default::default();
-        hasher.update(self.secret.0.to_le_bytes());
-        hasher.update(username);
-        let hash = hasher.finalize();
-        let hash_bytes: &[u8] = hash.as_ref();
-
-        // It is okay to expect here because SaltString has a buffer of 64 bytes by requirement
-        // from the PHC spec. 48 bytes of data when encoded as base64 transform to 64 bytes.
-        // This gives us the most entropy possible from the hash in the SaltString.
-        let salt = SaltString::encode_b64(&hash_bytes[..48]).map_err(Error::PasswordHashing)?;
+        // generate the salt from the hash of the server secret and the user's name, it is as
+        // long as the salts users are enrolled with so that its length reveals nothing
+        let hash = self.secret.fallback_hasher::<D>(username).finalize();
+        let salt = hash
+            .get(..Salt::RECOMMENDED_LENGTH)
+            .ok_or(Error::InternalInvariant)?;
+        let salt = SaltString::encode_b64(salt).map_err(Error::PasswordHashing)?;
 
         let message = ServerMessage::AugmentationInfo {
-            group: "ristretto255",
-            x_pub,
+            group: GROUP,
+            x_pub: CachedPoint::new(x_pub),
             salt,
-            pbkdf_params: ParamsString::default(),
+            pbkdf_params: self.secret.fallback_params::<D>(username),
         };
 
         Ok((prs, message))
@@ -508,10 +870,8 @@ where
         };
 
         // generate q from the hash of the username and the server secret
-        let mut hasher: D = Default::default();
-        hasher.update(self.secret.0.to_le_bytes());
-        hasher.update(username);
-        let cofactor = Scalar::ONE;
+        let hasher: D = self.secret.fallback_hasher(username);
+        let cofactor = ristretto_cofactor();
         let q = Scalar::from_hash(hasher);
         let fake_blinded_salt = blinded * (q * cofactor);
 
@@ -521,10 +881,10 @@ where
         }
 
         let message = ServerMessage::StrongAugmentationInfo {
-            group: "ristretto255",
-            x_pub,
+            group: GROUP,
+            x_pub: CachedPoint::new(x_pub),
             blinded_salt: fake_blinded_salt,
-            pbkdf_params: ParamsString::default(),
+            pbkdf_params: self.secret.fallback_params::<D>(username),
         };
 
         Ok((prs, message))
@@ -533,25 +893,40 @@ where
 
 /// Server in the `CPace` substep phase
 #[derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
-pub struct AuCPaceServerCPaceSubstep<D, CSPRNG, const K1: usize>
+pub struct AuCPaceServerCPaceSubstep<D, CSPRNG, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
     CSPRNG: TryRngCore + TryCryptoRng,
+    S: HashScheme,
 {
     #[zeroize(skip)]
     ssid: Output<D>,
     prs: [u8; 32],
     #[zeroize(skip)]
     rng: CSPRNG,
+    scheme: PhantomData<S>,
 }
 
-impl<D, CSPRNG, const K1: usize> AuCPaceServerCPaceSubstep<D, CSPRNG, K1>
+impl<D, CSPRNG, const K1: usize, S> AuCPaceServerCPaceSubstep<D, CSPRNG, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
     CSPRNG: TryRngCore + TryCryptoRng,
+    S: HashScheme,
 {
     const fn new(ssid: Output<D>, prs: [u8; 32], rng: CSPRNG) -> Self {
-        Self { ssid, prs, rng }
+        Self {
+            ssid,
+            prs,
+            rng,
+            scheme: PhantomData,
+        }
+    }
+
+    /// Abandon the handshake, e.g. because the client disconnected
+    ///
+    /// This zeroizes the step's secrets now rather than whenever it would otherwise be dropped.
+    pub fn abort(mut self) {
+        self.zeroize();
     }
 
     /// Generate a public key
@@ -559,8 +934,8 @@ where
     ///
     /// # Arguments:
     /// - `channel_identifier` - `CI` from the protocol definition, in the context of TCP/IP this
-    ///     is usually some combination of the server and client's IP address and TCP port numbers.
-    ///     It's purpose is to prevent relay attacks.
+    ///   is usually some combination of the server and client's IP address and TCP port numbers.
+    ///   It's purpose is to prevent relay attacks.
     /// - `rng` - the CSPRNG used when generating the public/private keypair
     ///
     /// # Return:
@@ -572,13 +947,13 @@ where
         mut self,
         channel_identifier: CI,
     ) -> Result<(
-        AuCPaceServerRecvClientKey<D, K1>,
+        AuCPaceServerRecvClientKey<D, K1, S>,
         ServerMessage<'static, K1>,
     )> {
-        let (priv_key, pub_key) = generate_keypair::<D, CSPRNG, CI>(
+        let (priv_key, pub_key) = generate_keypair_with_scheme::<D, S, CSPRNG, CI>(
             &mut self.rng,
             self.ssid,
-            self.prs,
+            &self.prs,
             channel_identifier,
         )?;
 
@@ -591,21 +966,35 @@ where
 
 /// Server in the `CPace` substep phase
 #[derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
-pub struct AuCPaceServerRecvClientKey<D, const K1: usize>
+pub struct AuCPaceServerRecvClientKey<D, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     #[zeroize(skip)]
     ssid: Output<D>,
     priv_key: Scalar,
+    scheme: PhantomData<S>,
 }
 
-impl<D, const K1: usize> AuCPaceServerRecvClientKey<D, K1>
+impl<D, const K1: usize, S> AuCPaceServerRecvClientKey<D, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     const fn new(ssid: Output<D>, priv_key: Scalar) -> Self {
-        Self { ssid, priv_key }
+        Self {
+            ssid,
+            priv_key,
+            scheme: PhantomData,
+        }
+    }
+
+    /// Abandon the handshake, e.g. because the client disconnected
+    ///
+    /// This zeroizes the step's secrets now rather than whenever it would otherwise be dropped.
+    pub fn abort(mut self) {
+        self.zeroize();
     }
 
     /// Receive the client's public key
@@ -620,16 +1009,59 @@ where
     pub fn receive_client_pubkey(
         self,
         client_pubkey: RistrettoPoint,
-    ) -> Result<AuCPaceServerExpMutAuth<D, K1>> {
+    ) -> Result<AuCPaceServerExpMutAuth<D, K1, S>> {
         // check for the neutral point
         if client_pubkey.is_identity() {
             return Err(Error::IllegalPointError);
         }
 
-        let sk1 = compute_first_session_key::<D>(self.ssid, self.priv_key, client_pubkey);
+        let sk1 =
+            compute_first_session_key_with_scheme::<D, S>(self.ssid, self.priv_key, client_pubkey);
         Ok(AuCPaceServerExpMutAuth::new(self.ssid, sk1))
     }
 
+    /// Receive the client's public key, rejecting a key which was used recently
+    ///
+    /// Like [`receive_client_pubkey`](Self::receive_client_pubkey), but the public key is first
+    /// checked against and recorded in `guard`. A client reusing its ephemeral key across
+    /// sessions loses the forward secrecy of `CPace`, this detects exact reuse within the
+    /// guard's window. The keys are hashed with a label of their own, so the guard can also be
+    /// used for SSIDs.
+    ///
+    /// # Arguments:
+    /// - `client_pubkey` - the client's public key
+    /// - `guard`: the client public keys used recently
+    ///
+    /// # Return:
+    /// - Ok([`next_step`](AuCPaceServerExpMutAuth)): the server in the Explicit Mutual
+    ///   Authentication phase
+    /// - Err([`Error::EphemeralReuse`](Error::EphemeralReuse)): the public key was used within
+    ///   the guard's window
+    /// - Err([`Error::ReplayGuardFull`](Error::ReplayGuardFull)): the guard cannot record the
+    ///   public key
+    ///
+    #[cfg(feature = "std")]
+    pub fn receive_client_pubkey_guarded(
+        self,
+        client_pubkey: RistrettoPoint,
+        guard: &mut crate::ReplayGuard<D>,
+    ) -> Result<AuCPaceServerExpMutAuth<D, K1, S>> {
+        // an illegal point is rejected without recording it
+        if client_pubkey.is_identity() {
+            return Err(Error::IllegalPointError);
+        }
+
+        let key_hash = D::default()
+            .chain_update(CLIENT_PUBKEY_LABEL)
+            .chain_update(client_pubkey.compress().as_bytes())
+            .finalize();
+        guard.check_and_record(&key_hash).map_err(|err| match err {
+            Error::ReplayDetected => Error::EphemeralReuse,
+            other => other,
+        })?;
+        self.receive_client_pubkey(client_pubkey)
+    }
+
     /// Allow exiting the protocol early in the case of implicit authentication
     /// Note: this should only be used in special circumstances and the
     ///       explicit mutual authentication stage should be used in all other cases
@@ -649,46 +1081,94 @@ where
             return Err(Error::IllegalPointError);
         }
 
-        let sk1 = compute_first_session_key::<D>(self.ssid, self.priv_key, client_pubkey);
+        let sk1 =
+            compute_first_session_key_with_scheme::<D, S>(self.ssid, self.priv_key, client_pubkey);
         Ok(secret_utils::wrappers::SecretKey::from(
-            compute_session_key::<D>(self.ssid, sk1).as_slice().to_vec(),
+            compute_session_key_with_scheme::<D, S>(self.ssid, sk1)
+                .as_slice()
+                .to_vec(),
         ))
     }
 }
 
 /// Server in the Explicity Mutual Authenticaton phase
-#[derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
-pub struct AuCPaceServerExpMutAuth<D, const K1: usize>
+///
+/// The session key `sk1` is zeroized when the step is dropped.
+pub struct AuCPaceServerExpMutAuth<D, const K1: usize, S = LeU32HashScheme>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
-    #[zeroize(skip)]
     ssid: Output<D>,
-    #[zeroize(skip)]
     sk1: Output<D>,
+    scheme: PhantomData<S>,
 }
 
-impl<D, const K1: usize> AuCPaceServerExpMutAuth<D, K1>
+impl<D, const K1: usize, S> Drop for AuCPaceServerExpMutAuth<D, K1, S>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
+{
+    fn drop(&mut self) {
+        self.zeroize_secrets();
+    }
+}
+
+impl<D, const K1: usize, S> zeroize::ZeroizeOnDrop for AuCPaceServerExpMutAuth<D, K1, S>
+where
+    D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
+{
+}
+
+impl<D, const K1: usize, S> AuCPaceServerExpMutAuth<D, K1, S>
+where
+    D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     const fn new(ssid: Output<D>, sk1: Output<D>) -> Self {
-        Self { ssid, sk1 }
+        Self {
+            ssid,
+            sk1,
+            scheme: PhantomData,
+        }
+    }
+
+    /// Abandon the handshake, e.g. because the client disconnected
+    ///
+    /// This drops the step, zeroizing `sk1`, so it cannot be used afterwards:
+    ///
+    /// ```compile_fail,E0382
+    /// # use aucpace_conflux::server::AuCPaceServerExpMutAuth;
+    /// fn abandon(server: AuCPaceServerExpMutAuth<sha2::Sha512, 16>) {
+    ///     server.abort();
+    ///     let _ = server.receive_client_authenticator([0u8; 64]);
+    /// }
+    /// ```
+    pub fn abort(self) {
+        drop(self);
+    }
+
+    /// Zeroize the session key `sk1`, the SSID is public
+    fn zeroize_secrets(&mut self) {
+        self.sk1.as_mut_slice().zeroize();
     }
 
-    /// Receive the server's authenticator.
+    /// Receive the client's authenticator.
     /// This completes the protocol and returns the derived key.
     ///
     /// # Arguments:
-    /// - `server_authenticator` - the server's authenticator
+    /// - `client_authenticator` - the client's authenticator `Tb`
     ///
     /// # Return:
     /// either:
     /// - Ok((`sk`, `message`)):
     ///     - `sk` - the session key reached by the `AuCPace` protocol
-    ///     - [`message`](ServerMessage::Authenticator) - the message to send to the client
-    /// - Err([`Error::MutualAuthFail`](Error::MutualAuthFail)): an error if the authenticator we computed doesn't match
-    ///     the client's authenticator, compared in constant time.
+    ///     - [`message`](ServerMessage::Authenticator) - the message to send to the client,
+    ///       carrying the server's key confirmation authenticator `Ta`
+    /// - Err([`Error::KeyConfirmationFailed`](Error::KeyConfirmationFailed)): an error if the
+    ///   authenticator we computed doesn't match the client's authenticator, compared in
+    ///   constant time.
     ///
     pub fn receive_client_authenticator(
         self,
@@ -697,26 +1177,120 @@ where
         secret_utils::wrappers::SecretKey,
         ServerMessage<'static, K1>,
     )> {
-        let (ta, tb) = compute_authenticator_messages::<D>(self.ssid, self.sk1);
-        if tb.ct_eq(&client_authenticator).into() {
-            let sk = compute_session_key::<D>(self.ssid, self.sk1);
-            let ta_arr = ta
-                .as_slice()
-                .try_into()
-                .map_err(|_| Error::HashSizeInvalid)?;
-            let message = ServerMessage::Authenticator(ta_arr);
-            Ok((
-                secret_utils::wrappers::SecretKey::from(sk.as_slice().to_vec()),
-                message,
-            ))
-        } else {
-            Err(Error::MutualAuthFail)
+        let (ta, sk) = verify_session_key_confirmation_with_scheme::<D, S>(
+            self.ssid,
+            self.sk1,
+            client_authenticator,
+        )?;
+        Ok((sk, ServerMessage::Authenticator(ta)))
+    }
+}
+
+/// A point sent in a [`ServerMessage`], together with its compressed encoding
+///
+/// The point is compressed once, when the message is built, and serializing the message
+/// copies the stored encoding rather than compressing the point again, e.g. when a server
+/// resends the same message on retries. It is serialized exactly like a `RistrettoPoint`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CachedPoint {
+    point: RistrettoPoint,
+    compressed: CompressedRistretto,
+}
+
+impl CachedPoint {
+    /// Compress `point` and store it alongside its encoding
+    pub fn new(point: RistrettoPoint) -> Self {
+        Self {
+            point,
+            compressed: point.compress(),
         }
     }
+
+    /// The point
+    pub const fn as_point(&self) -> &RistrettoPoint {
+        &self.point
+    }
+
+    /// The point's compressed encoding, as it is sent
+    pub const fn compressed(&self) -> &CompressedRistretto {
+        &self.compressed
+    }
+}
+
+impl From<RistrettoPoint> for CachedPoint {
+    fn from(point: RistrettoPoint) -> Self {
+        Self::new(point)
+    }
+}
+
+impl From<CachedPoint> for RistrettoPoint {
+    fn from(point: CachedPoint) -> Self {
+        point.point
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for CachedPoint {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.compressed.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for CachedPoint {
+    fn deserialize<De>(deserializer: De) -> core::result::Result<Self, De::Error>
+    where
+        De: serde::Deserializer<'de>,
+    {
+        let compressed = CompressedRistretto::deserialize(deserializer)?;
+        let point = compressed
+            .decompress()
+            .ok_or_else(|| serde::de::Error::custom("decompression failed"))?;
+        Ok(Self { point, compressed })
+    }
 }
 
 /// An enum representing the different messages the server can send to the client
-#[derive(Debug)]
+///
+/// This enum is `#[non_exhaustive]` so that new message types can be added without a breaking
+/// change, matches outside this crate must include a wildcard arm:
+///
+/// ```
+/// use aucpace_conflux::ServerMessage;
+///
+/// fn describe(message: &ServerMessage<'_, 16>) -> &'static str {
+///     match message {
+///         ServerMessage::Nonce(_) => "nonce",
+///         ServerMessage::AugmentationInfo { .. } => "augmentation info",
+///         ServerMessage::PublicKey(_) => "public key",
+///         ServerMessage::Authenticator(_) => "authenticator",
+///         _ => "unknown message",
+///     }
+/// }
+/// # let _ = describe;
+/// ```
+///
+/// Without the wildcard the match is rejected as non-exhaustive, even though every message
+/// type is listed:
+///
+/// ```compile_fail,E0004
+/// use aucpace_conflux::ServerMessage;
+///
+/// fn describe(message: &ServerMessage<'_, 16>) -> &'static str {
+///     match message {
+///         ServerMessage::Nonce(_) => "nonce",
+///         ServerMessage::AugmentationInfo { .. } => "augmentation info",
+///         #[cfg(feature = "strong_aucpace")]
+///         ServerMessage::StrongAugmentationInfo { .. } => "strong augmentation info",
+///         ServerMessage::PublicKey(_) => "public key",
+///         ServerMessage::Authenticator(_) => "authenticator",
+///     }
+/// }
+/// ```
+#[non_exhaustive]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ServerMessage<'a, const K1: usize> {
     /// SSID establishment message - the server's nonce: `s`
@@ -728,7 +1302,7 @@ pub enum ServerMessage<'a, const K1: usize> {
         group: &'a str,
 
         /// X from the protocol definition
-        x_pub: RistrettoPoint,
+        x_pub: CachedPoint,
 
         /// the salt used with the PBKDF
         #[cfg_attr(feature = "serde", serde(with = "serde_saltstring"))]
@@ -746,7 +1320,7 @@ pub enum ServerMessage<'a, const K1: usize> {
         group: &'a str,
 
         /// X from the protocol definition
-        x_pub: RistrettoPoint,
+        x_pub: CachedPoint,
 
         /// the blinded salt used with the PBKDF
         blinded_salt: RistrettoPoint,
@@ -763,6 +1337,178 @@ pub enum ServerMessage<'a, const K1: usize> {
     Authenticator(#[cfg_attr(feature = "serde", serde(with = "serde_byte_array"))] [u8; 64]),
 }
 
+/// The number of characters of the PBKDF parameters shown by `ServerMessage`'s `Debug`
+const DEBUG_PARAMS_LEN: usize = 16;
+
+// salts and PBKDF parameters help an offline attack on a stolen database, so `Debug`, which ends
+// up in logs and test panics, redacts the salt and truncates the parameters
+impl<const K1: usize> fmt::Debug for ServerMessage<'_, K1> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Params<'p>(&'p ParamsString);
+
+        impl fmt::Debug for Params<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let params = self.0.as_str();
+                match params.get(..DEBUG_PARAMS_LEN) {
+                    Some(prefix) if prefix.len() < params.len() => write!(f, "{prefix:?}.."),
+                    _ => write!(f, "{params:?}"),
+                }
+            }
+        }
+
+        match self {
+            ServerMessage::Nonce(nonce) => f.debug_tuple("Nonce").field(nonce).finish(),
+            ServerMessage::AugmentationInfo {
+                group,
+                x_pub,
+                salt,
+                pbkdf_params,
+            } => f
+                .debug_struct("AugmentationInfo")
+                .field("group", group)
+                .field("x_pub", x_pub.compressed())
+                .field(
+                    "salt",
+                    &format_args!("[redacted], len={}", salt.as_str().len()),
+                )
+                .field("pbkdf_params", &Params(pbkdf_params))
+                .finish(),
+            #[cfg(feature = "strong_aucpace")]
+            ServerMessage::StrongAugmentationInfo {
+                group,
+                x_pub,
+                pbkdf_params,
+                ..
+            } => f
+                .debug_struct("StrongAugmentationInfo")
+                .field("group", group)
+                .field("x_pub", x_pub.compressed())
+                .field("blinded_salt", &format_args!("[redacted]"))
+                .field("pbkdf_params", &Params(pbkdf_params))
+                .finish(),
+            ServerMessage::PublicKey(public_key) => f
+                .debug_tuple("PublicKey")
+                .field(&public_key.compress())
+                .finish(),
+            ServerMessage::Authenticator(authenticator) => {
+                f.debug_tuple("Authenticator").field(authenticator).finish()
+            }
+        }
+    }
+}
+
+impl<const K1: usize> ServerMessage<'_, K1> {
+    /// The server's augmentation public key `X`, if this is an augmentation info message
+    pub fn x_pub(&self) -> Option<&RistrettoPoint> {
+        match self {
+            ServerMessage::AugmentationInfo { x_pub, .. } => Some(x_pub.as_point()),
+            #[cfg(feature = "strong_aucpace")]
+            ServerMessage::StrongAugmentationInfo { x_pub, .. } => Some(x_pub.as_point()),
+            _ => None,
+        }
+    }
+
+    /// The compressed encoding of the server's augmentation public key `X`, if this is an
+    /// augmentation info message
+    ///
+    /// This is the encoding `X` is sent as, it was computed once when the message was built,
+    /// see [`CachedPoint`].
+    pub fn compressed_x_pub(&self) -> Option<&CompressedRistretto> {
+        match self {
+            ServerMessage::AugmentationInfo { x_pub, .. } => Some(x_pub.compressed()),
+            #[cfg(feature = "strong_aucpace")]
+            ServerMessage::StrongAugmentationInfo { x_pub, .. } => Some(x_pub.compressed()),
+            _ => None,
+        }
+    }
+
+    /// The blinded salt `UQ`, if this is a strong augmentation info message
+    #[cfg(feature = "strong_aucpace")]
+    pub fn blinded_salt(&self) -> Option<&RistrettoPoint> {
+        match self {
+            ServerMessage::StrongAugmentationInfo { blinded_salt, .. } => Some(blinded_salt),
+            _ => None,
+        }
+    }
+
+    /// The exact length of this message's `postcard` encoding, to preallocate a buffer for it
+    ///
+    /// The crate's messages have no bespoke wire format, they are serialized through `serde`.
+    /// `postcard` lays them out as a varint variant index followed by each field: points as
+    /// their 32 byte compression, and strings and byte arrays as a varint length followed by
+    /// the bytes. Other `serde` formats use other layouts, and so other lengths.
+    #[cfg(feature = "serde")]
+    pub fn serialized_len(&self) -> usize {
+        const POINT_LEN: usize = 32;
+        // every variant index fits in a single varint byte
+        const VARIANT_LEN: usize = 1;
+        let bytes_len = |len: usize| varint_len(len) + len;
+
+        VARIANT_LEN
+            + match self {
+                ServerMessage::Nonce(nonce) => bytes_len(nonce.len()),
+                ServerMessage::AugmentationInfo {
+                    group,
+                    salt,
+                    pbkdf_params,
+                    ..
+                } => {
+                    bytes_len(group.len())
+                        + POINT_LEN
+                        + bytes_len(salt.len())
+                        + bytes_len(pbkdf_params.len())
+                }
+                #[cfg(feature = "strong_aucpace")]
+                ServerMessage::StrongAugmentationInfo {
+                    group,
+                    pbkdf_params,
+                    ..
+                } => bytes_len(group.len()) + 2 * POINT_LEN + bytes_len(pbkdf_params.len()),
+                ServerMessage::PublicKey(_) => POINT_LEN,
+                ServerMessage::Authenticator(authenticator) => bytes_len(authenticator.len()),
+            }
+    }
+}
+
+#[cfg(feature = "postcard")]
+impl<'a, const K1: usize> ServerMessage<'a, K1> {
+    /// Encode this message with `postcard` into `buf`, returning the encoded bytes
+    ///
+    /// This only needs `core`, so it is available in `no_std` builds without `alloc`; size
+    /// `buf` with [`serialized_len`](Self::serialized_len).
+    ///
+    /// # Return:
+    /// - Ok(`bytes`): the prefix of `buf` holding the encoded message
+    /// - Err([`Error::Codec`](Error::Codec)): `buf` is too small for the message
+    ///
+    pub fn to_postcard<'b>(&self, buf: &'b mut [u8]) -> Result<&'b mut [u8]> {
+        postcard::to_slice(self, buf).map_err(|_| Error::Codec)
+    }
+
+    /// Decode a message encoded with [`to_postcard`](Self::to_postcard)
+    ///
+    /// Like encoding this only needs `core`; the group name is borrowed from `bytes`.
+    ///
+    /// # Return:
+    /// - Ok(`message`): the decoded message
+    /// - Err([`Error::Codec`](Error::Codec)): `bytes` is not a valid encoding of a message
+    ///
+    pub fn from_postcard(bytes: &'a [u8]) -> Result<Self> {
+        postcard::from_bytes(bytes).map_err(|_| Error::Codec)
+    }
+}
+
+/// The length of `value` as a `postcard` varint: seven bits per byte
+#[cfg(feature = "serde")]
+const fn varint_len(value: usize) -> usize {
+    let bits = usize::BITS - value.leading_zeros();
+    if bits == 0 {
+        1
+    } else {
+        bits.div_ceil(7) as usize
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused)]
@@ -770,14 +1516,172 @@ mod tests {
     #[allow(unused)]
     use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
 
+    /// RNG which succeeds for the first `successes` draws, then fails
+    struct ExhaustedRng {
+        successes: usize,
+    }
+
+    impl TryRngCore for ExhaustedRng {
+        type Error = fmt::Error;
+
+        fn try_next_u32(&mut self) -> core::result::Result<u32, Self::Error> {
+            let mut bytes = [0u8; 4];
+            self.try_fill_bytes(&mut bytes)?;
+            Ok(u32::from_le_bytes(bytes))
+        }
+
+        fn try_next_u64(&mut self) -> core::result::Result<u64, Self::Error> {
+            let mut bytes = [0u8; 8];
+            self.try_fill_bytes(&mut bytes)?;
+            Ok(u64::from_le_bytes(bytes))
+        }
+
+        fn try_fill_bytes(&mut self, dst: &mut [u8]) -> core::result::Result<(), Self::Error> {
+            if self.successes == 0 {
+                return Err(fmt::Error);
+            }
+            self.successes -= 1;
+            dst.fill(0x42);
+            Ok(())
+        }
+    }
+
+    impl TryCryptoRng for ExhaustedRng {}
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_rng_failure_stages() {
+        type TestServer = AuCPaceServer<sha2::Sha512, ExhaustedRng, 16>;
+
+        // the RNG fails while the server is constructed
+        let res = TestServer::new(ExhaustedRng { successes: 0 });
+        assert!(matches!(res, Err(Error::RngInit)));
+        let res = TestServer::with_secret(
+            ExhaustedRng { successes: 0 },
+            SecretScalar::new(Scalar::ONE),
+        );
+        assert!(matches!(res, Err(Error::RngInit)));
+
+        // the RNG fails when drawing the first nonce
+        let mut server = TestServer::new(ExhaustedRng { successes: 1 }).unwrap();
+        assert!(matches!(server.begin(), Err(Error::Rng)));
+    }
+
     #[test]
     #[cfg(all(feature = "sha2", feature = "getrandom"))]
-    fn test_server_doesnt_accept_insecure_ssid() {
-        use crate::Server;
-        use rand::rngs::OsRng;
-        let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
-        let res = server.begin_prestablished_ssid("bad ssid");
-        assert!(matches!(res, Err(Error::InsecureSsid)));
+    fn test_group_and_digest_name() {
+        let server = crate::Server::new(rand::rngs::OsRng).unwrap();
+        assert_eq!(server.group(), "ristretto255");
+        assert_eq!(server.digest_name(), "SHA-512");
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    #[allow(unsafe_code)]
+    fn test_abort_zeroizes_secrets() {
+        use crate::utils::H0;
+        use core::mem::ManuallyDrop;
+        use core::ptr::addr_of;
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>()
+            .chain_update([1u8; 16])
+            .finalize();
+        let sk1 = H0::<sha2::Sha512, LeU32HashScheme>()
+            .chain_update([2u8; 64])
+            .finalize();
+
+        // abort consumes the step, so where it was held is not observable afterwards; it
+        // zeroizes the step and drops it, whose effect is checked in place below
+        AuCPaceServerRecvClientKey::<sha2::Sha512, 16>::new(ssid, Scalar::ONE).abort();
+        AuCPaceServerExpMutAuth::<sha2::Sha512, 16>::new(ssid, sk1).abort();
+        let mut server = AuCPaceServerRecvClientKey::<sha2::Sha512, 16>::new(ssid, Scalar::ONE);
+        server.zeroize();
+        assert_eq!(server.priv_key, Scalar::ZERO);
+
+        // dropping a step wipes its secrets where it lies
+        let mut server = ManuallyDrop::new(AuCPaceServerRecvClientKey::<sha2::Sha512, 16>::new(
+            ssid,
+            Scalar::ONE,
+        ));
+        let ptr: *const AuCPaceServerRecvClientKey<sha2::Sha512, 16> = &*server;
+        // SAFETY: `server` is not used again after being dropped
+        unsafe { ManuallyDrop::drop(&mut server) };
+        // SAFETY: the step's storage lives until the end of the test, and zeroizing leaves a
+        // valid scalar in it
+        let priv_key = unsafe { addr_of!((*ptr).priv_key).read() };
+        assert_eq!(priv_key, Scalar::ZERO);
+
+        let mut server =
+            ManuallyDrop::new(AuCPaceServerExpMutAuth::<sha2::Sha512, 16>::new(ssid, sk1));
+        let ptr: *const AuCPaceServerExpMutAuth<sha2::Sha512, 16> = &*server;
+        // SAFETY: `server` is not used again after being dropped
+        unsafe { ManuallyDrop::drop(&mut server) };
+        // SAFETY: the step's storage lives until the end of the test, and any bytes are a valid
+        // digest output
+        let (dropped_ssid, dropped_sk1) =
+            unsafe { (addr_of!((*ptr).ssid).read(), addr_of!((*ptr).sk1).read()) };
+        assert!(dropped_sk1.iter().all(|&b| b == 0));
+        assert_eq!(dropped_ssid, ssid);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_server_authenticator_message_carries_ta() {
+        use crate::utils::{H0, compute_authenticator_messages};
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>()
+            .chain_update([1u8; 16])
+            .finalize();
+        let sk1 = H0::<sha2::Sha512, LeU32HashScheme>()
+            .chain_update([2u8; 64])
+            .finalize();
+        let (ta, tb) = compute_authenticator_messages::<sha2::Sha512>(ssid, sk1);
+
+        let server = AuCPaceServerExpMutAuth::<sha2::Sha512, 16>::new(ssid, sk1);
+        let (_, message) = server
+            .receive_client_authenticator(tb.as_slice().try_into().unwrap())
+            .expect("the client's authenticator Tb should be accepted");
+
+        if let ServerMessage::Authenticator(authenticator) = message {
+            assert_eq!(authenticator.as_slice(), ta.as_slice());
+        } else {
+            panic!("expected a ServerMessage::Authenticator, got {message:?}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serialized_len_matches_postcard() {
+        fn assert_len(message: &ServerMessage<'_, 16>) {
+            let mut buf = [0u8; 512];
+            let bytes = postcard::to_slice(message, &mut buf).unwrap();
+            assert_eq!(bytes.len(), message.serialized_len(), "{message:?}");
+        }
+
+        let x_pub = CachedPoint::new(RISTRETTO_BASEPOINT_POINT * Scalar::from(7u32));
+        let salt = SaltString::encode_b64(&[0x5a; 16]).unwrap();
+        let pbkdf_params: ParamsString = "ln=15,r=8,p=1".parse().unwrap();
+        assert_len(&ServerMessage::AugmentationInfo {
+            group: "ristretto255",
+            x_pub,
+            salt,
+            pbkdf_params: pbkdf_params.clone(),
+        });
+        // a group name long enough to need a two byte length prefix
+        assert_len(&ServerMessage::AugmentationInfo {
+            group: core::str::from_utf8(&[b'g'; 200]).unwrap(),
+            x_pub,
+            salt: SaltString::encode_b64(&[0x5a; 48]).unwrap(),
+            pbkdf_params: ParamsString::default(),
+        });
+        #[cfg(feature = "strong_aucpace")]
+        assert_len(&ServerMessage::StrongAugmentationInfo {
+            group: "ristretto255",
+            x_pub,
+            blinded_salt: RISTRETTO_BASEPOINT_POINT,
+            pbkdf_params,
+        });
+        assert_len(&ServerMessage::Nonce([1u8; 16]));
+        assert_len(&ServerMessage::PublicKey(*x_pub.as_point()));
+        assert_len(&ServerMessage::Authenticator([2u8; 64]));
     }
 
     #[test]
@@ -785,7 +1689,7 @@ mod tests {
     fn test_server_doesnt_accept_invalid_pubkey() {
         use crate::utils::H0;
         use curve25519_dalek::traits::Identity;
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_server: AuCPaceServerRecvClientKey<sha2::Sha512, 16> =
             AuCPaceServerRecvClientKey::new(ssid, Scalar::from(420u32));
         let res = aug_server.receive_client_pubkey(RistrettoPoint::identity());
@@ -802,7 +1706,7 @@ mod tests {
     fn test_server_doesnt_accept_invalid_pubkey_implicit_auth() {
         use crate::utils::H0;
         use curve25519_dalek::traits::Identity;
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_server: AuCPaceServerRecvClientKey<sha2::Sha512, 16> =
             AuCPaceServerRecvClientKey::new(ssid, Scalar::from(420u32));
         let res = aug_server.implicit_auth(RistrettoPoint::identity());
@@ -841,7 +1745,7 @@ mod tests {
             _secret_exponent: Self::Exponent,
             _params: ParamsString,
         ) {
-            unimplemented!()
+            panic!("store_verifier_strong is not used by this test")
         }
     }
 
@@ -863,7 +1767,97 @@ mod tests {
             _priv_key: Self::PrivateKey,
             _pub_key: Self::PublicKey,
         ) -> Result<()> {
-            unimplemented!()
+            panic!("store_long_term_keypair is not used by this test")
+        }
+    }
+
+    /// A database which must never be queried
+    #[cfg(all(feature = "sha2", feature = "strong_aucpace"))]
+    struct PanickingDatabase;
+
+    #[cfg(all(feature = "sha2", feature = "strong_aucpace"))]
+    impl StrongDatabase for PanickingDatabase {
+        type PasswordVerifier = RistrettoPoint;
+        type Exponent = Scalar;
+
+        fn lookup_verifier_strong(
+            &self,
+            _username: &[u8],
+        ) -> Option<(Self::PasswordVerifier, Self::Exponent, ParamsString)> {
+            panic!("the database was queried for an identity blinded point");
+        }
+
+        fn store_verifier_strong(
+            &mut self,
+            _username: &[u8],
+            _uad: Option<&[u8]>,
+            _verifier: Self::PasswordVerifier,
+            _secret_exponent: Self::Exponent,
+            _params: ParamsString,
+        ) {
+            panic!("store_verifier_strong is not used by this test")
+        }
+    }
+
+    #[cfg(all(
+        feature = "sha2",
+        feature = "strong_aucpace",
+        feature = "partial_augmentation"
+    ))]
+    impl PartialAugDatabase for PanickingDatabase {
+        type PrivateKey = Scalar;
+        type PublicKey = RistrettoPoint;
+
+        fn lookup_long_term_keypair(
+            &self,
+            _username: &[u8],
+        ) -> Option<(Self::PrivateKey, Self::PublicKey)> {
+            panic!("the database was queried for an identity blinded point");
+        }
+
+        fn store_long_term_keypair(
+            &mut self,
+            _username: &[u8],
+            _priv_key: Self::PrivateKey,
+            _pub_key: Self::PublicKey,
+        ) -> Result<()> {
+            panic!("store_long_term_keypair is not used by this test")
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "sha2", feature = "getrandom", feature = "strong_aucpace"))]
+    fn test_server_rejects_identity_blinded_point_before_lookup() {
+        use crate::utils::H0;
+        use curve25519_dalek::traits::Identity;
+        use rand::rngs::OsRng;
+
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
+        let aug_server: AuCPaceServerAugLayer<sha2::Sha512, 16> =
+            AuCPaceServerAugLayer::new(ServerSecret::from_key(25519).unwrap(), None, ssid);
+        let ssid_tag = compute_ssid_tag::<sha2::Sha512, LeU32HashScheme>(ssid).into();
+        let res = aug_server.generate_client_info_strong(
+            b"bobbyyyy",
+            ssid_tag,
+            RistrettoPoint::identity(),
+            &PanickingDatabase,
+            OsRng,
+        );
+        assert!(matches!(res, Err(Error::IllegalPointError)));
+
+        #[cfg(feature = "partial_augmentation")]
+        {
+            let aug_server: AuCPaceServerAugLayer<sha2::Sha512, 16> =
+                AuCPaceServerAugLayer::new(ServerSecret::from_key(25519).unwrap(), None, ssid);
+            let ssid_tag = compute_ssid_tag::<sha2::Sha512, LeU32HashScheme>(ssid).into();
+            let res = aug_server.generate_client_info_partial_strong(
+                b"bobbyyyy",
+                ssid_tag,
+                RistrettoPoint::identity(),
+                &PanickingDatabase,
+                OsRng,
+            );
+            assert!(matches!(res, Err(Error::IllegalPointError)));
         }
     }
 
@@ -874,11 +1868,13 @@ mod tests {
         use curve25519_dalek::traits::Identity;
         use rand::rngs::OsRng;
 
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_server: AuCPaceServerAugLayer<sha2::Sha512, 16> =
-            AuCPaceServerAugLayer::new(ServerSecret(25519), ssid);
+            AuCPaceServerAugLayer::new(ServerSecret::from_key(25519).unwrap(), None, ssid);
+        let ssid_tag = compute_ssid_tag::<sha2::Sha512, LeU32HashScheme>(ssid).into();
         let res = aug_server.generate_client_info_strong(
             b"bobbyyyy",
+            ssid_tag,
             RistrettoPoint::identity(),
             &FakeDatabase(),
             OsRng,
@@ -898,11 +1894,13 @@ mod tests {
         use curve25519_dalek::traits::Identity;
         use rand::rngs::OsRng;
 
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_server: AuCPaceServerAugLayer<sha2::Sha512, 16> =
-            AuCPaceServerAugLayer::new(ServerSecret(25519), ssid);
+            AuCPaceServerAugLayer::new(ServerSecret::from_key(25519).unwrap(), None, ssid);
+        let ssid_tag = compute_ssid_tag::<sha2::Sha512, LeU32HashScheme>(ssid).into();
         let res = aug_server.generate_client_info_partial_strong(
             b"bobbyyyy",
+            ssid_tag,
             RistrettoPoint::identity(),
             &FakeDatabase(),
             OsRng,
@@ -916,41 +1914,180 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "postcard")]
+    fn test_postcard_round_trip() {
+        let x_pub = RISTRETTO_BASEPOINT_POINT * Scalar::from(7u32);
+        let pbkdf_params: ParamsString = "ln=15,r=8,p=1".parse().unwrap();
+        let mut buf = [0u8; 256];
+
+        let message: ServerMessage<'_, 16> = ServerMessage::AugmentationInfo {
+            group: "ristretto255",
+            x_pub: x_pub.into(),
+            salt: SaltString::encode_b64(&[0x5a; 16]).unwrap(),
+            pbkdf_params: pbkdf_params.clone(),
+        };
+        let bytes = message.to_postcard(&mut buf).unwrap();
+        assert_eq!(bytes.len(), message.serialized_len());
+        let decoded = ServerMessage::<'_, 16>::from_postcard(bytes).unwrap();
+        let ServerMessage::AugmentationInfo {
+            group,
+            salt,
+            pbkdf_params: params,
+            ..
+        } = &decoded
+        else {
+            panic!("expected AugmentationInfo, got {decoded:?}");
+        };
+        assert_eq!(*group, "ristretto255");
+        assert_eq!(decoded.x_pub(), Some(&x_pub));
+        assert_eq!(
+            salt.as_str(),
+            SaltString::encode_b64(&[0x5a; 16]).unwrap().as_str()
+        );
+        assert_eq!(params.as_str(), pbkdf_params.as_str());
+
+        #[cfg(feature = "strong_aucpace")]
+        {
+            let message: ServerMessage<'_, 16> = ServerMessage::StrongAugmentationInfo {
+                group: "ristretto255",
+                x_pub: x_pub.into(),
+                blinded_salt: RISTRETTO_BASEPOINT_POINT,
+                pbkdf_params: pbkdf_params.clone(),
+            };
+            let bytes = message.to_postcard(&mut buf).unwrap();
+            let decoded = ServerMessage::<'_, 16>::from_postcard(bytes).unwrap();
+            let ServerMessage::StrongAugmentationInfo {
+                group,
+                pbkdf_params: params,
+                ..
+            } = &decoded
+            else {
+                panic!("expected StrongAugmentationInfo, got {decoded:?}");
+            };
+            assert_eq!(*group, "ristretto255");
+            assert_eq!(decoded.x_pub(), Some(&x_pub));
+            assert_eq!(decoded.blinded_salt(), Some(&RISTRETTO_BASEPOINT_POINT));
+            assert_eq!(params.as_str(), pbkdf_params.as_str());
+        }
+
+        // too small a buffer, and truncated input, are codec errors
+        let res = message.to_postcard(&mut buf[..8]);
+        assert!(matches!(res, Err(Error::Codec)));
+        let len = message.to_postcard(&mut buf).unwrap().len();
+        let res = ServerMessage::<'_, 16>::from_postcard(&buf[..len - 1]);
+        assert!(matches!(res, Err(Error::Codec)));
+    }
+
+    #[test]
+    #[cfg(feature = "postcard")]
+    fn test_serialize_writes_cached_encoding() {
+        let point = RISTRETTO_BASEPOINT_POINT * Scalar::from(7u32);
+        let cached = CachedPoint::new(point);
+        assert_eq!(cached.compressed(), &point.compress());
+
+        // a stale cache shows up in the output, so serializing never recompresses the point
+        let stale = CachedPoint {
+            point,
+            compressed: RISTRETTO_BASEPOINT_POINT.compress(),
+        };
+        let message: ServerMessage<'_, 16> = ServerMessage::AugmentationInfo {
+            group: "ristretto255",
+            x_pub: stale,
+            salt: SaltString::encode_b64(&[0x5a; 16]).unwrap(),
+            pbkdf_params: ParamsString::default(),
+        };
+        assert_eq!(
+            message.compressed_x_pub(),
+            Some(&RISTRETTO_BASEPOINT_POINT.compress())
+        );
+        let mut buf = [0u8; 128];
+        let bytes = message.to_postcard(&mut buf).unwrap();
+        // `X` follows the variant tag and the length prefixed group name
+        let offset = 2 + "ristretto255".len();
+        assert_eq!(
+            &bytes[offset..offset + 32],
+            RISTRETTO_BASEPOINT_POINT.compress().as_bytes()
+        );
+        let decoded = ServerMessage::<'_, 16>::from_postcard(bytes).unwrap();
+        assert_eq!(decoded.x_pub(), Some(&RISTRETTO_BASEPOINT_POINT));
+    }
+
+    /// Fake DB that always returns None for lookup_verifier to force lookup_failed path
     #[cfg(all(feature = "sha2", feature = "getrandom"))]
-    fn test_server_lookup_failed_returns_ok() {
-        use crate::utils::H0;
-        use rand::rngs::OsRng;
+    struct NoneDb;
 
-        // Fake DB that always returns None for lookup_verifier to force lookup_failed path
-        struct NoneDb;
-        impl Database for NoneDb {
-            type PasswordVerifier = RistrettoPoint;
+    #[cfg(all(feature = "sha2", feature = "getrandom"))]
+    impl Database for NoneDb {
+        type PasswordVerifier = RistrettoPoint;
+        type Error = Error;
 
-            fn lookup_verifier(
-                &self,
-                _username: &[u8],
-            ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
-                None
-            }
+        fn lookup_verifier(
+            &self,
+            _username: &[u8],
+        ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+            None
+        }
 
-            fn store_verifier(
-                &mut self,
-                _username: &[u8],
-                _salt: SaltString,
-                _uad: Option<&[u8]>,
-                _verifier: Self::PasswordVerifier,
-                _params: ParamsString,
-            ) {
-                unimplemented!()
-            }
+        fn store_verifier(
+            &mut self,
+            _username: &[u8],
+            _salt: SaltString,
+            _uad: Option<&[u8]>,
+            _verifier: Self::PasswordVerifier,
+            _params: ParamsString,
+        ) {
+            panic!("store_verifier is not used by this test")
         }
+    }
+
+    #[test]
+    #[cfg(all(feature = "sha2", feature = "getrandom"))]
+    fn test_server_with_secret_uses_fixed_key() {
+        use rand::rngs::OsRng;
+
+        let res = AuCPaceServer::<sha2::Sha512, _, 16>::with_secret(
+            OsRng,
+            SecretScalar::new(Scalar::ZERO),
+        );
+        assert!(matches!(res, Err(Error::ZeroScalar)));
+
+        let mut server = AuCPaceServer::<sha2::Sha512, _, 16>::with_secret(
+            OsRng,
+            SecretScalar::new(Scalar::from(7u32)),
+        )
+        .expect("failed to initialize server RNG");
+        let expected = RISTRETTO_BASEPOINT_POINT * Scalar::from(7u32);
+
+        // both ways into the augmentation layer carry the fixed key, in every session
+        let (ssid_establish, _) = server.begin().unwrap();
+        let sessions = [
+            ssid_establish.agree_ssid([1u8; 16]),
+            server.begin_prestablished_ssid(Ssid::try_new(&[2u8; 16]).unwrap()),
+            server.begin_prestablished_ssid(Ssid::try_new(&[3u8; 16]).unwrap()),
+        ];
+        for aug_server in sessions {
+            let ssid_tag =
+                compute_ssid_tag::<sha2::Sha512, LeU32HashScheme>(aug_server.ssid).into();
+            let (_, message) = aug_server
+                .generate_client_info(b"jlpicard", ssid_tag, &NoneDb, OsRng)
+                .unwrap();
+            assert_eq!(message.x_pub(), Some(&expected));
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "sha2", feature = "getrandom"))]
+    fn test_server_lookup_failed_returns_ok() {
+        use crate::utils::H0;
+        use rand::rngs::OsRng;
 
-        let ssid = H0::<sha2::Sha512>().finalize();
+        let ssid = H0::<sha2::Sha512, LeU32HashScheme>().finalize();
         let aug_server: AuCPaceServerAugLayer<sha2::Sha512, 16> =
-            AuCPaceServerAugLayer::new(ServerSecret(25519), ssid);
+            AuCPaceServerAugLayer::new(ServerSecret::from_key(25519).unwrap(), None, ssid);
+        let ssid_tag = compute_ssid_tag::<sha2::Sha512, LeU32HashScheme>(ssid).into();
 
         // This should take the lookup_failed path and not panic; it should return Ok
-        let res = aug_server.generate_client_info(b"missing-user", &NoneDb, OsRng);
+        let res = aug_server.generate_client_info(b"missing-user", ssid_tag, &NoneDb, OsRng);
 
         assert!(res.is_ok());
         if let Ok((_next_step, ServerMessage::AugmentationInfo { .. })) = res {
@@ -959,4 +2096,72 @@ mod tests {
             panic!("Expected AugmentationInfo on lookup_failed path");
         }
     }
+
+    #[test]
+    #[cfg(feature = "scrypt")]
+    fn test_recommended_fallback_params_match_scrypt() {
+        let expected: ParamsString = scrypt::Params::recommended().try_into().unwrap();
+        let fallback = FallbackParams::recommended().unwrap();
+        assert_eq!(fallback.len, 1);
+        assert_eq!(fallback.select(0xff), &expected);
+    }
+
+    #[test]
+    fn test_server_message_debug_redacts_salt() {
+        extern crate std;
+
+        let salt = SaltString::encode_b64(b"saltyboi").unwrap();
+        let message: ServerMessage<'_, 16> = ServerMessage::AugmentationInfo {
+            group: "ristretto255",
+            x_pub: RISTRETTO_BASEPOINT_POINT.into(),
+            salt: salt.clone(),
+            pbkdf_params: "m=19456,t=2,p=1,keyid=c2FsdHk".parse().unwrap(),
+        };
+        let debug = std::format!("{message:?}");
+        assert!(debug.starts_with("AugmentationInfo { group: \"ristretto255\""));
+        assert!(!debug.contains(salt.as_str()));
+        assert!(debug.contains("salt: [redacted], len=11"));
+        assert!(debug.contains("pbkdf_params: \"m=19456,t=2,p=1,\".."));
+        assert!(!debug.contains("keyid"));
+    }
+
+    #[test]
+    fn test_server_message_accessors_default_variant() {
+        use curve25519_dalek::traits::Identity;
+
+        let message: ServerMessage<'_, 16> = ServerMessage::AugmentationInfo {
+            group: "ristretto255",
+            x_pub: RISTRETTO_BASEPOINT_POINT.into(),
+            salt: SaltString::encode_b64(b"saltyboi").unwrap(),
+            pbkdf_params: ParamsString::default(),
+        };
+        assert_eq!(message.x_pub(), Some(&RISTRETTO_BASEPOINT_POINT));
+        assert_eq!(
+            message.compressed_x_pub(),
+            Some(&RISTRETTO_BASEPOINT_POINT.compress())
+        );
+        #[cfg(feature = "strong_aucpace")]
+        assert_eq!(message.blinded_salt(), None);
+
+        let message: ServerMessage<'_, 16> = ServerMessage::PublicKey(RistrettoPoint::identity());
+        assert_eq!(message.x_pub(), None);
+        assert_eq!(message.compressed_x_pub(), None);
+        #[cfg(feature = "strong_aucpace")]
+        assert_eq!(message.blinded_salt(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "strong_aucpace")]
+    fn test_server_message_accessors_strong_variant() {
+        use curve25519_dalek::traits::Identity;
+
+        let message: ServerMessage<'_, 16> = ServerMessage::StrongAugmentationInfo {
+            group: "ristretto255",
+            x_pub: RISTRETTO_BASEPOINT_POINT.into(),
+            blinded_salt: RistrettoPoint::identity(),
+            pbkdf_params: ParamsString::default(),
+        };
+        assert_eq!(message.x_pub(), Some(&RISTRETTO_BASEPOINT_POINT));
+        assert_eq!(message.blinded_salt(), Some(&RistrettoPoint::identity()));
+    }
 }
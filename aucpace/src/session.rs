@@ -0,0 +1,229 @@
+use crate::server::{
+    AuCPaceServer, AuCPaceServerAugLayer, AuCPaceServerExpMutAuth, AuCPaceServerRecvClientKey,
+};
+use crate::utils::{HashScheme, LeU32HashScheme, compute_ssid_with_scheme};
+use crate::{ClientMessage, Database, Error, Result, ServerMessage};
+use curve25519_dalek::{
+    digest::consts::U64,
+    digest::{Digest, Output},
+    ristretto::RistrettoPoint,
+};
+use rand_core::{TryCryptoRng, TryRngCore};
+use secret_utils::wrappers::SecretKey;
+
+extern crate alloc;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// An in-flight handshake, in the state it is waiting for the client's next message in
+enum Session<D, CSPRNG, const K1: usize, S>
+where
+    D: Digest<OutputSize = U64> + Default,
+    CSPRNG: TryRngCore + TryCryptoRng,
+    S: HashScheme,
+{
+    /// waiting for the client's username
+    AugLayer {
+        server: AuCPaceServerAugLayer<D, K1, S>,
+        rng: CSPRNG,
+        channel_identifier: Vec<u8>,
+    },
+    /// waiting for the client's public key
+    RecvClientKey(AuCPaceServerRecvClientKey<D, K1, S>),
+    /// waiting for the client's authenticator
+    ExpMutAuth(AuCPaceServerExpMutAuth<D, K1, S>),
+}
+
+/// Tracks many in-flight server handshakes, keyed by their SSID
+///
+/// The server step types consume themselves as the protocol advances, which makes them awkward
+/// to hold for many concurrent clients. A `SessionStore` holds each handshake, in whichever
+/// state it is in, until the client's next message arrives:
+///
+/// - [`begin_session`](Self::begin_session): the client's nonce arrives, reply with the server's
+///   nonce and use the returned SSID to refer to the session from then on
+/// - [`advance_session`](Self::advance_session): the client's username and public key arrive
+/// - [`finish_session`](Self::finish_session): the client's authenticator arrives, this returns
+///   the session key and removes the session
+///
+/// Any error removes the session, the handshake must then be restarted. At most
+/// `max_sessions` handshakes can be in-flight at once, so that clients who abandon a handshake
+/// cannot exhaust the server's memory, use [`abort_session`](Self::abort_session) to remove
+/// sessions which have timed out.
+///
+/// Only the default, explicit mutual authentication, variant of the protocol is supported.
+pub struct SessionStore<D, CSPRNG, const K1: usize, S = LeU32HashScheme>
+where
+    D: Digest<OutputSize = U64> + Default,
+    CSPRNG: TryRngCore + TryCryptoRng,
+    S: HashScheme,
+{
+    sessions: BTreeMap<Output<D>, Session<D, CSPRNG, K1, S>>,
+    max_sessions: usize,
+}
+
+impl<D, CSPRNG, const K1: usize, S> SessionStore<D, CSPRNG, K1, S>
+where
+    D: Digest<OutputSize = U64> + Default,
+    CSPRNG: TryRngCore + TryCryptoRng,
+    S: HashScheme,
+{
+    /// Create an empty store which allows at most `max_sessions` concurrent handshakes
+    pub const fn new(max_sessions: usize) -> Self {
+        Self {
+            sessions: BTreeMap::new(),
+            max_sessions,
+        }
+    }
+
+    /// The number of in-flight handshakes
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Whether there are no in-flight handshakes
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Begin a new handshake with a client, given the client's nonce
+    ///
+    /// # Arguments:
+    /// - `server`: the server to begin the handshake with
+    /// - `client_nonce`: the client's nonce `t`
+    /// - `rng`: the CSPRNG used for this handshake's remaining steps
+    /// - `channel_identifier`: `CI` from the protocol definition, see
+    ///   [`generate_public_key`](crate::server::AuCPaceServerCPaceSubstep::generate_public_key)
+    ///
+    /// # Return:
+    /// - Ok((`ssid`, [`message`](ServerMessage::Nonce))):
+    ///   - `ssid`: the SSID identifying this session in later calls
+    ///   - [`message`](ServerMessage::Nonce): the message to send to the client
+    /// - Err([`Error::SessionLimitReached`](Error::SessionLimitReached)): `max_sessions`
+    ///   handshakes are already in-flight
+    ///
+    pub fn begin_session<CI>(
+        &mut self,
+        server: &mut AuCPaceServer<D, CSPRNG, K1, S>,
+        client_nonce: [u8; K1],
+        rng: CSPRNG,
+        channel_identifier: CI,
+    ) -> Result<(Output<D>, ServerMessage<'static, K1>)>
+    where
+        CI: AsRef<[u8]>,
+    {
+        if self.sessions.len() >= self.max_sessions {
+            return Err(Error::SessionLimitReached);
+        }
+
+        let (server, message) = server.begin()?;
+        // `begin` always returns a nonce message
+        let ServerMessage::Nonce(server_nonce) = message else {
+            return Err(Error::InternalInvariant);
+        };
+        // the server nonce is fresh, so SSIDs of different sessions cannot collide
+        let ssid = compute_ssid_with_scheme::<D, S, K1>(server_nonce, client_nonce);
+        let session = Session::AugLayer {
+            server: server.agree_ssid(client_nonce),
+            rng,
+            channel_identifier: channel_identifier.as_ref().to_vec(),
+        };
+        self.sessions.insert(ssid, session);
+
+        Ok((ssid, message))
+    }
+
+    /// Advance a handshake with the client's next message
+    ///
+    /// # Arguments:
+    /// - `ssid`: the SSID returned by [`begin_session`](Self::begin_session)
+    /// - `message`: the client's message, either its username or its public key
+    /// - `database`: the password verifier database to retrieve the client's information from
+    ///
+    /// # Return:
+    /// - Ok(`messages`): the messages to send to the client, in order: for a username this is
+    ///   the [`AugmentationInfo`](ServerMessage::AugmentationInfo) followed by the server's
+    ///   [`PublicKey`](ServerMessage::PublicKey), for a public key there is nothing to send
+    /// - Err([`Error::UnknownSession`](Error::UnknownSession)): there is no session for `ssid`
+    /// - Err([`Error::UnexpectedMessage`](Error::UnexpectedMessage)): the message is not the
+    ///   one the session is waiting for
+    ///
+    /// Errors are returned as the store's [`Error`](Database::Error), see
+    /// [`generate_client_info`](crate::server::AuCPaceServerAugLayer::generate_client_info).
+    ///
+    pub fn advance_session<DB>(
+        &mut self,
+        ssid: &Output<D>,
+        message: ClientMessage<'_, K1>,
+        database: &DB,
+    ) -> core::result::Result<Vec<ServerMessage<'static, K1>>, DB::Error>
+    where
+        DB: Database<PasswordVerifier = RistrettoPoint>,
+    {
+        let session = self.sessions.remove(ssid).ok_or(Error::UnknownSession)?;
+
+        let (next, messages) = match (session, message) {
+            (
+                Session::AugLayer {
+                    server,
+                    rng,
+                    channel_identifier,
+                },
+                ClientMessage::Username { username, ssid_tag },
+            ) => {
+                let (server, info) =
+                    server.generate_client_info(username, ssid_tag, database, rng)?;
+                let (server, public_key) = server.generate_public_key(channel_identifier)?;
+                (
+                    Session::RecvClientKey(server),
+                    alloc::vec![info, public_key],
+                )
+            }
+            (Session::RecvClientKey(server), ClientMessage::PublicKey(client_pubkey)) => {
+                let server = server.receive_client_pubkey(client_pubkey)?;
+                (Session::ExpMutAuth(server), Vec::new())
+            }
+            _ => return Err(Error::UnexpectedMessage.into()),
+        };
+
+        self.sessions.insert(*ssid, next);
+        Ok(messages)
+    }
+
+    /// Finish a handshake with the client's authenticator, removing the session
+    ///
+    /// # Arguments:
+    /// - `ssid`: the SSID returned by [`begin_session`](Self::begin_session)
+    /// - `message`: the client's [`Authenticator`](ClientMessage::Authenticator) message
+    ///
+    /// # Return:
+    /// - Ok((`sk`, [`message`](ServerMessage::Authenticator))):
+    ///   - `sk`: the session key reached by the `AuCPace` protocol
+    ///   - [`message`](ServerMessage::Authenticator): the message to send to the client
+    /// - Err([`Error::UnknownSession`](Error::UnknownSession)): there is no session for `ssid`
+    /// - Err([`Error::UnexpectedMessage`](Error::UnexpectedMessage)): the session is not waiting
+    ///   for the client's authenticator
+    /// - Err([`Error::KeyConfirmationFailed`](Error::KeyConfirmationFailed)): the authenticator
+    ///   is incorrect
+    ///
+    pub fn finish_session(
+        &mut self,
+        ssid: &Output<D>,
+        message: ClientMessage<'_, K1>,
+    ) -> Result<(SecretKey, ServerMessage<'static, K1>)> {
+        match (self.sessions.remove(ssid), message) {
+            (Some(Session::ExpMutAuth(server)), ClientMessage::Authenticator(authenticator)) => {
+                server.receive_client_authenticator(authenticator)
+            }
+            (Some(_), _) => Err(Error::UnexpectedMessage),
+            (None, _) => Err(Error::UnknownSession),
+        }
+    }
+
+    /// Remove a session without completing it, e.g. once it has timed out
+    ///
+    /// Returns `true` if there was a session for `ssid`.
+    pub fn abort_session(&mut self, ssid: &Output<D>) -> bool {
+        self.sessions.remove(ssid).is_some()
+    }
+}
@@ -0,0 +1,79 @@
+use crate::constants::MIN_SSID_LEN;
+use crate::{Error, Result};
+
+/// A pre-established SSID, checked to be long enough to be secure
+///
+/// The SSID establishment phase can be bypassed when both sides already agree on an SSID, e.g.
+/// channel binding data from an outer protocol. Such an SSID must be at least
+/// [`MIN_SSID_LEN`] bytes long, [`try_new`](Self::try_new) checks this once so that the
+/// `begin_prestablished_ssid` methods of the client and server can rely on it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Ssid<'a>(&'a [u8]);
+
+impl<'a> Ssid<'a> {
+    /// Check that `bytes` is long enough to be used as an SSID
+    ///
+    /// # Return:
+    /// - Ok(`ssid`): the SSID
+    /// - Err([`Error::SsidTooShort`](Error::SsidTooShort)): `bytes` is shorter than
+    ///   [`MIN_SSID_LEN`]
+    ///
+    pub const fn try_new(bytes: &'a [u8]) -> Result<Self> {
+        if bytes.len() < MIN_SSID_LEN {
+            return Err(Error::SsidTooShort {
+                min: MIN_SSID_LEN,
+                got: bytes.len(),
+            });
+        }
+        Ok(Self(bytes))
+    }
+
+    /// The bytes of the SSID
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for Ssid<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self.0
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Ssid<'a> {
+    type Error = Error;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self> {
+        Self::try_new(bytes)
+    }
+}
+
+// pre-established SSIDs may be derived from secret material, so only the length is printed
+impl core::fmt::Debug for Ssid<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Ssid(len={})", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ssid_try_new() {
+        assert_eq!(
+            Ssid::try_new(b"bad ssid"),
+            Err(Error::SsidTooShort {
+                min: MIN_SSID_LEN,
+                got: 8
+            })
+        );
+        assert!(Ssid::try_new(&[]).is_err());
+        assert!(Ssid::try_new(&[0u8; MIN_SSID_LEN - 1]).is_err());
+
+        let bytes = [7u8; MIN_SSID_LEN];
+        let ssid = Ssid::try_new(&bytes).unwrap();
+        assert_eq!(ssid.as_bytes(), &bytes);
+        assert_eq!(Ssid::try_from(&bytes[..]), Ok(ssid));
+    }
+}
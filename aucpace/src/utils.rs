@@ -1,27 +1,106 @@
 use crate::{Error, Result};
+#[cfg(any(test, not(feature = "precomputed-tables")))]
 use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::traits::Identity;
 use curve25519_dalek::{
     digest::consts::U64,
     digest::{Digest, Output},
     ristretto::RistrettoPoint,
     scalar::Scalar,
 };
-use password_hash::PasswordHash;
+use password_hash::{ParamsString, PasswordHash, Salt, SaltString};
 use rand_core::{TryCryptoRng, TryRngCore};
+use secret_utils::wrappers::{SecretArray, SecretBytes, SecretKey};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
 
-#[allow(non_snake_case)]
+use core::marker::PhantomData;
+use secret_utils::wrappers::SecretScalar;
+
+#[cfg(feature = "stringprep")]
+use secret_utils::wrappers::SecretString;
+
+/// How the index `n` of the hash oracle `Hn` is encoded in front of every hash input.
+///
+/// `AuCPace` separates its hash oracles `H0..H5` by prefixing the input with a domain tag, this
+/// crate uses the 4-byte little-endian encoding of `n` ([`LeU32HashScheme`]). Implementations
+/// which encode the tag differently derive different SSIDs, generators and keys, a matching
+/// scheme must be selected to interoperate with them.
+///
+/// The client and server state machines take the scheme as their last type parameter, which
+/// defaults to [`LeU32HashScheme`], e.g. `AuCPaceServer<Sha512, OsRng, 16, SingleByteHashScheme>`.
+/// Both sides of a handshake must use the same scheme.
+pub trait HashScheme {
+    /// Absorb the domain tag of the hash oracle `HN` into `hasher`
+    fn domain_tag<D: Digest, const N: u32>(hasher: &mut D);
+}
+
+/// The default [`HashScheme`], `Hn` is prefixed with `n` as 4 little-endian bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LeU32HashScheme;
+
+impl HashScheme for LeU32HashScheme {
+    #[inline]
+    fn domain_tag<D: Digest, const N: u32>(hasher: &mut D) {
+        hasher.update(N.to_le_bytes());
+    }
+}
+
+/// A [`HashScheme`] prefixing `Hn` with `n` as a single byte
+///
+/// Only the oracles `H0..H5` are used by the protocol, so the tag always fits into one byte.
+/// An oracle index which does not is rejected when the oracle is instantiated:
+///
+/// ```compile_fail,E0080
+/// # use aucpace_conflux::{SingleByteHashScheme, hash_oracle};
+/// let hasher: sha2::Sha512 = hash_oracle::<_, SingleByteHashScheme, 256>();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SingleByteHashScheme;
+
+impl HashScheme for SingleByteHashScheme {
+    #[inline]
+    fn domain_tag<D: Digest, const N: u32>(hasher: &mut D) {
+        const {
+            assert!(
+                N <= u8::MAX as u32,
+                "hash oracle index does not fit in a byte"
+            );
+        }
+        hasher.update([N as u8]);
+    }
+}
+
+/// The name of a digest, as reported by
+/// [`AuCPaceServer::digest_name`](crate::AuCPaceServer::digest_name)
+///
+/// Implemented for the digests this crate supports, implement it for other digests to be able
+/// to report them.
+pub trait DigestName {
+    /// The digest's name, e.g. `"SHA-512"`
+    const DIGEST_NAME: &'static str;
+}
+
+#[cfg(feature = "sha2")]
+impl DigestName for sha2::Sha512 {
+    const DIGEST_NAME: &'static str = "SHA-512";
+}
+
+/// Construct the hash oracle `HN`, with its domain tag encoded by the [`HashScheme`] `S`
+///
+/// `hash_oracle::<D, LeU32HashScheme, N>()` is the oracle used by default throughout this crate.
 #[inline]
-fn H<D: Digest + Default, const N: u32>() -> D {
+pub fn hash_oracle<D: Digest + Default, S: HashScheme, const N: u32>() -> D {
     let mut hasher: D = Default::default();
-    hasher.update(N.to_le_bytes());
+    S::domain_tag::<D, N>(&mut hasher);
     hasher
 }
 
 macro_rules! create_h_impl {
     ($name:ident, $n:literal) => {
         #[allow(non_snake_case)]
-        pub fn $name<D: Digest + Default>() -> D {
-            H::<D, $n>()
+        pub fn $name<D: Digest + Default, S: HashScheme>() -> D {
+            hash_oracle::<D, S, $n>()
         }
     };
 }
@@ -33,6 +112,36 @@ create_h_impl!(H2, 2);
 create_h_impl!(H3, 3);
 create_h_impl!(H4, 4);
 create_h_impl!(H5, 5);
+// H6 tags the SSID confirmation the client sends with its username
+create_h_impl!(H6, 6);
+
+/// The cofactor `c_J` of ristretto255.
+///
+/// ristretto255 is a prime-order group, so its cofactor is one. Raw curve25519 has a cofactor
+/// of 8, which must never be paired with `RISTRETTO_BASEPOINT_POINT`.
+const RISTRETTO_COFACTOR: Scalar = Scalar::ONE;
+
+/// Multiply `RISTRETTO_BASEPOINT_POINT` by `scalar`
+///
+/// With the `precomputed-tables` feature this uses `RISTRETTO_BASEPOINT_TABLE`, which is about
+/// twice as fast as a variable-base multiplication at the cost of a larger binary.
+#[inline]
+pub(crate) fn basepoint_mul(scalar: &Scalar) -> RistrettoPoint {
+    #[cfg(feature = "precomputed-tables")]
+    {
+        curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE * scalar
+    }
+    #[cfg(not(feature = "precomputed-tables"))]
+    {
+        RISTRETTO_BASEPOINT_POINT * scalar
+    }
+}
+
+/// Return the cofactor to use alongside `RISTRETTO_BASEPOINT_POINT`.
+#[inline]
+pub const fn ristretto_cofactor() -> Scalar {
+    RISTRETTO_COFACTOR
+}
 
 /// Generate a fixed length nonce using a CSPRNG.
 ///
@@ -40,6 +149,9 @@ create_h_impl!(H5, 5);
 /// CSPRNG fails to produce bytes (for example, due to an OS entropy failure).
 /// Callers should handle or propagate this error.
 ///
+/// The nonce is filled in blocks of at most 256 bytes, so nonces of any length can be drawn
+/// from RNGs which limit the size of a single request.
+///
 /// Examples
 ///
 /// Propagate the error with `?`:
@@ -62,19 +174,289 @@ where
     CSPRNG: TryRngCore + TryCryptoRng,
 {
     let mut nonce = [0; N];
-    rng.try_fill_bytes(&mut nonce).map_err(|_| Error::Rng)?;
+    try_fill_chunked(rng, &mut nonce)?;
     Ok(nonce)
 }
 
+/// The largest number of bytes requested from a CSPRNG in a single call
+///
+/// Some RNGs refuse requests over a per-call limit, e.g. `getentropy` fails for more than 256
+/// bytes, so larger buffers are filled in blocks of this size.
+const RNG_FILL_CHUNK: usize = 256;
+
+/// Fill `dst` from `rng` in blocks of at most [`RNG_FILL_CHUNK`] bytes
+///
+/// Returns `Err(Error::Rng)` as soon as a block fails, `dst` may then be partially filled.
+#[inline]
+fn try_fill_chunked<CSPRNG>(rng: &mut CSPRNG, dst: &mut [u8]) -> Result<()>
+where
+    CSPRNG: TryRngCore + TryCryptoRng,
+{
+    for block in dst.chunks_mut(RNG_FILL_CHUNK) {
+        rng.try_fill_bytes(block).map_err(|_| Error::Rng)?;
+    }
+    Ok(())
+}
+
+/// Generate a fixed length nonce using a CSPRNG, retrying on RNG failure.
+///
+/// Like [`generate_nonce`], but a failing RNG is retried up to `attempts` times in total before
+/// `Err(Error::Rng)` is returned, for platforms where the RNG can fail transiently under load.
+/// Bytes written by a failed attempt are zeroized before the next attempt, nothing is carried
+/// over between attempts. With `attempts == 0` the RNG is not called and `Err(Error::Rng)` is
+/// returned.
+pub fn generate_nonce_retry<CSPRNG, const N: usize>(
+    rng: &mut CSPRNG,
+    attempts: usize,
+) -> Result<[u8; N]>
+where
+    CSPRNG: TryRngCore + TryCryptoRng,
+{
+    let mut nonce = [0; N];
+    for _ in 0..attempts {
+        if try_fill_chunked(rng, &mut nonce).is_ok() {
+            return Ok(nonce);
+        }
+        nonce.zeroize();
+    }
+    Err(Error::Rng)
+}
+
+/// Generate a random salt for registering a user, using a CSPRNG.
+///
+/// [`Salt::RECOMMENDED_LENGTH`] random bytes are drawn from `rng` and Base64 encoded into a
+/// [`SaltString`], so that the salt comes from the same RNG as the rest of the handshake rather
+/// than from [`SaltString::generate`] with a separate one.
+///
+/// # Return:
+/// - Ok(`salt`): the encoded salt
+/// - Err([`Error::Rng`](Error::Rng)): the RNG failed to generate the salt bytes
+#[inline]
+pub fn generate_salt<CSPRNG>(rng: &mut CSPRNG) -> Result<SaltString>
+where
+    CSPRNG: TryRngCore + TryCryptoRng,
+{
+    let mut bytes = [0u8; Salt::RECOMMENDED_LENGTH];
+    rng.try_fill_bytes(&mut bytes).map_err(|_| Error::Rng)?;
+    SaltString::encode_b64(&bytes).map_err(Error::PasswordHashing)
+}
+
+/// Generate a fixed length secret random value using a CSPRNG.
+///
+/// Like [`generate_nonce`], but the bytes are written straight into a zeroizing
+/// [`SecretArray`]. If the RNG fails part way through filling the array, the partially
+/// written bytes are zeroized before `Err(Error::Rng)` is returned.
+#[inline]
+pub fn generate_nonce_secret<CSPRNG, const N: usize>(rng: &mut CSPRNG) -> Result<SecretArray<N>>
+where
+    CSPRNG: TryRngCore + TryCryptoRng,
+{
+    let mut secret = SecretArray::zeroed();
+    fill_secret(rng, &mut secret)?;
+    Ok(secret)
+}
+
+/// Hash 64 random bytes, written into `seed`, to a private scalar
+///
+/// `seed` is zeroized if the RNG fails after writing some of it.
+#[inline]
+fn scalar_from_seed<D, CSPRNG>(rng: &mut CSPRNG, seed: &mut SecretArray<64>) -> Result<Scalar>
+where
+    D: Digest<OutputSize = U64> + Default,
+    CSPRNG: TryRngCore + TryCryptoRng,
+{
+    fill_secret(rng, seed)?;
+    let mut rng_hasher: D = Default::default();
+    rng_hasher.update(seed.expose());
+    Ok(Scalar::from_hash(rng_hasher))
+}
+
+/// Fill `secret` from `rng`, zeroizing it if the RNG fails
+#[inline]
+fn fill_secret<CSPRNG, const N: usize>(rng: &mut CSPRNG, secret: &mut SecretArray<N>) -> Result<()>
+where
+    CSPRNG: TryRngCore + TryCryptoRng,
+{
+    try_fill_chunked(rng, secret.expose_mut()).inspect_err(|_| secret.zeroize())
+}
+
+/// Generate a non-zero blinding scalar for the strong `AuCPace` client.
+///
+/// The scalar is returned in a zeroize-on-drop [`SecretScalar`], and the random bytes it is
+/// reduced from are zeroized before returning. It is guaranteed to be non-zero, so it can
+/// always be inverted to unblind the salt.
+#[cfg(feature = "strong_aucpace")]
+#[inline]
+pub fn generate_blinding_scalar<CSPRNG>(rng: &mut CSPRNG) -> Result<SecretScalar>
+where
+    CSPRNG: TryRngCore + TryCryptoRng,
+{
+    let mut rand_bytes = SecretArray::<64>::zeroed();
+    loop {
+        fill_secret(rng, &mut rand_bytes)?;
+        let blinding = SecretScalar::new(Scalar::from_bytes_mod_order_wide(rand_bytes.expose()));
+        if *blinding.expose() != Scalar::ZERO {
+            return Ok(blinding);
+        }
+    }
+}
+
+/// Invert a blinding scalar to unblind with it, rejecting zero
+///
+/// Zero has no multiplicative inverse, [`Scalar::invert`] returns zero for it, which would
+/// silently unblind to the identity. The check is done in constant time.
+///
+/// # Return:
+/// - Ok(`inverse`): the multiplicative inverse of `s`
+/// - Err([`Error::ZeroScalar`](Error::ZeroScalar)): `s` is zero
+#[cfg(feature = "strong_aucpace")]
+#[inline]
+pub fn invert_blinding(s: &Scalar) -> Result<Scalar> {
+    if bool::from(s.ct_eq(&Scalar::ZERO)) {
+        return Err(Error::ZeroScalar);
+    }
+    Ok(s.invert())
+}
+
+/// Blind the salt point `Z` with the blinding scalar `r`, computing `U = Z^(r * c_J)`
+#[cfg(feature = "strong_aucpace")]
+#[inline]
+pub fn blind_salt(salt_point: RistrettoPoint, blinding: &Scalar) -> RistrettoPoint {
+    salt_point * (blinding * ristretto_cofactor())
+}
+
 /// Computes the SSID from two server and client nonces - s and t
+///
+/// The SSID is public in the normal protocol flow, where it is derived from the exchanged
+/// nonces; use [`compute_ssid_secret`] if it is derived from secret, pre-established material.
 #[inline]
 pub fn compute_ssid<D: Digest + Default, const K1: usize>(s: [u8; K1], t: [u8; K1]) -> Output<D> {
-    let mut hasher: D = H0();
-    hasher.update(s);
-    hasher.update(t);
+    compute_ssid_with_scheme::<D, LeU32HashScheme, K1>(s, t)
+}
+
+/// Computes the SSID like [`compute_ssid`], with `H0`'s domain tag encoded by the
+/// [`HashScheme`] `S`
+#[inline]
+pub(crate) fn compute_ssid_with_scheme<D, S, const K1: usize>(s: [u8; K1], t: [u8; K1]) -> Output<D>
+where
+    D: Digest + Default,
+    S: HashScheme,
+{
+    let mut hasher = SsidHasher::<D, S>::new();
+    hasher.absorb(s);
+    hasher.absorb(t);
     hasher.finalize()
 }
 
+/// Computes the SSID from nonces `s` and `t` like [`compute_ssid`], given as slices
+///
+/// For nonces received from the network, whose lengths are only known at runtime. The
+/// protocol requires both nonces to have the same length, which is checked before hashing.
+///
+/// # Return:
+/// - Ok(`ssid`): the SSID, equal to that of [`compute_ssid`] for the same nonces
+/// - Err([`Error::NonceLengthMismatch`](Error::NonceLengthMismatch)): `s` and `t` have
+///   different lengths
+#[inline]
+pub fn compute_ssid_slices<D: Digest + Default>(s: &[u8], t: &[u8]) -> Result<Output<D>> {
+    if s.len() != t.len() {
+        return Err(Error::NonceLengthMismatch);
+    }
+    let mut hasher = SsidHasher::<D>::new();
+    hasher.absorb(s);
+    hasher.absorb(t);
+    Ok(hasher.finalize())
+}
+
+/// Computes the SSID from `s` and `t` like [`compute_ssid`], returning it in a zeroizing wrapper
+///
+/// Use this when the inputs are secret pre-established material, so the SSID itself is
+/// sensitive and should not be left behind in memory. The intermediate digest output is
+/// zeroized once it has been copied into the returned `SecretKey`.
+pub fn compute_ssid_secret<D: Digest + Default, const K1: usize>(
+    s: [u8; K1],
+    t: [u8; K1],
+) -> SecretKey {
+    let mut ssid = compute_ssid::<D, K1>(s, t);
+    let secret = SecretKey::from(ssid.as_slice().to_vec());
+    ssid.as_mut_slice().zeroize();
+    secret
+}
+
+/// Incrementally computes the SSID `H0(s || t)`.
+///
+/// Useful when the server and client nonces arrive in different messages: absorb each
+/// nonce as it arrives, in the same order as [`compute_ssid`] (server nonce `s` first),
+/// and finalize once both have been received. `H0`'s domain tag is encoded by the
+/// [`HashScheme`] `S`.
+#[derive(Clone, Debug)]
+pub struct SsidHasher<D: Digest + Default, S: HashScheme = LeU32HashScheme> {
+    hasher: D,
+    scheme: PhantomData<S>,
+}
+
+impl<D: Digest + Default, S: HashScheme> SsidHasher<D, S> {
+    /// Create a new hasher, initialised with the `H0` domain label
+    pub fn new() -> Self {
+        Self {
+            hasher: H0::<D, S>(),
+            scheme: PhantomData,
+        }
+    }
+
+    /// Absorb the next part of the SSID input
+    pub fn absorb(&mut self, data: impl AsRef<[u8]>) {
+        self.hasher.update(data);
+    }
+
+    /// Finish absorbing and compute the SSID
+    pub fn finalize(self) -> Output<D> {
+        self.hasher.finalize()
+    }
+}
+
+impl<D: Digest + Default, S: HashScheme> Default for SsidHasher<D, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the ephemeral `CPace` generator `G = Map2Point(H1(ssid || PRS || CI))`.
+///
+/// This is the generator [`generate_keypair`] uses; it is exposed so that interop
+/// mismatches can be debugged by comparing generators between implementations.
+#[inline]
+pub fn cpace_generator<D, CI>(ssid: Output<D>, prs: [u8; 32], ci: CI) -> RistrettoPoint
+where
+    D: Digest<OutputSize = U64> + Default,
+    CI: AsRef<[u8]>,
+{
+    cpace_generator_with_scheme::<D, LeU32HashScheme, CI>(ssid, &prs, ci)
+}
+
+/// Compute the `CPace` generator like [`cpace_generator`], with `H1`'s domain tag encoded by
+/// the [`HashScheme`] `S`
+///
+/// This is useful to check a peer implementation's generators, the client and server state
+/// machines use the scheme they are instantiated with.
+pub fn cpace_generator_with_scheme<D, S, CI>(
+    ssid: Output<D>,
+    prs: &[u8; 32],
+    ci: CI,
+) -> RistrettoPoint
+where
+    D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
+    CI: AsRef<[u8]>,
+{
+    let mut hasher: D = H1::<D, S>();
+    hasher.update(ssid);
+    hasher.update(prs);
+    hasher.update(ci);
+
+    RistrettoPoint::from_hash(hasher)
+}
+
 /// Generate a Diffie-Hellman keypair for the `CPace` substep of the protocol.
 ///
 /// This function is fallible and will return `Err(Error::Rng)` if the provided
@@ -110,89 +492,405 @@ where
     CSPRNG: TryRngCore + TryCryptoRng,
     CI: AsRef<[u8]>,
 {
-    let mut hasher: D = H1();
-    hasher.update(ssid);
-    hasher.update(prs);
-    hasher.update(ci);
+    generate_keypair_ref::<D, CSPRNG, CI>(rng, ssid, &prs, ci)
+}
 
-    let generator = RistrettoPoint::from_hash(hasher);
-    let mut rng_bytes = [0u8; 64];
-    rng.try_fill_bytes(&mut rng_bytes).map_err(|_| Error::Rng)?;
-    let mut rng_hasher: D = Default::default();
-    rng_hasher.update(&rng_bytes);
-    let priv_key = Scalar::from_hash(rng_hasher);
-    let cofactor = Scalar::ONE;
-    let pub_key = generator * (priv_key * cofactor);
+/// Generate a Diffie-Hellman keypair for the `CPace` substep, borrowing the PRS.
+///
+/// Behaves exactly like [`generate_keypair`] but takes `prs` by reference, so callers
+/// threading the same PRS through several computations avoid copying it each time.
+/// The random bytes the private key is derived from are zeroized, also when the RNG fails.
+#[inline]
+pub fn generate_keypair_ref<D, CSPRNG, CI>(
+    rng: &mut CSPRNG,
+    ssid: Output<D>,
+    prs: &[u8; 32],
+    ci: CI,
+) -> Result<(Scalar, RistrettoPoint)>
+where
+    D: Digest<OutputSize = U64> + Default,
+    CSPRNG: TryRngCore + TryCryptoRng,
+    CI: AsRef<[u8]>,
+{
+    generate_keypair_with_scheme::<D, LeU32HashScheme, CSPRNG, CI>(rng, ssid, prs, ci)
+}
+
+/// Generate a `CPace` keypair like [`generate_keypair_ref`], with `H1`'s domain tag encoded by
+/// the [`HashScheme`] `S`
+#[inline]
+pub(crate) fn generate_keypair_with_scheme<D, S, CSPRNG, CI>(
+    rng: &mut CSPRNG,
+    ssid: Output<D>,
+    prs: &[u8; 32],
+    ci: CI,
+) -> Result<(Scalar, RistrettoPoint)>
+where
+    D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
+    CSPRNG: TryRngCore + TryCryptoRng,
+    CI: AsRef<[u8]>,
+{
+    let mut rng_bytes = SecretArray::<64>::zeroed();
+    let priv_key = scalar_from_seed::<D, CSPRNG>(rng, &mut rng_bytes)?;
+
+    let generator = cpace_generator_with_scheme::<D, S, CI>(ssid, prs, ci);
+    Ok((priv_key, generator * (priv_key * ristretto_cofactor())))
+}
+
+/// Generate a Diffie-Hellman keypair for the `CPace` substep, writing the public key into `out_pub`.
+///
+/// Behaves exactly like [`generate_keypair`], but the public key is written as its 32 byte
+/// compressed encoding into the caller's buffer, e.g. a network buffer, and only the private
+/// key is returned. `out_pub` is left unchanged if the RNG fails.
+#[inline]
+pub fn generate_keypair_into<D, CSPRNG, CI>(
+    rng: &mut CSPRNG,
+    ssid: Output<D>,
+    prs: [u8; 32],
+    ci: CI,
+    out_pub: &mut [u8; 32],
+) -> Result<Scalar>
+where
+    D: Digest<OutputSize = U64> + Default,
+    CSPRNG: TryRngCore + TryCryptoRng,
+    CI: AsRef<[u8]>,
+{
+    let (priv_key, pub_key) = generate_keypair_ref::<D, CSPRNG, CI>(rng, ssid, &prs, ci)?;
+    *out_pub = pub_key.compress().to_bytes();
+    Ok(priv_key)
+}
+
+/// Compute a `CPace` keypair from a caller-supplied private key, bypassing the RNG.
+///
+/// This is the deterministic part of [`generate_keypair_ref`]: the public key is
+/// `G^(scalar * c_J)` for the generator `G` derived from `ssid`, `prs` and `ci`. It is meant
+/// for tests, e.g. of a peer which reuses its private key; protocol code must use a fresh
+/// random scalar for every session.
+#[inline]
+pub fn keypair_from_scalar<D, CI>(
+    scalar: Scalar,
+    ssid: Output<D>,
+    prs: &[u8; 32],
+    ci: CI,
+) -> (Scalar, RistrettoPoint)
+where
+    D: Digest<OutputSize = U64> + Default,
+    CI: AsRef<[u8]>,
+{
+    let generator = cpace_generator_with_scheme::<D, LeU32HashScheme, CI>(ssid, prs, ci);
+    let pub_key = generator * (scalar * ristretto_cofactor());
 
-    Ok((priv_key, pub_key))
+    (scalar, pub_key)
 }
 
-/// Compute the first session key sk1 from our private key and the other participant's public key
+/// Compute the first session key sk1 from our private key and the other participant's public
+/// key, with `H2`'s domain tag encoded by the [`HashScheme`] `S`
 #[inline]
-pub fn compute_first_session_key<D>(
+pub(crate) fn compute_first_session_key_with_scheme<D, S>(
     ssid: Output<D>,
     priv_key: Scalar,
     pub_key: RistrettoPoint,
 ) -> Output<D>
 where
     D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
 {
     let shared_point = pub_key * priv_key;
 
-    let mut hasher: D = H2();
+    let mut hasher: D = H2::<D, S>();
     hasher.update(ssid);
     hasher.update(shared_point.compress().to_bytes());
 
     hasher.finalize()
 }
 
+/// Compute the first session key sk1 from a wrapped private key and the other participant's
+/// public key
+///
+/// The private scalar is only borrowed from the [`SecretScalar`], and the encoding of the
+/// shared point is zeroized once it has been hashed.
+#[inline]
+pub fn compute_first_session_key_ref<D>(
+    ssid: Output<D>,
+    priv_key: &SecretScalar,
+    pub_key: RistrettoPoint,
+) -> Output<D>
+where
+    D: Digest<OutputSize = U64> + Default,
+{
+    let mut shared_point = (pub_key * priv_key.expose()).compress().to_bytes();
+
+    let mut hasher: D = H2::<D, LeU32HashScheme>();
+    hasher.update(ssid);
+    hasher.update(shared_point);
+    shared_point.zeroize();
+
+    hasher.finalize()
+}
+
 /// Compute the two authenticator messages Ta and Tb
 #[inline]
 pub fn compute_authenticator_messages<D>(ssid: Output<D>, sk1: Output<D>) -> (Output<D>, Output<D>)
 where
     D: Digest<OutputSize = U64> + Default,
 {
-    let mut ta_hasher: D = H3();
+    compute_authenticator_messages_with_scheme::<D, LeU32HashScheme>(ssid, sk1)
+}
+
+/// Compute the two authenticator messages Ta and Tb like [`compute_authenticator_messages`],
+/// with the domain tags of `H3` and `H4` encoded by the [`HashScheme`] `S`
+#[inline]
+pub(crate) fn compute_authenticator_messages_with_scheme<D, S>(
+    ssid: Output<D>,
+    sk1: Output<D>,
+) -> (Output<D>, Output<D>)
+where
+    D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
+{
+    let mut ta_hasher: D = H3::<D, S>();
     ta_hasher.update(ssid);
     ta_hasher.update(sk1);
 
-    let mut tb_hasher: D = H4();
+    let mut tb_hasher: D = H4::<D, S>();
     tb_hasher.update(ssid);
     tb_hasher.update(sk1);
 
     (ta_hasher.finalize(), tb_hasher.finalize())
 }
 
+/// Compute the two authenticator messages Ta and Tb, returning them in zeroizing wrappers
+///
+/// Like [`compute_authenticator_messages`], for callers who want the key confirmation material
+/// derived from `sk1` to be zeroized once it has been sent and compared. The intermediate
+/// digest outputs are zeroized once they have been copied into the wrappers.
+pub fn compute_authenticator_messages_secret<D>(
+    ssid: Output<D>,
+    sk1: Output<D>,
+) -> (SecretBytes, SecretBytes)
+where
+    D: Digest<OutputSize = U64> + Default,
+{
+    let (mut ta, mut tb) = compute_authenticator_messages::<D>(ssid, sk1);
+    let secrets = (
+        SecretBytes::new(ta.as_slice().to_vec()),
+        SecretBytes::new(tb.as_slice().to_vec()),
+    );
+    ta.as_mut_slice().zeroize();
+    tb.as_mut_slice().zeroize();
+    secrets
+}
+
+/// Compute the tag `H6(ssid)` confirming the SSID to the other participant
+///
+/// The client sends it with its username, so that the server can detect that the two sides
+/// computed different SSIDs before any key is derived. `H6`'s domain tag keeps it distinct from
+/// every other hash of the SSID, and the SSID cannot be recovered from it.
+#[inline]
+pub(crate) fn compute_ssid_tag<D, S>(ssid: Output<D>) -> Output<D>
+where
+    D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
+{
+    let mut hasher: D = H6::<D, S>();
+    hasher.update(ssid);
+    hasher.finalize()
+}
+
 /// Compute the session key - sk
+#[cfg(any(test, feature = "secret-wrappers"))]
 #[inline]
 pub fn compute_session_key<D>(ssid: Output<D>, sk1: Output<D>) -> Output<D>
 where
     D: Digest<OutputSize = U64> + Default,
 {
-    let mut hasher: D = H5();
+    compute_session_key_with_scheme::<D, LeU32HashScheme>(ssid, sk1)
+}
+
+/// Compute the session key like [`compute_session_key`], with `H5`'s domain tag encoded by the
+/// [`HashScheme`] `S`
+#[inline]
+pub(crate) fn compute_session_key_with_scheme<D, S>(ssid: Output<D>, sk1: Output<D>) -> Output<D>
+where
+    D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
+{
+    let mut hasher: D = H5::<D, S>();
     hasher.update(ssid);
     hasher.update(sk1);
     hasher.finalize()
 }
 
-/// Compute a scalar from a password hash
+/// Compute the session key binding both `CPace` public keys, `sk = H5(ssid || sk1 || Ya || Yb)`
+///
+/// [`compute_session_key`] only absorbs `ssid || sk1`, some `CPace` formulations also hash the
+/// exchanged public keys so that the key is bound to the full transcript. The keys are absorbed
+/// by role, never by local and remote: `ya` is always the client's public key and `yb` the
+/// server's, each as its 32 byte compressed encoding, so both sides hash them in the same
+/// order. The result differs from [`compute_session_key`], both sides must use this variant.
 #[inline]
-pub fn scalar_from_hash(pw_hash: &PasswordHash<'_>) -> Result<Scalar> {
-    let hash = pw_hash.hash.ok_or(Error::HashEmpty)?;
-    let hash_bytes = hash.as_bytes();
-
-    // support both 32 and 64 byte hashes
-    match hash_bytes.len() {
-        32 => {
-            let arr: [u8; 32] = hash_bytes.try_into().map_err(|_| Error::HashSizeInvalid)?;
-            Ok(Scalar::from_bytes_mod_order(arr))
-        }
-        64 => {
-            let arr: [u8; 64] = hash_bytes.try_into().map_err(|_| Error::HashSizeInvalid)?;
-            Ok(Scalar::from_bytes_mod_order_wide(&arr))
-        }
-        _ => Err(Error::HashSizeInvalid),
+pub fn compute_session_key_bound<D>(
+    ssid: Output<D>,
+    sk1: Output<D>,
+    ya: &RistrettoPoint,
+    yb: &RistrettoPoint,
+) -> Output<D>
+where
+    D: Digest<OutputSize = U64> + Default,
+{
+    let mut hasher: D = H5::<D, LeU32HashScheme>();
+    hasher.update(ssid);
+    hasher.update(sk1);
+    hasher.update(ya.compress().as_bytes());
+    hasher.update(yb.compress().as_bytes());
+    hasher.finalize()
+}
+
+/// Check the client's key confirmation message and produce the server's.
+///
+/// The received authenticator is compared in constant time against the locally computed
+/// `Tb = H4(ssid || sk1)`. On success the server's authenticator `Ta = H3(ssid || sk1)` and
+/// the session key `sk = H5(ssid || sk1)` are returned, otherwise
+/// `Err(Error::KeyConfirmationFailed)`.
+#[inline]
+pub fn verify_session_key_confirmation<D>(
+    ssid: Output<D>,
+    sk1: Output<D>,
+    client_authenticator: [u8; 64],
+) -> Result<([u8; 64], SecretKey)>
+where
+    D: Digest<OutputSize = U64> + Default,
+{
+    verify_session_key_confirmation_with_scheme::<D, LeU32HashScheme>(
+        ssid,
+        sk1,
+        client_authenticator,
+    )
+}
+
+/// Check the client's key confirmation message like [`verify_session_key_confirmation`], with
+/// the domain tags of `H3..H5` encoded by the [`HashScheme`] `S`
+#[inline]
+pub(crate) fn verify_session_key_confirmation_with_scheme<D, S>(
+    ssid: Output<D>,
+    sk1: Output<D>,
+    client_authenticator: [u8; 64],
+) -> Result<([u8; 64], SecretKey)>
+where
+    D: Digest<OutputSize = U64> + Default,
+    S: HashScheme,
+{
+    let (ta, tb) = compute_authenticator_messages_with_scheme::<D, S>(ssid, sk1);
+    if tb.ct_eq(&client_authenticator).into() {
+        let sk = compute_session_key_with_scheme::<D, S>(ssid, sk1);
+        // unreachable: `D::OutputSize` is `U64`, so the slice is always 64 bytes long
+        let ta_arr = ta
+            .as_slice()
+            .try_into()
+            .map_err(|_| Error::InternalInvariant)?;
+        Ok((ta_arr, SecretKey::from(sk.as_slice().to_vec())))
+    } else {
+        Err(Error::KeyConfirmationFailed)
+    }
+}
+
+/// Normalize a password with the SASLprep profile ([RFC 4013]) before it is hashed
+///
+/// SASLprep maps non-ASCII spaces to U+0020, removes characters which are commonly mapped to
+/// nothing, applies Unicode NFKC normalization and then rejects prohibited code points (control
+/// characters, private use and non-character code points, surrogates, ...) as well as invalid
+/// bidirectional text. This ensures the same password typed on different platforms yields the
+/// same verifier, so the normalized password must be used both at registration and at login.
+///
+/// ASCII passwords without control characters are returned unchanged. The normalized password
+/// is returned in a zeroize-on-drop `SecretString`, however the intermediate buffers used by
+/// the normalization itself are not zeroized.
+///
+/// # Return:
+/// - Ok(`password`): the normalized password
+/// - Err([`Error::PasswordNormalization`](Error::PasswordNormalization)): the password contains
+///   text prohibited by the profile
+///
+/// [RFC 4013]: https://datatracker.ietf.org/doc/html/rfc4013
+#[cfg(feature = "stringprep")]
+pub fn normalize_password(input: &str) -> Result<SecretString> {
+    // `into_owned` moves an already normalized password into the wrapper without copying it
+    stringprep::saslprep(input)
+        .map(|password| SecretString::new(password.into_owned()))
+        .map_err(|_| Error::PasswordNormalization)
+}
+
+/// Parse stored or received PBKDF parameters, e.g. read from a database column
+///
+/// # Return:
+/// - Ok(`params`): the parsed parameters
+/// - Err([`Error::ParamsTooLong`](Error::ParamsTooLong)): `s` is longer than
+///   [`MAX_PARAMS_LEN`](crate::constants::MAX_PARAMS_LEN)
+/// - Err([`Error::PasswordHashing`](Error::PasswordHashing)): `s` is not a valid PHC
+///   parameter string
+pub fn parse_params(s: &str) -> Result<ParamsString> {
+    if s.len() > crate::constants::MAX_PARAMS_LEN {
+        return Err(Error::ParamsTooLong);
+    }
+    s.parse().map_err(Error::PasswordHashing)
+}
+
+/// Compute a scalar from a password hash
+///
+/// 32 and 64 byte hashes are supported, both are read as a little-endian integer and reduced
+/// modulo the group order: a 32 byte hash gives the same scalar as
+/// `Scalar::from_bytes_mod_order`, a 64 byte hash the same as
+/// `Scalar::from_bytes_mod_order_wide`. Other lengths return
+/// [`Error::HashSizeInvalid`](Error::HashSizeInvalid).
+///
+/// The length is public, it is only checked to reject unsupported hashes. Every supported hash
+/// then takes the same path: it is zero-extended into a 64 byte buffer, which leaves its value
+/// unchanged, and reduced with a wide reduction. The buffer is zeroized once it is reduced.
+#[inline]
+pub fn scalar_from_hash(pw_hash: &PasswordHash<'_>) -> Result<Scalar> {
+    let hash = pw_hash.hash.ok_or(Error::HashEmpty)?;
+    let hash_bytes = hash.as_bytes();
+    if !matches!(hash_bytes.len(), 32 | 64) {
+        return Err(Error::HashSizeInvalid);
     }
+
+    let mut wide = SecretArray::<64>::zeroed();
+    wide.expose_mut()[..hash_bytes.len()].copy_from_slice(hash_bytes);
+    Ok(scalar_from_wide_hash(wide.expose()))
+}
+
+/// Compute the password verifier `W = B^(w * c_J)` from a password hash
+///
+/// `w` is computed with [`scalar_from_hash`], this is the verifier clients register and servers
+/// store.
+///
+/// # Return:
+/// - Ok(`verifier`): the password verifier `W`
+/// - Err([`Error::HashEmpty`](Error::HashEmpty) | [`Error::HashSizeInvalid`](Error::HashSizeInvalid)):
+///   the hash is missing or is not 32 or 64 bytes long
+#[inline]
+pub fn verifier_point_from_hash(pw_hash: &PasswordHash<'_>) -> Result<RistrettoPoint> {
+    let w = scalar_from_hash(pw_hash)?;
+    Ok(basepoint_mul(&(w * ristretto_cofactor())))
+}
+
+/// Compute the compressed encoding of the password verifier `W` from a password hash
+///
+/// Like [`verifier_point_from_hash`], returning the 32 bytes `W` is stored and sent as.
+#[inline]
+pub fn verifier_bytes_from_hash(pw_hash: &PasswordHash<'_>) -> Result<[u8; 32]> {
+    Ok(verifier_point_from_hash(pw_hash)?.compress().to_bytes())
+}
+
+/// Compute a scalar from a 64 byte hash output
+///
+/// This is the infallible fast path of [`scalar_from_hash`] for callers which already hold
+/// the hash as a `&[u8; 64]`, it skips the length check and the copy into a padded buffer.
+/// The saving is small (a 64 byte copy per call) but measurable for high-throughput servers,
+/// see the `scalar_from_hash` benchmark.
+#[inline]
+pub fn scalar_from_wide_hash(hash: &[u8; 64]) -> Scalar {
+    Scalar::from_bytes_mod_order_wide(hash)
 }
 
 /// Generate a keypair (x, X) for the server
@@ -218,24 +916,169 @@ where
     D: Digest<OutputSize = U64> + Default,
     CSPRNG: TryRngCore + TryCryptoRng,
 {
-    // for ristretto255 the cofactor is 1, for normal curve25519 it is 8
-    // this will need to be provided by a group trait in the future
-    let cofactor = Scalar::ONE;
-    let mut rng_bytes = [0u8; 64];
-    rng.try_fill_bytes(&mut rng_bytes).map_err(|_| Error::Rng)?;
-    let mut rng_hasher: D = Default::default();
-    rng_hasher.update(&rng_bytes);
-    let private = Scalar::from_hash(rng_hasher);
-    let public = RISTRETTO_BASEPOINT_POINT * (private * cofactor);
+    generate_server_keypair_with_cofactor::<D, CSPRNG>(rng, ristretto_cofactor())
+}
+
+/// Generate a keypair (x, X) for the server, clearing the cofactor `c_J` of the group
+///
+/// Computes `X = B^(x * cofactor)`. [`generate_server_keypair`] calls this with the ristretto255
+/// cofactor of one; other cofactors are only meaningful once groups other than ristretto255 are
+/// supported, as the basepoint is always `RISTRETTO_BASEPOINT_POINT`.
+///
+/// Like [`generate_server_keypair`], this returns `Err(Error::Rng)` if the RNG fails.
+#[inline]
+pub fn generate_server_keypair_with_cofactor<D, CSPRNG>(
+    rng: &mut CSPRNG,
+    cofactor: Scalar,
+) -> Result<(Scalar, RistrettoPoint)>
+where
+    D: Digest<OutputSize = U64> + Default,
+    CSPRNG: TryRngCore + TryCryptoRng,
+{
+    let (private, public) = server_keypair_secret::<D, CSPRNG>(rng, cofactor)?;
+    Ok((*private.expose(), public))
+}
+
+/// Generate the server's keypair `(x, X = B^(x * cofactor))` with `x` wrapped from the start
+///
+/// The random bytes `x` is derived from are zeroized, also when the RNG fails after writing
+/// some of them, and `x` is only ever held in a [`SecretScalar`] so it is zeroized on every
+/// return path.
+#[inline]
+fn server_keypair_secret<D, CSPRNG>(
+    rng: &mut CSPRNG,
+    cofactor: Scalar,
+) -> Result<(SecretScalar, RistrettoPoint)>
+where
+    D: Digest<OutputSize = U64> + Default,
+    CSPRNG: TryRngCore + TryCryptoRng,
+{
+    let mut rng_bytes = SecretArray::<64>::zeroed();
+    let private = SecretScalar::new(scalar_from_seed::<D, CSPRNG>(rng, &mut rng_bytes)?);
+    let public = basepoint_mul(&(private.expose() * cofactor));
 
     Ok((private, public))
 }
 
+/// Generate a `CPace` keypair like [`generate_keypair_ref`], wrapping the private key
+///
+/// The private scalar `ya`/`yb` is returned as a [`SecretScalar`] and so zeroized on drop.
+#[cfg(feature = "secret-wrappers")]
+#[inline]
+pub fn generate_keypair_secret<D, CSPRNG, CI>(
+    rng: &mut CSPRNG,
+    ssid: Output<D>,
+    prs: &[u8; 32],
+    ci: CI,
+) -> Result<(SecretScalar, RistrettoPoint)>
+where
+    D: Digest<OutputSize = U64> + Default,
+    CSPRNG: TryRngCore + TryCryptoRng,
+    CI: AsRef<[u8]>,
+{
+    let (priv_key, pub_key) = generate_keypair_ref::<D, CSPRNG, CI>(rng, ssid, prs, ci)?;
+    Ok((SecretScalar::new(priv_key), pub_key))
+}
+
+/// Generate the server's keypair `(x, X)` like [`generate_server_keypair`], wrapping the
+/// private key
+///
+/// The private scalar `x` is returned as a [`SecretScalar`] and so zeroized on drop.
+#[cfg(feature = "secret-wrappers")]
+#[inline]
+pub fn generate_server_keypair_secret<D, CSPRNG>(
+    rng: &mut CSPRNG,
+) -> Result<(SecretScalar, RistrettoPoint)>
+where
+    D: Digest<OutputSize = U64> + Default,
+    CSPRNG: TryRngCore + TryCryptoRng,
+{
+    server_keypair_secret::<D, CSPRNG>(rng, ristretto_cofactor())
+}
+
+/// Compute the first session key `sk1 = H2(ssid || K)` from a wrapped private key, returning
+/// it in a zeroizing wrapper
+///
+/// Like [`compute_first_session_key_ref`], the digest output is zeroized once it has been copied
+/// into the returned [`SecretKey`].
+#[cfg(feature = "secret-wrappers")]
+#[inline]
+pub fn compute_first_session_key_secret<D>(
+    ssid: Output<D>,
+    priv_key: &SecretScalar,
+    pub_key: RistrettoPoint,
+) -> SecretKey
+where
+    D: Digest<OutputSize = U64> + Default,
+{
+    SecretKey::from(compute_first_session_key_ref::<D>(ssid, priv_key, pub_key))
+}
+
+/// Compute the session key `sk = H5(ssid || sk1)` from a wrapped first session key,
+/// returning it in a zeroizing wrapper
+///
+/// `sk1` is the output of [`compute_first_session_key_secret`]. The key is derived by
+/// [`compute_session_key`], the copy of `sk1` passed to it and the digest output are zeroized
+/// once the result has been copied into the returned [`SecretKey`].
+///
+/// # Panics
+/// If `sk1` is not 64 bytes long, the output size of `D`.
+#[cfg(feature = "secret-wrappers")]
+#[inline]
+pub fn compute_session_key_secret<D>(ssid: Output<D>, sk1: &SecretKey) -> SecretKey
+where
+    D: Digest<OutputSize = U64> + Default,
+{
+    let mut sk1 = Output::<D>::clone_from_slice(sk1.expose());
+    let sk = compute_session_key::<D>(ssid, sk1);
+    sk1.as_mut_slice().zeroize();
+    SecretKey::from(sk)
+}
+
+/// Check that a received point is not the identity, in constant time
+///
+/// Returns `Choice(1)` for a valid point and `Choice(0)` for the identity. Unlike
+/// [`IsIdentity::is_identity`](curve25519_dalek::traits::IsIdentity::is_identity) the result
+/// is a [`Choice`], so it can be folded into a larger constant-time decision before branching.
+#[inline]
+pub fn point_is_valid_ct(p: &RistrettoPoint) -> Choice {
+    !p.ct_eq(&RistrettoPoint::identity())
+}
+
+/// Compute the server's password related string (PRS) from the user's verifier and the
+/// server's private scalar
+///
+/// `PRS = W^(x * c_J)`, encoded as the compressed ristretto255 point, where `W = B^(w * c_J)`
+/// is the stored verifier, `x` is the scalar of the server keypair `(x, X)` and `c_J` is the
+/// cofactor. The client computes the same string from `X` and `w` with [`client_prs`], as
+/// `X^(w * c_J) = B^(x * w * c_J * c_J) = W^(x * c_J)`.
+#[inline]
+pub fn server_prs(verifier: &RistrettoPoint, x: &Scalar) -> [u8; 32] {
+    (verifier * (x * ristretto_cofactor()))
+        .compress()
+        .to_bytes()
+}
+
+/// Compute the client's password related string (PRS) from the server's public key and the
+/// client's password scalar
+///
+/// `PRS = X^(w * c_J)`, encoded as the compressed ristretto255 point, where `X` is the public
+/// key sent in the augmentation info and `w` is the scalar derived from the password hash,
+/// see [`server_prs`] for why both sides agree.
+#[inline]
+pub fn client_prs(x_pub: &RistrettoPoint, w: &Scalar) -> [u8; 32] {
+    (x_pub * (w * ristretto_cofactor())).compress().to_bytes()
+}
+
 // serde_with helper modules for serialising
+//
+// Salts are serialised in their canonical form: unpadded base64, exactly as returned by
+// `SaltString::as_str`. Deserialisation only accepts that same form, so padded input, or
+// input whose trailing bits do not re-encode to the same string, is rejected.
 #[cfg(feature = "serde")]
 pub mod serde_saltstring {
     use core::fmt;
-    use password_hash::SaltString;
+    use password_hash::{Salt, SaltString};
     use serde::de::{Error, Visitor};
     use serde::{Deserializer, Serializer};
 
@@ -252,14 +1095,28 @@ pub mod serde_saltstring {
         type Value = SaltString;
 
         fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(formatter, "a valid ASCII salt string")
+            write!(formatter, "a canonical unpadded base64 salt string")
         }
 
         fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
         where
             E: Error,
         {
-            SaltString::from_b64(v).map_err(Error::custom)
+            if v.ends_with('=') {
+                return Err(Error::custom("salt must be unpadded base64"));
+            }
+
+            let salt = SaltString::from_b64(v).map_err(Error::custom)?;
+
+            // re-encode the decoded bytes to ensure the input was canonical
+            let mut buf = [0u8; Salt::MAX_LENGTH];
+            let decoded = salt.decode_b64(&mut buf).map_err(Error::custom)?;
+            let canonical = SaltString::encode_b64(decoded).map_err(Error::custom)?;
+            if canonical.as_str() != v {
+                return Err(Error::custom("salt is not canonical unpadded base64"));
+            }
+
+            Ok(salt)
         }
     }
 
@@ -298,7 +1155,7 @@ pub mod serde_paramsstring {
         where
             E: Error,
         {
-            v.parse().map_err(Error::custom)
+            crate::utils::parse_params(v).map_err(Error::custom)
         }
     }
 
@@ -309,3 +1166,853 @@ pub mod serde_paramsstring {
         deserializer.deserialize_str(ParamsStringVisitor {})
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// In a prime-order group cofactor clearing must be a no-op, so any cofactor which moves
+    /// the basepoint belongs to a different group.
+    fn cofactor_matches_basepoint(cofactor: Scalar, basepoint: RistrettoPoint) -> bool {
+        basepoint * cofactor == basepoint
+    }
+
+    #[test]
+    fn test_ristretto_cofactor_matches_basepoint() {
+        assert_eq!(ristretto_cofactor(), Scalar::ONE);
+        assert!(cofactor_matches_basepoint(
+            ristretto_cofactor(),
+            RISTRETTO_BASEPOINT_POINT
+        ));
+    }
+
+    #[test]
+    fn test_curve25519_cofactor_clears_eight_torsion() {
+        use curve25519_dalek::constants::EIGHT_TORSION;
+        use curve25519_dalek::traits::IsIdentity;
+
+        // clearing the torsion of raw curve25519 takes its cofactor of 8, the ristretto255
+        // cofactor leaves it in place: only ristretto's encoding has no torsion to clear
+        for torsion in EIGHT_TORSION {
+            assert!((torsion * Scalar::from(8u8)).is_identity());
+        }
+        assert!(
+            EIGHT_TORSION[1..]
+                .iter()
+                .all(|torsion| !(torsion * ristretto_cofactor()).is_identity())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_compute_first_session_key_ref_matches_unwrapped() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let priv_key = Scalar::from(0x1701u32);
+        let pub_key = RISTRETTO_BASEPOINT_POINT * Scalar::from(0x1864u32);
+
+        let sk1 = compute_first_session_key_with_scheme::<sha2::Sha512, LeU32HashScheme>(
+            ssid, priv_key, pub_key,
+        );
+        let wrapped = compute_first_session_key_ref::<sha2::Sha512>(
+            ssid,
+            &SecretScalar::new(priv_key),
+            pub_key,
+        );
+        assert_eq!(sk1, wrapped);
+    }
+
+    #[test]
+    #[cfg(all(feature = "sha2", feature = "secret-wrappers"))]
+    fn test_compute_session_key_secret_matches_unwrapped() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let sk1 = H2::<sha2::Sha512, LeU32HashScheme>()
+            .chain_update([2u8; 64])
+            .finalize();
+
+        let sk = compute_session_key::<sha2::Sha512>(ssid, sk1);
+        let wrapped =
+            compute_session_key_secret::<sha2::Sha512>(ssid, &SecretKey::from(sk1.to_vec()));
+        assert_eq!(wrapped.expose(), sk.as_slice());
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_keypair_from_scalar_is_deterministic() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let scalar = Scalar::from(0x1701u32);
+
+        let (priv_key, pub_key) =
+            keypair_from_scalar::<sha2::Sha512, _>(scalar, ssid, &[2; 32], b"ci");
+        let (_, reused) = keypair_from_scalar::<sha2::Sha512, _>(scalar, ssid, &[2; 32], b"ci");
+        assert_eq!(priv_key, scalar);
+        assert_eq!(pub_key, reused);
+
+        let generator = cpace_generator::<sha2::Sha512, _>(ssid, [2; 32], b"ci");
+        assert_eq!(pub_key, generator * scalar);
+
+        // a different session gives the same scalar a different public key
+        let (_, other) = keypair_from_scalar::<sha2::Sha512, _>(scalar, ssid, &[3; 32], b"ci");
+        assert_ne!(pub_key, other);
+    }
+
+    #[test]
+    fn test_point_is_valid_ct_matches_is_identity() {
+        use curve25519_dalek::traits::IsIdentity;
+
+        let identity = RistrettoPoint::identity();
+        assert!(identity.is_identity());
+        assert!(!bool::from(point_is_valid_ct(&identity)));
+
+        let point = RistrettoPoint::from_uniform_bytes(&[0x5a; 64]);
+        assert!(!point.is_identity());
+        assert!(bool::from(point_is_valid_ct(&point)));
+    }
+
+    #[test]
+    fn test_prs_known_answer() {
+        let x = Scalar::from(3u64);
+        let w = Scalar::from(5u64);
+        let x_pub = RISTRETTO_BASEPOINT_POINT * x;
+        let verifier = RISTRETTO_BASEPOINT_POINT * w;
+
+        let prs = server_prs(&verifier, &x);
+        assert_eq!(prs, client_prs(&x_pub, &w));
+        // both sides compute B^(x * w) = B^15 with the ristretto255 cofactor of one, the
+        // encoding of 15B is listed in the multiples of the generator in RFC 9496, appendix A.1
+        let expected = [
+            0xe0, 0xc4, 0x18, 0xf7, 0xc8, 0xd9, 0xc4, 0xcd, 0xd7, 0x39, 0x5b, 0x93, 0xea, 0x12,
+            0x4f, 0x3a, 0xd9, 0x90, 0x21, 0xbb, 0x68, 0x1d, 0xfc, 0x33, 0x02, 0xa9, 0xd9, 0x9a,
+            0x2e, 0x53, 0xe6, 0x4e,
+        ];
+        assert_eq!(prs, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "stringprep")]
+    fn test_normalize_password_equivalent_forms() {
+        // precomposed U+00E9 and "e" + combining acute accent U+0301 normalize to the same password
+        let precomposed = normalize_password("caf\u{e9}").unwrap();
+        let decomposed = normalize_password("cafe\u{301}").unwrap();
+        assert_eq!(precomposed.expose(), decomposed.expose());
+
+        // non-ASCII spaces are mapped to U+0020 and soft hyphens are removed
+        let password = normalize_password("correct\u{a0}horse\u{ad}battery").unwrap();
+        assert_eq!(password.expose(), "correct horsebattery");
+
+        // ASCII passwords pass through unchanged
+        assert_eq!(normalize_password("hunter2").unwrap().expose(), "hunter2");
+    }
+
+    #[test]
+    #[cfg(feature = "stringprep")]
+    fn test_normalize_password_rejects_prohibited_characters() {
+        for password in ["pass\u{7}word", "pass\u{e000}word", "pass\u{fffe}word"] {
+            assert_eq!(
+                normalize_password(password).unwrap_err(),
+                Error::PasswordNormalization
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_compute_authenticator_messages_secret_matches_raw() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([3u8; 16], [4u8; 16]);
+        let sk1 = H0::<sha2::Sha512, LeU32HashScheme>()
+            .chain_update([5u8; 64])
+            .finalize();
+
+        let (ta, tb) = compute_authenticator_messages::<sha2::Sha512>(ssid, sk1);
+        let (ta_secret, tb_secret) =
+            compute_authenticator_messages_secret::<sha2::Sha512>(ssid, sk1);
+        assert_eq!(ta_secret.expose(), ta.as_slice());
+        assert_eq!(tb_secret.expose(), tb.as_slice());
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_compute_ssid_secret_matches_compute_ssid() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([3u8; 16], [4u8; 16]);
+        let secret = compute_ssid_secret::<sha2::Sha512, 16>([3u8; 16], [4u8; 16]);
+        assert_eq!(secret.expose(), ssid.as_slice());
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_compute_ssid_slices() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([3u8; 16], [4u8; 16]);
+        let from_slices = compute_ssid_slices::<sha2::Sha512>(&[3u8; 16], &[4u8; 16]);
+        assert_eq!(from_slices, Ok(ssid));
+
+        let res = compute_ssid_slices::<sha2::Sha512>(&[3u8; 16], &[4u8; 15]);
+        assert_eq!(res, Err(Error::NonceLengthMismatch));
+        let res = compute_ssid_slices::<sha2::Sha512>(&[], &[4u8; 16]);
+        assert_eq!(res, Err(Error::NonceLengthMismatch));
+    }
+
+    #[test]
+    fn test_parse_params_length_limit() {
+        use crate::constants::MAX_PARAMS_LEN;
+
+        // `a=xxx..,b=xxx..` of exactly the limit is accepted, one more byte is rejected; values
+        // are at most 64 bytes long, so two pairs are needed
+        let mut buf = [b'x'; MAX_PARAMS_LEN + 1];
+        buf[..2].copy_from_slice(b"a=");
+        buf[66..69].copy_from_slice(b",b=");
+        let at_limit = core::str::from_utf8(&buf[..MAX_PARAMS_LEN]).unwrap();
+        let over_limit = core::str::from_utf8(&buf).unwrap();
+        assert_eq!(parse_params(at_limit).unwrap().as_str(), at_limit);
+        assert_eq!(parse_params(over_limit), Err(Error::ParamsTooLong));
+
+        // the limit is `password_hash`'s own
+        assert!(at_limit.parse::<ParamsString>().is_ok());
+        assert!(over_limit.parse::<ParamsString>().is_err());
+
+        assert!(matches!(
+            parse_params("not params"),
+            Err(Error::PasswordHashing(_))
+        ));
+    }
+
+    #[test]
+    fn test_scalar_from_hash_wide_fast_path() {
+        let bytes: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let pw_hash = PasswordHash {
+            algorithm: password_hash::Ident::new("test").unwrap(),
+            version: None,
+            params: Default::default(),
+            salt: None,
+            hash: Some(password_hash::Output::new(&bytes).unwrap()),
+        };
+
+        let expected = Scalar::from_bytes_mod_order_wide(&bytes);
+        assert_eq!(scalar_from_wide_hash(&bytes), expected);
+        assert_eq!(scalar_from_hash(&pw_hash).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_scalar_from_hash_supported_lengths() {
+        fn scalar_for(bytes: &[u8]) -> Result<Scalar> {
+            let pw_hash = PasswordHash {
+                algorithm: password_hash::Ident::new("test").unwrap(),
+                version: None,
+                params: Default::default(),
+                salt: None,
+                hash: Some(password_hash::Output::new(bytes).unwrap()),
+            };
+            scalar_from_hash(&pw_hash)
+        }
+
+        // all ones exceeds the group order, so both reductions are exercised
+        let narrow = [0xffu8; 32];
+        assert_eq!(
+            scalar_for(&narrow).unwrap(),
+            Scalar::from_bytes_mod_order(narrow)
+        );
+        let wide = [0xffu8; 64];
+        assert_eq!(
+            scalar_for(&wide).unwrap(),
+            Scalar::from_bytes_mod_order_wide(&wide)
+        );
+
+        for len in [16, 31, 33, 48, 63] {
+            assert_eq!(scalar_for(&[0xff; 64][..len]), Err(Error::HashSizeInvalid));
+        }
+    }
+
+    #[test]
+    fn test_verifier_from_hash() {
+        use curve25519_dalek::ristretto::CompressedRistretto;
+
+        let mut pw_hash = PasswordHash {
+            algorithm: password_hash::Ident::new("test").unwrap(),
+            version: None,
+            params: Default::default(),
+            salt: None,
+            hash: Some(password_hash::Output::new(&[0x42; 64]).unwrap()),
+        };
+        let verifier = verifier_point_from_hash(&pw_hash).unwrap();
+        assert_eq!(
+            verifier,
+            RISTRETTO_BASEPOINT_POINT * scalar_from_hash(&pw_hash).unwrap()
+        );
+        let bytes = verifier_bytes_from_hash(&pw_hash).unwrap();
+        assert_eq!(CompressedRistretto(bytes).decompress(), Some(verifier));
+
+        pw_hash.hash = None;
+        assert_eq!(verifier_point_from_hash(&pw_hash), Err(Error::HashEmpty));
+        assert_eq!(verifier_bytes_from_hash(&pw_hash), Err(Error::HashEmpty));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_cpace_generator_known_answer() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let generator = cpace_generator::<sha2::Sha512, _>(ssid, [2u8; 32], b"channel_identifier");
+
+        // pinned from this implementation to catch regressions in the H1 input encoding
+        let expected = [
+            152, 203, 135, 135, 6, 208, 126, 131, 144, 63, 120, 168, 114, 56, 226, 82, 153, 58,
+            105, 34, 45, 85, 247, 237, 93, 100, 183, 61, 109, 22, 231, 48,
+        ];
+        assert_eq!(generator.compress().to_bytes(), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_hash_scheme_changes_oracles() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let prs = [2u8; 32];
+
+        let default = cpace_generator_with_scheme::<sha2::Sha512, LeU32HashScheme, _>(
+            ssid,
+            &prs,
+            b"channel_identifier",
+        );
+        assert_eq!(
+            default,
+            cpace_generator::<sha2::Sha512, _>(ssid, prs, b"channel_identifier")
+        );
+
+        let single_byte = cpace_generator_with_scheme::<sha2::Sha512, SingleByteHashScheme, _>(
+            ssid,
+            &prs,
+            b"channel_identifier",
+        );
+        assert_ne!(default, single_byte);
+
+        // the single byte scheme matches a hand-rolled one byte prefix
+        let expected = RistrettoPoint::from_hash(
+            sha2::Sha512::new()
+                .chain_update([1u8])
+                .chain_update(ssid)
+                .chain_update(prs)
+                .chain_update(b"channel_identifier"),
+        );
+        assert_eq!(single_byte, expected);
+
+        // the derived keys differ as well
+        let sk1 = Output::<sha2::Sha512>::clone_from_slice(&[3u8; 64]);
+        let sk_default = hash_oracle::<sha2::Sha512, LeU32HashScheme, 5>()
+            .chain_update(ssid)
+            .chain_update(sk1)
+            .finalize();
+        let sk_single_byte = hash_oracle::<sha2::Sha512, SingleByteHashScheme, 5>()
+            .chain_update(ssid)
+            .chain_update(sk1)
+            .finalize();
+        assert_eq!(sk_default, compute_session_key::<sha2::Sha512>(ssid, sk1));
+        assert_ne!(sk_default, sk_single_byte);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_compute_session_key_bound_regression() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let sk1 = Output::<sha2::Sha512>::clone_from_slice(&[3u8; 64]);
+        let ya = RISTRETTO_BASEPOINT_POINT * Scalar::from(2u64);
+        let yb = RISTRETTO_BASEPOINT_POINT * Scalar::from(3u64);
+        let sk = compute_session_key_bound::<sha2::Sha512>(ssid, sk1, &ya, &yb);
+
+        // a regression vector pinned from this implementation, not an external test vector: it
+        // catches changes to the H5 input encoding but not a mistake that was there all along
+        let expected = [
+            225, 38, 91, 107, 195, 159, 131, 146, 162, 177, 88, 20, 216, 80, 170, 179, 75, 70, 99,
+            67, 52, 14, 195, 115, 98, 80, 242, 140, 35, 132, 73, 7, 127, 253, 131, 174, 94, 248,
+            80, 24, 237, 87, 158, 90, 178, 31, 216, 198, 111, 155, 28, 246, 242, 249, 251, 143,
+            170, 44, 192, 219, 222, 167, 11, 14,
+        ];
+        assert_eq!(sk.as_slice(), expected);
+
+        // the keys are absorbed in role order and the result is not the unbound session key
+        assert_ne!(
+            sk,
+            compute_session_key_bound::<sha2::Sha512>(ssid, sk1, &yb, &ya)
+        );
+        assert_ne!(sk, compute_session_key::<sha2::Sha512>(ssid, sk1));
+    }
+
+    #[test]
+    #[cfg(all(feature = "sha2", feature = "getrandom"))]
+    fn test_generate_keypair_uses_cpace_generator() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let generator = cpace_generator::<sha2::Sha512, _>(ssid, [2u8; 32], b"channel_identifier");
+        let (priv_key, pub_key) = generate_keypair::<sha2::Sha512, _, _>(
+            &mut rand::rngs::OsRng,
+            ssid,
+            [2u8; 32],
+            b"channel_identifier",
+        )
+        .unwrap();
+
+        assert_eq!(pub_key, generator * priv_key);
+    }
+
+    /// Deterministic RNG which always yields the same byte, for comparing outputs only
+    #[cfg(feature = "sha2")]
+    struct FixedRng(u8);
+
+    #[cfg(feature = "sha2")]
+    impl rand_core::RngCore for FixedRng {
+        fn next_u32(&mut self) -> u32 {
+            u32::from_le_bytes([self.0; 4])
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            u64::from_le_bytes([self.0; 8])
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            dst.fill(self.0);
+        }
+    }
+
+    #[cfg(feature = "sha2")]
+    impl rand_core::CryptoRng for FixedRng {}
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_generate_keypair_ref_matches_owned() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let prs = [2u8; 32];
+
+        let owned =
+            generate_keypair::<sha2::Sha512, _, _>(&mut FixedRng(7), ssid, prs, b"ci").unwrap();
+        let borrowed =
+            generate_keypair_ref::<sha2::Sha512, _, _>(&mut FixedRng(7), ssid, &prs, b"ci")
+                .unwrap();
+
+        assert_eq!(owned, borrowed);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_generate_keypair_into_matches_compressed() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let prs = [2u8; 32];
+
+        let (priv_key, pub_key) =
+            generate_keypair::<sha2::Sha512, _, _>(&mut FixedRng(7), ssid, prs, b"ci").unwrap();
+        let mut out_pub = [0u8; 32];
+        let written = generate_keypair_into::<sha2::Sha512, _, _>(
+            &mut FixedRng(7),
+            ssid,
+            prs,
+            b"ci",
+            &mut out_pub,
+        )
+        .unwrap();
+
+        assert_eq!(written, priv_key);
+        assert_eq!(out_pub, pub_key.compress().to_bytes());
+
+        let mut untouched = [0xaau8; 32];
+        let res = generate_keypair_into::<sha2::Sha512, _, _>(
+            &mut FailingRng,
+            ssid,
+            prs,
+            b"ci",
+            &mut untouched,
+        );
+        assert_eq!(res, Err(Error::Rng));
+        assert_eq!(untouched, [0xaau8; 32]);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_generate_server_keypair_with_cofactor_one() {
+        let (private, public) =
+            generate_server_keypair::<sha2::Sha512, _>(&mut FixedRng(9)).unwrap();
+        let with_cofactor =
+            generate_server_keypair_with_cofactor::<sha2::Sha512, _>(&mut FixedRng(9), Scalar::ONE)
+                .unwrap();
+        assert_eq!((private, public), with_cofactor);
+
+        // the keypair the server generated before the cofactor was a parameter
+        let expected_private = Scalar::from_hash(sha2::Sha512::new().chain_update([9u8; 64]));
+        assert_eq!(private, expected_private);
+        assert_eq!(public, RISTRETTO_BASEPOINT_POINT * expected_private);
+    }
+
+    #[test]
+    fn test_basepoint_mul_matches_variable_base() {
+        let scalars = [
+            Scalar::ZERO,
+            Scalar::ONE,
+            Scalar::from(0x1701u32),
+            -Scalar::ONE,
+            Scalar::from_bytes_mod_order([0xa5; 32]),
+        ];
+        for scalar in scalars {
+            assert_eq!(basepoint_mul(&scalar), RISTRETTO_BASEPOINT_POINT * scalar);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_ssid_hasher_matches_compute_ssid() {
+        let s = [3u8; 16];
+        let t = [4u8; 16];
+
+        let mut hasher = SsidHasher::<sha2::Sha512>::new();
+        hasher.absorb(s);
+        // ... later, once the other nonce arrives
+        hasher.absorb(t);
+
+        assert_eq!(hasher.finalize(), compute_ssid::<sha2::Sha512, 16>(s, t));
+    }
+
+    /// RNG which writes some bytes then fails, as an RNG might after partially filling a buffer
+    struct FailingRng;
+
+    impl TryRngCore for FailingRng {
+        type Error = core::fmt::Error;
+
+        fn try_next_u32(&mut self) -> core::result::Result<u32, Self::Error> {
+            Err(core::fmt::Error)
+        }
+
+        fn try_next_u64(&mut self) -> core::result::Result<u64, Self::Error> {
+            Err(core::fmt::Error)
+        }
+
+        fn try_fill_bytes(&mut self, dst: &mut [u8]) -> core::result::Result<(), Self::Error> {
+            let half = dst.len() / 2;
+            dst[..half].fill(0xAA);
+            Err(core::fmt::Error)
+        }
+    }
+
+    impl TryCryptoRng for FailingRng {}
+
+    /// Fails, after partially filling the buffer, until `failures` reaches zero
+    struct FlakyRng {
+        failures: usize,
+        calls: usize,
+    }
+
+    impl TryRngCore for FlakyRng {
+        type Error = core::fmt::Error;
+
+        fn try_next_u32(&mut self) -> core::result::Result<u32, Self::Error> {
+            Err(core::fmt::Error)
+        }
+
+        fn try_next_u64(&mut self) -> core::result::Result<u64, Self::Error> {
+            Err(core::fmt::Error)
+        }
+
+        fn try_fill_bytes(&mut self, dst: &mut [u8]) -> core::result::Result<(), Self::Error> {
+            self.calls += 1;
+            if self.failures > 0 {
+                self.failures -= 1;
+                dst[..1].fill(0xAA);
+                return Err(core::fmt::Error);
+            }
+            dst.fill(0x42);
+            Ok(())
+        }
+    }
+
+    impl TryCryptoRng for FlakyRng {}
+
+    #[test]
+    fn test_generate_nonce_retry_succeeds_after_transient_failures() {
+        let mut rng = FlakyRng {
+            failures: 2,
+            calls: 0,
+        };
+        let nonce: [u8; 16] = generate_nonce_retry(&mut rng, 3).unwrap();
+        assert_eq!(nonce, [0x42; 16]);
+        assert_eq!(rng.calls, 3);
+
+        let mut rng = FlakyRng {
+            failures: 3,
+            calls: 0,
+        };
+        let res: Result<[u8; 16]> = generate_nonce_retry(&mut rng, 3);
+        assert_eq!(res, Err(Error::Rng));
+        assert_eq!(rng.calls, 3);
+
+        let res: Result<[u8; 16]> = generate_nonce_retry(&mut rng, 0);
+        assert_eq!(res, Err(Error::Rng));
+        assert_eq!(rng.calls, 3);
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_server_keypair_secret_matches_unwrapped() {
+        let (private, public) =
+            server_keypair_secret::<sha2::Sha512, _>(&mut FixedRng(9), Scalar::ONE).unwrap();
+        let unwrapped = generate_server_keypair::<sha2::Sha512, _>(&mut FixedRng(9)).unwrap();
+        assert_eq!((*private.expose(), public), unwrapped);
+
+        let res = server_keypair_secret::<sha2::Sha512, _>(&mut FailingRng, Scalar::ONE);
+        assert_eq!(res.err(), Some(Error::Rng));
+
+        // the RNG writes half of the seed before failing, it is cleared by fill_secret
+        let mut seed = SecretArray::<64>::zeroed();
+        let res = scalar_from_seed::<sha2::Sha512, _>(&mut FailingRng, &mut seed);
+        assert_eq!(res, Err(Error::Rng));
+        assert_eq!(seed.expose(), &[0u8; 64]);
+    }
+
+    #[test]
+    fn test_fill_secret_zeroizes_on_rng_failure() {
+        let mut secret = SecretArray::<32>::zeroed();
+        let res = fill_secret(&mut FailingRng, &mut secret);
+
+        assert_eq!(res, Err(Error::Rng));
+        assert_eq!(secret.expose(), &[0u8; 32]);
+    }
+
+    #[test]
+    #[cfg(feature = "getrandom")]
+    fn test_generate_salt_is_valid_base64() {
+        let salt = generate_salt(&mut rand::rngs::OsRng).unwrap();
+        // 16 bytes encode to 22 unpadded Base64 characters
+        assert_eq!(salt.as_str().len(), 22);
+
+        let mut buf = [0u8; Salt::MAX_LENGTH];
+        let decoded = salt.as_salt().decode_b64(&mut buf).unwrap();
+        assert_eq!(decoded.len(), Salt::RECOMMENDED_LENGTH);
+    }
+
+    #[test]
+    fn test_generate_salt_propagates_rng_failure() {
+        assert!(matches!(generate_salt(&mut FailingRng), Err(Error::Rng)));
+    }
+
+    #[test]
+    fn test_generate_nonce_secret_propagates_rng_failure() {
+        let res = generate_nonce_secret::<_, 32>(&mut FailingRng);
+        assert!(matches!(res, Err(Error::Rng)));
+    }
+
+    /// Refuses requests over `RNG_FILL_CHUNK` bytes, and fails once `blocks` is exhausted
+    struct BlockLimitedRng {
+        blocks: usize,
+        counter: u8,
+    }
+
+    impl TryRngCore for BlockLimitedRng {
+        type Error = core::fmt::Error;
+
+        fn try_next_u32(&mut self) -> core::result::Result<u32, Self::Error> {
+            Err(core::fmt::Error)
+        }
+
+        fn try_next_u64(&mut self) -> core::result::Result<u64, Self::Error> {
+            Err(core::fmt::Error)
+        }
+
+        fn try_fill_bytes(&mut self, dst: &mut [u8]) -> core::result::Result<(), Self::Error> {
+            if dst.len() > RNG_FILL_CHUNK || self.blocks == 0 {
+                return Err(core::fmt::Error);
+            }
+            self.blocks -= 1;
+            for byte in dst {
+                // never zero, so unfilled bytes are detectable
+                self.counter = self.counter % 255 + 1;
+                *byte = self.counter;
+            }
+            Ok(())
+        }
+    }
+
+    impl TryCryptoRng for BlockLimitedRng {}
+
+    #[test]
+    fn test_generate_nonce_large_is_filled_in_blocks() {
+        let mut rng = BlockLimitedRng {
+            blocks: 5,
+            counter: 0,
+        };
+        let nonce: [u8; 1100] = generate_nonce(&mut rng).unwrap();
+        assert_eq!(rng.blocks, 0);
+        assert!(nonce.iter().all(|&byte| byte != 0));
+        // the blocks are reassembled in order
+        assert!(
+            nonce
+                .iter()
+                .enumerate()
+                .all(|(i, &byte)| usize::from(byte) == i % 255 + 1)
+        );
+
+        // a failure of any block fails the nonce
+        let mut rng = BlockLimitedRng {
+            blocks: 4,
+            counter: 0,
+        };
+        let res: Result<[u8; 1100]> = generate_nonce(&mut rng);
+        assert_eq!(res, Err(Error::Rng));
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_verify_session_key_confirmation() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let sk1 = compute_ssid::<sha2::Sha512, 16>([5u8; 16], [6u8; 16]);
+        let (ta, tb) = compute_authenticator_messages::<sha2::Sha512>(ssid, sk1);
+        let tb: [u8; 64] = tb.into();
+
+        let (server_authenticator, sk) =
+            verify_session_key_confirmation::<sha2::Sha512>(ssid, sk1, tb).unwrap();
+        assert_eq!(server_authenticator.as_slice(), ta.as_slice());
+        assert_eq!(
+            sk.expose(),
+            compute_session_key::<sha2::Sha512>(ssid, sk1).as_slice()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_verify_session_key_confirmation_rejects_tampered_authenticator() {
+        let ssid = compute_ssid::<sha2::Sha512, 16>([0u8; 16], [1u8; 16]);
+        let sk1 = compute_ssid::<sha2::Sha512, 16>([5u8; 16], [6u8; 16]);
+        let (_, tb) = compute_authenticator_messages::<sha2::Sha512>(ssid, sk1);
+        let mut tampered: [u8; 64] = tb.into();
+        tampered[0] ^= 1;
+
+        let res = verify_session_key_confirmation::<sha2::Sha512>(ssid, sk1, tampered);
+        assert!(matches!(res, Err(Error::KeyConfirmationFailed)));
+    }
+
+    #[test]
+    #[cfg(all(feature = "strong_aucpace", feature = "getrandom"))]
+    fn test_blind_then_unblind_recovers_salt_point() {
+        let salt_point = RISTRETTO_BASEPOINT_POINT * Scalar::from(1234u32);
+        let blinding = generate_blinding_scalar(&mut rand::rngs::OsRng).unwrap();
+
+        let blinded = blind_salt(salt_point, blinding.expose());
+        assert_ne!(blinded, salt_point);
+
+        let unblinded = blinded * invert_blinding(blinding.expose()).unwrap();
+        assert_eq!(unblinded, salt_point);
+    }
+
+    #[test]
+    #[cfg(feature = "strong_aucpace")]
+    fn test_invert_blinding() {
+        let blinding = Scalar::from(7u64);
+        assert_eq!(invert_blinding(&blinding).unwrap() * blinding, Scalar::ONE);
+        assert_eq!(invert_blinding(&Scalar::ZERO), Err(Error::ZeroScalar));
+    }
+
+    #[test]
+    #[cfg(feature = "strong_aucpace")]
+    fn test_generate_blinding_scalar_propagates_rng_failure() {
+        let res = generate_blinding_scalar(&mut FailingRng);
+        assert!(matches!(res, Err(Error::Rng)));
+    }
+
+    #[test]
+    fn test_curve25519_cofactor_rejected_for_ristretto_basepoint() {
+        assert!(!cofactor_matches_basepoint(
+            Scalar::from(8u8),
+            RISTRETTO_BASEPOINT_POINT
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::{serde_paramsstring, serde_saltstring};
+    use password_hash::{ParamsString, SaltString};
+    use serde::de::value::{Error as ValueError, StrDeserializer};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct SaltWrapper {
+        #[serde(with = "serde_saltstring")]
+        salt: SaltString,
+    }
+
+    fn deserialize_salt(s: &str) -> Result<SaltString, ValueError> {
+        serde_saltstring::deserialize(StrDeserializer::<ValueError>::new(s))
+    }
+
+    #[test]
+    fn test_saltstring_round_trip() {
+        let salt = SaltString::encode_b64(b"a very salty salt").unwrap();
+        let wrapper = SaltWrapper { salt };
+
+        let mut buf = [0u8; 128];
+        let bytes = postcard::to_slice(&wrapper, &mut buf).unwrap();
+        let decoded: SaltWrapper = postcard::from_bytes(bytes).unwrap();
+
+        assert_eq!(decoded.salt, wrapper.salt);
+        assert_eq!(
+            deserialize_salt(wrapper.salt.as_str()).unwrap(),
+            wrapper.salt
+        );
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct ParamsWrapper {
+        #[serde(with = "serde_paramsstring")]
+        params: ParamsString,
+    }
+
+    #[test]
+    fn test_paramsstring_round_trip() {
+        let mut populated = ParamsString::new();
+        populated.add_decimal("ln", 17).unwrap();
+        populated.add_decimal("r", 8).unwrap();
+
+        // the empty params are what the fallback message carries without `scrypt`
+        for params in [ParamsString::default(), populated] {
+            let wrapper = ParamsWrapper { params };
+
+            let mut buf = [0u8; 128];
+            let bytes = postcard::to_slice(&wrapper, &mut buf).unwrap();
+            let decoded: ParamsWrapper = postcard::from_bytes(bytes).unwrap();
+
+            assert_eq!(decoded.params, wrapper.params);
+        }
+    }
+
+    #[test]
+    fn test_paramsstring_rejects_over_limit() {
+        use crate::constants::MAX_PARAMS_LEN;
+        use serde::de::Error as _;
+        use serde::de::value::{Error as ValueError, StrDeserializer};
+
+        let deserialize =
+            |s| serde_paramsstring::deserialize(StrDeserializer::<ValueError>::new(s));
+
+        // `a=xxx..,b=xxx..` strings longer than a single 64 byte value are accepted up to the
+        // limit, one byte over it is rejected as too long rather than as malformed
+        let mut buf = [b'x'; MAX_PARAMS_LEN + 1];
+        buf[..2].copy_from_slice(b"a=");
+        buf[66..69].copy_from_slice(b",b=");
+        for len in [100, MAX_PARAMS_LEN] {
+            let params = core::str::from_utf8(&buf[..len]).unwrap();
+            assert_eq!(deserialize(params).unwrap().as_str(), params);
+        }
+        let over_limit = core::str::from_utf8(&buf).unwrap();
+        assert_eq!(
+            deserialize(over_limit),
+            Err(ValueError::custom(crate::Error::ParamsTooLong))
+        );
+    }
+
+    #[test]
+    fn test_saltstring_rejects_padded_form() {
+        // "a very salty salt" is 17 bytes, so its padded form ends in `=`
+        let salt = SaltString::encode_b64(b"a very salty salt").unwrap();
+        let padded = [salt.as_str(), "="].concat();
+
+        assert!(deserialize_salt(&padded).is_err());
+    }
+
+    #[test]
+    fn test_saltstring_rejects_non_canonical_trailing_bits() {
+        // `saltyboi` encodes to `c2FsdHlib2k`; flipping the unused low bits of the final
+        // character gives a string which decodes to the same bytes but is not canonical
+        let salt = SaltString::encode_b64(b"saltyboi").unwrap();
+        assert_eq!(salt.as_str(), "c2FsdHlib2k");
+
+        assert!(deserialize_salt("c2FsdHlib2l").is_err());
+    }
+}
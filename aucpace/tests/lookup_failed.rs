@@ -1,14 +1,32 @@
 #![cfg(test)]
 
-use aucpace_conflux::{Database, Server, ServerMessage};
+use aucpace_conflux::{
+    Client, ClientMessage, Database, Error, LookupResult, Server, ServerMessage, Ssid,
+};
 use curve25519_dalek::ristretto::RistrettoPoint;
 use password_hash::{ParamsString, SaltString};
 use rand::rngs::OsRng;
 
+const SSID: &[u8] = b"0123456789abcdef";
+
+/// The tag a client sends with its username in a session with the pre-established [`SSID`]
+#[cfg(all(feature = "scrypt", feature = "sha2", feature = "getrandom"))]
+fn ssid_tag() -> [u8; 64] {
+    let mut client = Client::new(OsRng);
+    let (_, message) = client
+        .begin_prestablished_ssid(Ssid::try_new(SSID).expect("SSID is long enough"))
+        .start_augmentation(b"jlpicard", b"password");
+    match message {
+        ClientMessage::Username { ssid_tag, .. } => ssid_tag,
+        other => panic!("Expected Username, got: {:?}", other),
+    }
+}
+
 struct NoneDb;
 
 impl Database for NoneDb {
     type PasswordVerifier = RistrettoPoint;
+    type Error = Error;
 
     fn lookup_verifier(
         &self,
@@ -29,20 +47,116 @@ impl Database for NoneDb {
     }
 }
 
+/// The error of a networked store, carrying detail which `Error` has no room for
+#[derive(Debug, PartialEq)]
+enum StoreError {
+    Unreachable { retry_after_secs: u32 },
+    Protocol(Error),
+}
+
+impl From<Error> for StoreError {
+    fn from(error: Error) -> Self {
+        Self::Protocol(error)
+    }
+}
+
+/// A store which is unreachable, every lookup fails
+struct UnavailableDb;
+
+impl Database for UnavailableDb {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = StoreError;
+
+    fn lookup_verifier(
+        &self,
+        _username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        unreachable!("the server should use try_lookup_verifier")
+    }
+
+    fn try_lookup_verifier(&self, _username: &[u8]) -> LookupResult<Self> {
+        Err(StoreError::Unreachable {
+            retry_after_secs: 30,
+        })
+    }
+
+    fn store_verifier(
+        &mut self,
+        _username: &[u8],
+        _salt: SaltString,
+        _uad: Option<&[u8]>,
+        _verifier: Self::PasswordVerifier,
+        _params: ParamsString,
+    ) {
+        unimplemented!()
+    }
+}
+
+#[test]
+#[cfg(all(feature = "scrypt", feature = "sha2", feature = "getrandom"))]
+fn test_lookup_error_is_not_a_failed_lookup() {
+    let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
+    let aug_layer =
+        server.begin_prestablished_ssid(Ssid::try_new(SSID).expect("SSID is long enough"));
+
+    // an outage must be reported rather than answered with the fake lookup_failed response,
+    // and the store's own error reaches the caller intact
+    let result = aug_layer.generate_client_info(b"jlpicard", ssid_tag(), &UnavailableDb, OsRng);
+    assert!(matches!(
+        result,
+        Err(StoreError::Unreachable {
+            retry_after_secs: 30
+        })
+    ));
+}
+
+#[test]
+#[cfg(all(feature = "scrypt", feature = "sha2", feature = "getrandom"))]
+fn test_protocol_errors_convert_into_the_store_error() {
+    let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
+    let aug_layer =
+        server.begin_prestablished_ssid(Ssid::try_new(SSID).expect("SSID is long enough"));
+
+    // the lookup is never reached when the RNG fails first, the protocol error is wrapped
+    let result =
+        aug_layer.generate_client_info(b"jlpicard", ssid_tag(), &UnavailableDb, FailingRng);
+    assert!(matches!(result, Err(StoreError::Protocol(Error::Rng))));
+}
+
+/// RNG which always fails
+struct FailingRng;
+
+impl rand_core::TryRngCore for FailingRng {
+    type Error = core::fmt::Error;
+
+    fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+        Err(core::fmt::Error)
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+        Err(core::fmt::Error)
+    }
+
+    fn try_fill_bytes(&mut self, _dst: &mut [u8]) -> Result<(), Self::Error> {
+        Err(core::fmt::Error)
+    }
+}
+
+impl rand_core::TryCryptoRng for FailingRng {}
+
 #[test]
-#[cfg(all(feature = "sha2", feature = "getrandom"))]
+#[cfg(all(feature = "scrypt", feature = "sha2", feature = "getrandom"))]
 fn test_lookup_failed_aug_returns_ok() {
     // Prepare a server and pre-established SSID (length >= MIN_SSID_LEN)
     let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
-    let aug_layer = server
-        .begin_prestablished_ssid(b"0123456789abcdef")
-        .expect("failed to begin prestablished ssid");
+    let aug_layer =
+        server.begin_prestablished_ssid(Ssid::try_new(SSID).expect("SSID is long enough"));
 
     // Database with no entries to force lookup_failed path
     let db = NoneDb;
 
     // Generate client info; this should take the lookup_failed path and still succeed
-    let result = aug_layer.generate_client_info(b"nonexistent-user", &db, OsRng);
+    let result = aug_layer.generate_client_info(b"nonexistent-user", ssid_tag(), &db, OsRng);
 
     assert!(
         result.is_ok(),
@@ -50,7 +164,7 @@ fn test_lookup_failed_aug_returns_ok() {
     );
     let (_next_step, message) = result.unwrap();
 
-    // Should receive AugmentationInfo with default PBKDF params
+    // Should receive AugmentationInfo with plausible, non-empty PBKDF params
     match message {
         ServerMessage::AugmentationInfo {
             group,
@@ -59,13 +173,132 @@ fn test_lookup_failed_aug_returns_ok() {
             pbkdf_params,
         } => {
             assert_eq!(group, "ristretto255");
-            assert_eq!(pbkdf_params, ParamsString::default());
+            assert_eq!(pbkdf_params, expected_fallback_params());
         }
         other => panic!("Expected AugmentationInfo, got: {:?}", other),
     }
 }
 
-#[cfg(all(feature = "strong_aucpace", feature = "sha2", feature = "getrandom"))]
+#[test]
+#[cfg(all(feature = "scrypt", feature = "sha2", feature = "getrandom"))]
+fn test_lookup_failed_is_stable_per_username() {
+    let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
+    let db = NoneDb;
+
+    // probe the same unknown username in two separate sessions
+    let mut probe = |username: &[u8]| {
+        let aug_layer =
+            server.begin_prestablished_ssid(Ssid::try_new(SSID).expect("SSID is long enough"));
+        let (_next_step, message) = aug_layer
+            .generate_client_info(username, ssid_tag(), &db, OsRng)
+            .expect("lookup_failed path should not error");
+        match message {
+            ServerMessage::AugmentationInfo {
+                salt, pbkdf_params, ..
+            } => (salt, pbkdf_params),
+            other => panic!("Expected AugmentationInfo, got: {:?}", other),
+        }
+    };
+
+    let (salt1, params1) = probe(b"nonexistent-user");
+    let (salt2, params2) = probe(b"nonexistent-user");
+    assert_eq!(salt1, salt2);
+    assert_eq!(params1, params2);
+
+    // a different username gets a different salt but the same plausible params
+    let (salt3, params3) = probe(b"another-nonexistent-user");
+    assert_ne!(salt1, salt3);
+    assert_eq!(params1, params3);
+}
+
+#[test]
+#[cfg(all(feature = "scrypt", feature = "sha2", feature = "getrandom"))]
+fn test_lookup_failed_salt_matches_enrolled_salts() {
+    let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
+    let aug_layer =
+        server.begin_prestablished_ssid(Ssid::try_new(SSID).expect("SSID is long enough"));
+    let (_next_step, message) = aug_layer
+        .generate_client_info(b"nonexistent-user", ssid_tag(), &NoneDb, OsRng)
+        .expect("lookup_failed path should not error");
+    let ServerMessage::AugmentationInfo { salt, .. } = message else {
+        panic!("Expected AugmentationInfo, got: {:?}", message);
+    };
+
+    // the salts users are enrolled with, the fallback must not stand out by its length
+    let enrolled = aucpace_conflux::generate_salt(&mut OsRng).unwrap();
+    assert_eq!(salt.len(), enrolled.len());
+}
+
+#[test]
+#[cfg(all(feature = "scrypt", feature = "sha2", feature = "getrandom"))]
+fn test_lookup_failed_uses_configured_params() {
+    let templates: [ParamsString; 2] = [
+        "m=19456,t=2,p=1".parse().unwrap(),
+        "m=65536,t=3,p=4".parse().unwrap(),
+    ];
+    let mut server = Server::new(OsRng)
+        .expect("failed to initialize server RNG")
+        .with_fallback_params(&templates)
+        .unwrap();
+    let mut probe = |username: &[u8]| {
+        let aug_layer =
+            server.begin_prestablished_ssid(Ssid::try_new(SSID).expect("SSID is long enough"));
+        let (_next_step, message) = aug_layer
+            .generate_client_info(username, ssid_tag(), &NoneDb, OsRng)
+            .expect("lookup_failed path should not error");
+        match message {
+            ServerMessage::AugmentationInfo { pbkdf_params, .. } => pbkdf_params,
+            other => panic!("Expected AugmentationInfo, got: {:?}", other),
+        }
+    };
+
+    // every unknown username is consistently sent one of the templates, and all are used
+    let mut used = [false; 2];
+    for i in 0u8..32 {
+        let params = probe(&[b'u', i]);
+        assert_eq!(params, probe(&[b'u', i]));
+        let index = templates.iter().position(|t| *t == params).unwrap();
+        used[index] = true;
+    }
+    assert_eq!(used, [true, true]);
+}
+
+#[test]
+#[cfg(all(feature = "scrypt", feature = "sha2", feature = "getrandom"))]
+fn test_with_fallback_params_rejects_invalid_templates() {
+    let params: ParamsString = "m=19456,t=2,p=1".parse().unwrap();
+    let server = || Server::new(OsRng).expect("failed to initialize server RNG");
+
+    assert!(matches!(
+        server().with_fallback_params(&[]),
+        Err(Error::InvalidFallbackParams)
+    ));
+    assert!(matches!(
+        server().with_fallback_params(&[params.clone(), ParamsString::new()]),
+        Err(Error::InvalidFallbackParams)
+    ));
+    let too_many =
+        core::array::from_fn::<_, { aucpace_conflux::constants::MAX_FALLBACK_PARAMS + 1 }, _>(
+            |_| params.clone(),
+        );
+    assert!(matches!(
+        server().with_fallback_params(&too_many),
+        Err(Error::InvalidFallbackParams)
+    ));
+}
+
+/// The parameters the server is expected to send for unknown users by default
+fn expected_fallback_params() -> ParamsString {
+    // `scrypt::Params::recommended()`, whether or not the `scrypt` feature is enabled
+    "ln=17,r=8,p=1".parse().unwrap()
+}
+
+#[cfg(all(
+    feature = "strong_aucpace",
+    feature = "scrypt",
+    feature = "sha2",
+    feature = "getrandom"
+))]
 mod strong_lookup_failed_tests {
     use super::*;
     use aucpace_conflux::StrongDatabase;
@@ -99,9 +332,8 @@ mod strong_lookup_failed_tests {
     #[test]
     fn test_lookup_failed_strong_returns_ok() {
         let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
-        let aug_layer = server
-            .begin_prestablished_ssid(b"0123456789abcdef")
-            .expect("failed to begin prestablished ssid");
+        let aug_layer =
+            server.begin_prestablished_ssid(Ssid::try_new(SSID).expect("SSID is long enough"));
 
         // Strong DB with no entries to force lookup_failed_strong path
         let db = NoneStrongDb;
@@ -109,8 +341,13 @@ mod strong_lookup_failed_tests {
         // Use a non-identity blinded point to avoid IllegalPointError
         let blinded = RISTRETTO_BASEPOINT_POINT;
 
-        let result =
-            aug_layer.generate_client_info_strong(b"nonexistent-user", blinded, &db, OsRng);
+        let result = aug_layer.generate_client_info_strong(
+            b"nonexistent-user",
+            ssid_tag(),
+            blinded,
+            &db,
+            OsRng,
+        );
 
         assert!(
             result.is_ok(),
@@ -127,7 +364,7 @@ mod strong_lookup_failed_tests {
                 pbkdf_params,
             } => {
                 assert_eq!(group, "ristretto255");
-                assert_eq!(pbkdf_params, ParamsString::default());
+                assert_eq!(pbkdf_params, expected_fallback_params());
                 assert!(
                     !blinded_salt.is_identity(),
                     "fallback blinded_salt must not be identity"
@@ -137,3 +374,84 @@ mod strong_lookup_failed_tests {
         }
     }
 }
+
+/// A store holding a single user
+struct SingleUserDb;
+
+impl Database for SingleUserDb {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        (username == b"jlpicard").then(|| {
+            (
+                curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT,
+                SaltString::from_b64("c2FsdHNhbHRzYWx0").unwrap(),
+                ParamsString::default(),
+            )
+        })
+    }
+
+    fn store_verifier(
+        &mut self,
+        _username: &[u8],
+        _salt: SaltString,
+        _uad: Option<&[u8]>,
+        _verifier: Self::PasswordVerifier,
+        _params: ParamsString,
+    ) {
+        unimplemented!()
+    }
+}
+
+#[test]
+#[cfg(all(feature = "scrypt", feature = "sha2", feature = "getrandom"))]
+fn test_generate_client_info_metered_reports_fallback() {
+    use curve25519_dalek::scalar::Scalar;
+    use secret_utils::wrappers::SecretScalar;
+
+    // a fixed key so that X is the same in every session
+    let x = SecretScalar::new(Scalar::from(0x1701u32));
+    let mut server = Server::with_secret(OsRng, x).expect("failed to initialize server");
+
+    for (username, fallback) in [(&b"jlpicard"[..], false), (&b"nonexistent-user"[..], true)] {
+        let aug_layer =
+            server.begin_prestablished_ssid(Ssid::try_new(SSID).expect("SSID is long enough"));
+        let (_next_step, metered, used_fallback) = aug_layer
+            .generate_client_info_metered(username, ssid_tag(), &SingleUserDb, OsRng)
+            .expect("lookup should not error");
+        assert_eq!(used_fallback, fallback);
+
+        // the flag is not part of the message, which is the same as the unmetered one
+        let aug_layer =
+            server.begin_prestablished_ssid(Ssid::try_new(SSID).expect("SSID is long enough"));
+        let (_next_step, message) = aug_layer
+            .generate_client_info(username, ssid_tag(), &SingleUserDb, OsRng)
+            .expect("lookup should not error");
+        match (metered, message) {
+            (
+                ServerMessage::AugmentationInfo {
+                    group: g1,
+                    x_pub: x1,
+                    salt: s1,
+                    pbkdf_params: p1,
+                },
+                ServerMessage::AugmentationInfo {
+                    group: g2,
+                    x_pub: x2,
+                    salt: s2,
+                    pbkdf_params: p2,
+                },
+            ) => {
+                assert_eq!(g1, g2);
+                assert_eq!(x1.compressed().to_bytes(), x2.compressed().to_bytes());
+                assert_eq!(s1.as_str().as_bytes(), s2.as_str().as_bytes());
+                assert_eq!(p1.as_str().as_bytes(), p2.as_str().as_bytes());
+            }
+            other => panic!("Expected AugmentationInfo, got: {:?}", other),
+        }
+    }
+}
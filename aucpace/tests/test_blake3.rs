@@ -0,0 +1,160 @@
+#![cfg(all(
+    feature = "blake3",
+    feature = "alloc",
+    feature = "scrypt",
+    feature = "getrandom"
+))]
+use aucpace_conflux::{
+    AuCPaceClient, AuCPaceServer, Blake3Digest, ClientMessage, Database, Result, ServerMessage,
+    generate_keypair, keypair_from_scalar, scalar_from_wide_hash,
+};
+use curve25519_dalek::{RistrettoPoint, Scalar, digest::Digest};
+use password_hash::{ParamsString, SaltString};
+use rand::rngs::OsRng;
+use scrypt::{Params, Scrypt};
+
+const USERNAME: &[u8] = b"jlpicard_1701";
+const PASSWORD: &[u8] = b"g04tEd_c4pT41N";
+const CI: &[u8] = b"test_channel_identifier";
+const K1: usize = 16;
+
+/// Password Verifier database which can store the info for one user
+#[derive(Debug, Default)]
+struct SingleUserDatabase {
+    user: Option<Vec<u8>>,
+    data: Option<(RistrettoPoint, SaltString, ParamsString)>,
+}
+
+impl Database for SingleUserDatabase {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = aucpace_conflux::Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        match &self.user {
+            Some(stored_username) if stored_username == username => self.data.clone(),
+            _ => None,
+        }
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        _uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.user = Some(username.to_vec());
+        self.data = Some((verifier, salt, params));
+    }
+}
+
+#[test]
+fn test_blake3_digest_is_the_xof_prefix() {
+    let output = Blake3Digest::new().chain_update(b"ncc-1701").finalize();
+
+    let mut expected = [0u8; 64];
+    blake3::Hasher::new()
+        .update(b"ncc-1701")
+        .finalize_xof()
+        .fill(&mut expected);
+    assert_eq!(output.as_slice(), expected.as_slice());
+
+    // the first 32 bytes are the regular BLAKE3 hash
+    assert_eq!(&output[..32], blake3::hash(b"ncc-1701").as_bytes());
+
+    // a wide output reduces to a scalar like any other 64 byte digest
+    let scalar = scalar_from_wide_hash(&expected);
+    assert_eq!(scalar, Scalar::from_bytes_mod_order_wide(&expected));
+}
+
+#[test]
+fn test_blake3_keypair() -> Result<()> {
+    let ssid = Blake3Digest::new().chain_update(b"ssid").finalize();
+    let (priv_key, pub_key) =
+        generate_keypair::<Blake3Digest, _, _>(&mut OsRng, ssid, [1; 32], CI)?;
+    let (_, expected) = keypair_from_scalar::<Blake3Digest, _>(priv_key, ssid, &[1; 32], CI);
+    assert_eq!(pub_key, expected);
+    Ok(())
+}
+
+/// run the whole protocol with BLAKE3 as the digest on both sides
+#[test]
+fn test_blake3_key_agreement() -> Result<()> {
+    let mut base_client: AuCPaceClient<Blake3Digest, Scrypt, OsRng, K1> = AuCPaceClient::new(OsRng);
+    let mut base_server: AuCPaceServer<Blake3Digest, OsRng, K1> = AuCPaceServer::new(OsRng)?;
+    assert_eq!(base_server.digest_name(), "BLAKE3");
+    let mut database = SingleUserDatabase::default();
+
+    // weak parameters to keep the test fast, do not use them
+    let params = Params::new(1, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    if let ClientMessage::Registration {
+        username,
+        salt,
+        params,
+        verifier,
+    } = base_client.register_alloc(USERNAME, PASSWORD, params, Scrypt)?
+    {
+        database.store_verifier(username, salt, None, verifier, params);
+    }
+
+    let (server, server_message) = base_server.begin()?;
+    let (client, client_message) = base_client.begin()?;
+    let ClientMessage::Nonce(client_nonce) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let server = server.agree_ssid(client_nonce);
+    let ServerMessage::Nonce(server_nonce) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let client = client.agree_ssid(server_nonce);
+
+    let (client, client_message) = client.start_augmentation(USERNAME, PASSWORD);
+    let ClientMessage::Username { username, ssid_tag } = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let (server, server_message) =
+        server.generate_client_info(username, ssid_tag, &database, OsRng)?;
+    let ServerMessage::AugmentationInfo {
+        x_pub,
+        salt,
+        pbkdf_params,
+        ..
+    } = server_message
+    else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let params = {
+        let log_n = pbkdf_params.get_str("ln").unwrap().parse().unwrap();
+        let r = pbkdf_params.get_str("r").unwrap().parse().unwrap();
+        let p = pbkdf_params.get_str("p").unwrap().parse().unwrap();
+        Params::new(log_n, r, p, Params::RECOMMENDED_LEN).unwrap()
+    };
+    let client = client.generate_cpace_alloc(x_pub.into(), &salt, params, Scrypt)?;
+
+    let (server, server_message) = server.generate_public_key(CI)?;
+    let (client, client_message) = client.generate_public_key(CI, &mut OsRng)?;
+    let ClientMessage::PublicKey(client_pubkey) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let server = server.receive_client_pubkey(client_pubkey)?;
+    let ServerMessage::PublicKey(server_pubkey) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let (client, client_message) = client.receive_server_pubkey(server_pubkey)?;
+
+    let ClientMessage::Authenticator(client_authenticator) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let (server_key, server_message) = server.receive_client_authenticator(client_authenticator)?;
+    let ServerMessage::Authenticator(server_authenticator) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let client_key = client.receive_server_authenticator(server_authenticator)?;
+
+    assert!(client_key.ct_eq(&server_key));
+    Ok(())
+}
@@ -0,0 +1,190 @@
+//! The whole handshake driven by a caller-supplied CSPRNG, without `getrandom` or `OsRng`.
+use aucpace_conflux::{
+    AuCPaceClient, AuCPaceServer, ClientMessage, Database, Result, ServerMessage,
+};
+use curve25519_dalek::RistrettoPoint;
+use password_hash::{ParamsString, SaltString};
+use rand_core::{CryptoRng, RngCore};
+use scrypt::{Params, Scrypt};
+use sha2::{Digest, Sha512};
+
+const USERNAME: &[u8] = b"jlpicard_1701";
+const PASSWORD: &[u8] = b"g04tEd_c4pT41N";
+const CI: &[u8] = b"test_channel_identifier";
+const K1: usize = 16;
+
+/// Deterministic RNG producing `SHA-512(seed || counter)` blocks
+///
+/// It is only marked as a [`CryptoRng`] so that it can drive the protocol in tests, it must not
+/// be used outside of them. A hardware RNG is plugged in the same way: implement [`RngCore`] and
+/// [`CryptoRng`], or the fallible `TryRngCore` and `TryCryptoRng` if reads can fail.
+struct TestRng {
+    seed: [u8; 32],
+    counter: u64,
+}
+
+impl TestRng {
+    fn new(seed: u8) -> Self {
+        Self {
+            seed: [seed; 32],
+            counter: 0,
+        }
+    }
+}
+
+impl RngCore for TestRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(64) {
+            let block = Sha512::new()
+                .chain_update(self.seed)
+                .chain_update(self.counter.to_le_bytes())
+                .finalize();
+            self.counter += 1;
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+}
+
+impl CryptoRng for TestRng {}
+
+/// Password Verifier database which can store the info for one user
+#[derive(Debug, Default)]
+struct SingleUserDatabase {
+    user: Option<Vec<u8>>,
+    data: Option<(RistrettoPoint, SaltString, ParamsString)>,
+}
+
+impl Database for SingleUserDatabase {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = aucpace_conflux::Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        match &self.user {
+            Some(stored_username) if stored_username == username => self.data.clone(),
+            _ => None,
+        }
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        _uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.user = Some(username.to_vec());
+        self.data = Some((verifier, salt, params));
+    }
+}
+
+#[test]
+fn test_key_agreement_with_custom_rng() -> Result<()> {
+    let mut base_server: AuCPaceServer<Sha512, TestRng, K1> = AuCPaceServer::new(TestRng::new(1))?;
+    let mut base_client: AuCPaceClient<Sha512, Scrypt, TestRng, K1> =
+        AuCPaceClient::new(TestRng::new(2));
+
+    // weak parameters to keep the test fast, do not use them
+    let params = Params::new(1, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    let mut database = SingleUserDatabase::default();
+    if let ClientMessage::Registration {
+        username,
+        salt,
+        params,
+        verifier,
+    } = base_client.register::<&[u8], 128>(USERNAME, PASSWORD, params, Scrypt)?
+    {
+        database.store_verifier(username, salt, None, verifier, params);
+    }
+
+    // ===== SSID Establishment =====
+    let (server, server_message) = base_server.begin()?;
+    let (client, client_message) = base_client.begin()?;
+    let ClientMessage::Nonce(client_nonce) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let server = server.agree_ssid(client_nonce);
+    let ServerMessage::Nonce(server_nonce) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let client = client.agree_ssid(server_nonce);
+
+    // ===== Augmentation Layer =====
+    let (client, client_message) = client.start_augmentation(USERNAME, PASSWORD);
+    let ClientMessage::Username { username, ssid_tag } = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let (server, server_message) =
+        server.generate_client_info(username, ssid_tag, &database, TestRng::new(3))?;
+    let ServerMessage::AugmentationInfo { x_pub, salt, .. } = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let client = client.generate_cpace::<&SaltString, 128>(x_pub.into(), &salt, params, Scrypt)?;
+
+    // ===== CPace substep =====
+    let (server, server_message) = server.generate_public_key(CI)?;
+    let (client, client_message) = client.generate_public_key(CI, &mut TestRng::new(4))?;
+    let ClientMessage::PublicKey(client_pubkey) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let server = server.receive_client_pubkey(client_pubkey)?;
+    let ServerMessage::PublicKey(server_pubkey) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let (client, client_message) = client.receive_server_pubkey(server_pubkey)?;
+
+    // ===== Explicit Mutual Authentication =====
+    let ClientMessage::Authenticator(authenticator) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let (server_key, server_message) = server.receive_client_authenticator(authenticator)?;
+    let ServerMessage::Authenticator(authenticator) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let client_key = client.receive_server_authenticator(authenticator)?;
+
+    assert!(client_key.ct_eq(&server_key));
+
+    Ok(())
+}
+
+#[test]
+fn test_lookup_failed_with_custom_rng() -> Result<()> {
+    let mut server: AuCPaceServer<Sha512, TestRng, K1> = AuCPaceServer::new(TestRng::new(1))?;
+    let mut client: AuCPaceClient<Sha512, Scrypt, TestRng, K1> =
+        AuCPaceClient::new(TestRng::new(2));
+    let (server, server_message) = server.begin()?;
+    let (client, client_message) = client.begin()?;
+    let ClientMessage::Nonce(client_nonce) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let server = server.agree_ssid(client_nonce);
+    let ServerMessage::Nonce(server_nonce) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let (_, client_message) = client
+        .agree_ssid(server_nonce)
+        .start_augmentation(USERNAME, PASSWORD);
+    let ClientMessage::Username { username, ssid_tag } = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+
+    // an unknown user still gets augmentation info, generated from the supplied RNG
+    let database = SingleUserDatabase::default();
+    let (_, message) =
+        server.generate_client_info(username, ssid_tag, &database, TestRng::new(3))?;
+    assert!(matches!(message, ServerMessage::AugmentationInfo { .. }));
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use aucpace_conflux::{Database, Error, Result};
+use curve25519_dalek::{RistrettoPoint, Scalar};
+use password_hash::{ParamsString, SaltString};
+
+/// Password Verifier database which can store the info for many users
+#[derive(Debug, Default)]
+struct MultiUserDatabase {
+    users: Vec<(Vec<u8>, RistrettoPoint, SaltString, ParamsString)>,
+}
+
+impl Database for MultiUserDatabase {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        self.users
+            .iter()
+            .find(|(user, ..)| user == username)
+            .map(|(_, verifier, salt, params)| (*verifier, salt.clone(), params.clone()))
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        _uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.users.retain(|(user, ..)| user != username);
+        self.users.push((username.to_vec(), verifier, salt, params));
+    }
+
+    fn migrate<F>(&mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&[u8], Self::PasswordVerifier) -> Self::PasswordVerifier,
+    {
+        for (username, verifier, ..) in &mut self.users {
+            *verifier = f(username, *verifier);
+        }
+        Ok(())
+    }
+}
+
+/// Password Verifier database which only supports single lookups and stores
+#[derive(Debug, Default)]
+struct SingleUserDatabase {
+    data: Option<(Vec<u8>, RistrettoPoint, SaltString, ParamsString)>,
+}
+
+impl Database for SingleUserDatabase {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        match &self.data {
+            Some((user, verifier, salt, params)) if user == username => {
+                Some((*verifier, salt.clone(), params.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        _uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.data = Some((username.to_vec(), verifier, salt, params));
+    }
+}
+
+const USERS: [&[u8]; 3] = [b"jlpicard", b"wriker", b"dtroi"];
+
+fn verifier_for(index: u64) -> RistrettoPoint {
+    RistrettoPoint::mul_base(&Scalar::from(index + 7))
+}
+
+#[test]
+fn test_migrate_visits_every_verifier() -> Result<()> {
+    let mut database = MultiUserDatabase::default();
+    let salt = SaltString::from_b64("c2FsdHNhbHRzYWx0").unwrap();
+    for (i, user) in (0u64..).zip(USERS) {
+        database.store_verifier(
+            user,
+            salt.clone(),
+            None,
+            verifier_for(i),
+            ParamsString::new(),
+        );
+    }
+
+    // double every verifier's scalar, recording which users were visited
+    let mut visited = Vec::new();
+    database.migrate(|username, verifier| {
+        visited.push(username.to_vec());
+        verifier + verifier
+    })?;
+
+    assert_eq!(visited, USERS.map(<[u8]>::to_vec));
+    for (i, user) in (0u64..).zip(USERS) {
+        let (verifier, stored_salt, _) = database.lookup_verifier(user).unwrap();
+        assert_eq!(
+            verifier,
+            RistrettoPoint::mul_base(&Scalar::from(2 * (i + 7)))
+        );
+        assert_eq!(stored_salt, salt);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_migrate_unsupported_by_default() {
+    let mut database = SingleUserDatabase::default();
+    let salt = SaltString::from_b64("c2FsdHNhbHRzYWx0").unwrap();
+    database.store_verifier(USERS[0], salt, None, verifier_for(0), ParamsString::new());
+
+    let res = database.migrate(|_, verifier| verifier + verifier);
+    assert_eq!(res, Err(Error::MigrationUnsupported));
+    let (verifier, ..) = database.lookup_verifier(USERS[0]).unwrap();
+    assert_eq!(verifier, verifier_for(0));
+}
@@ -0,0 +1,240 @@
+#![cfg(all(feature = "argon2", feature = "getrandom", feature = "sha2"))]
+use argon2::{Argon2, Params};
+use aucpace_conflux::{
+    Argon2Enrollment, AuCPaceClient, ClientMessage, Database, Error, Result, Server, ServerMessage,
+    enroll_argon2,
+};
+use curve25519_dalek::RistrettoPoint;
+use password_hash::{ParamsString, SaltString};
+use rand::rngs::OsRng;
+use sha2::Sha512;
+
+const USERNAME: &[u8] = b"jlpicard_1701";
+const PASSWORD: &[u8] = b"g04tEd_c4pT41N";
+const CI: &[u8] = b"test_channel_identifier";
+
+/// Password Verifier database which can store the info for one user
+#[derive(Debug, Default)]
+struct SingleUserDatabase {
+    user: Option<Vec<u8>>,
+    data: Option<(RistrettoPoint, SaltString, ParamsString)>,
+}
+
+impl Database for SingleUserDatabase {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        match &self.user {
+            Some(stored_username) if stored_username == username => self.data.clone(),
+            _ => None,
+        }
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        _uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.user = Some(username.to_vec());
+        self.data = Some((verifier, salt, params));
+    }
+}
+
+/// Weak parameters to keep the test fast, do not use them
+fn test_params() -> Params {
+    Params::new(64, 1, 1, None).unwrap()
+}
+
+/// enrol a user with Argon2 then check the stored values authenticate a client using Argon2
+#[test]
+fn test_enroll_argon2_then_lookup() -> Result<()> {
+    let mut database = SingleUserDatabase::default();
+    let (salt, verifier, params) = enroll_argon2(PASSWORD, USERNAME, test_params())?;
+    database.store_verifier(USERNAME, salt, None, verifier, params);
+
+    let (stored_verifier, stored_salt, stored_params) = database
+        .lookup_verifier(USERNAME)
+        .expect("user was just enrolled");
+    assert_eq!(stored_verifier, verifier);
+
+    // enrolling again must pick a fresh salt
+    let (other_salt, other_verifier, _) = enroll_argon2(PASSWORD, USERNAME, test_params())?;
+    assert_ne!(other_salt, stored_salt);
+    assert_ne!(other_verifier, stored_verifier);
+
+    // the stored parameters round trip back to the ones used for enrolment
+    let parsed = Params::new(
+        stored_params.get_decimal("m").unwrap(),
+        stored_params.get_decimal("t").unwrap(),
+        stored_params.get_decimal("p").unwrap(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(parsed, test_params());
+
+    // a client hashing with Argon2 derives a matching key
+    let mut base_server = Server::new(OsRng)?;
+    let mut base_client: AuCPaceClient<Sha512, Argon2<'static>, OsRng, 16> =
+        AuCPaceClient::new(OsRng);
+
+    let (server, server_message) = base_server.begin()?;
+    let (client, client_message) = base_client.begin()?;
+    let server = if let ClientMessage::Nonce(client_nonce) = client_message {
+        server.agree_ssid(client_nonce)
+    } else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let client = if let ServerMessage::Nonce(server_nonce) = server_message {
+        client.agree_ssid(server_nonce)
+    } else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+
+    let (client, client_message) = client.start_augmentation(USERNAME, PASSWORD);
+    let (server, server_message) =
+        if let ClientMessage::Username { username, ssid_tag } = client_message {
+            server.generate_client_info(username, ssid_tag, &database, OsRng)?
+        } else {
+            panic!("Received invalid client message {:?}", client_message);
+        };
+    let client = if let ServerMessage::AugmentationInfo { x_pub, salt, .. } = server_message {
+        client.generate_cpace_alloc(x_pub.into(), &salt, parsed, Argon2::default())?
+    } else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+
+    let (server, server_message) = server.generate_public_key(CI)?;
+    let (client, client_message) = client.generate_public_key(CI, &mut OsRng)?;
+
+    let server_key = if let ClientMessage::PublicKey(client_pubkey) = client_message {
+        server.implicit_auth(client_pubkey)?
+    } else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let client_key = if let ServerMessage::PublicKey(server_pubkey) = server_message {
+        client.implicit_auth(server_pubkey)?
+    } else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+
+    assert!(client_key.ct_eq(&server_key));
+
+    Ok(())
+}
+
+#[test]
+fn test_enroll_argon2_rejects_empty_password() {
+    let res = enroll_argon2(b"", USERNAME, test_params());
+    assert!(matches!(res, Err(Error::EmptyPassword)));
+
+    let res = Argon2Enrollment::new(test_params()).enroll(b"", USERNAME);
+    assert!(matches!(res, Err(Error::EmptyPassword)));
+}
+
+#[test]
+fn test_enroll_argon2_allows_empty_password_when_configured() -> Result<()> {
+    let enrollment = Argon2Enrollment::new(test_params()).allow_empty_password(true);
+    let (salt, verifier, params) = enrollment.enroll(b"", USERNAME)?;
+
+    // the empty password still gets a fresh salt and the configured parameters
+    let (other_salt, other_verifier, _) = enrollment.enroll(b"", USERNAME)?;
+    assert_ne!(other_salt, salt);
+    assert_ne!(other_verifier, verifier);
+    assert_eq!(params.get_decimal("m"), Some(64));
+
+    // non-empty passwords are unaffected by the flag
+    enrollment.enroll(PASSWORD, USERNAME)?;
+
+    Ok(())
+}
+
+/// the verifier `W` for `password` with the given salt, computed as the client computes it
+#[cfg(feature = "argon2-pepper")]
+fn verifier_for(password: &[u8], salt: &SaltString) -> RistrettoPoint {
+    use curve25519_dalek::{Scalar, constants::RISTRETTO_BASEPOINT_POINT};
+    use password_hash::PasswordHasher;
+
+    let user_pass = [USERNAME, b":", password].concat();
+    let hash = Argon2::default()
+        .hash_password_customized(&user_pass, None, None, test_params(), salt)
+        .unwrap()
+        .hash
+        .unwrap();
+    let w = Scalar::from_bytes_mod_order(hash.as_bytes().try_into().unwrap());
+    RISTRETTO_BASEPOINT_POINT * w
+}
+
+#[test]
+#[cfg(feature = "argon2-pepper")]
+fn test_enroll_argon2_with_pepper() -> Result<()> {
+    use aucpace_conflux::pepper_password;
+    use secret_utils::wrappers::SecretKey;
+
+    let pepper = SecretKey::new(b"starfleet-pepper".to_vec());
+    let enrollment = Argon2Enrollment::new(test_params()).pepper(pepper);
+    let (salt, verifier, _) = enrollment.enroll(PASSWORD, USERNAME)?;
+
+    // the verifier is that of the peppered password, the client must use it too
+    let pepper = SecretKey::new(b"starfleet-pepper".to_vec());
+    let peppered = pepper_password(&pepper, PASSWORD);
+    assert_eq!(verifier, verifier_for(peppered.expose(), &salt));
+
+    // with the same salt, no pepper or a different pepper gives a different verifier
+    assert_ne!(verifier, verifier_for(PASSWORD, &salt));
+    let other = pepper_password(&SecretKey::new(b"romulan-pepper".to_vec()), PASSWORD);
+    assert_ne!(verifier, verifier_for(other.expose(), &salt));
+
+    Ok(())
+}
+
+/// per-user parameters chosen at enrolment are stored and sent to the client unchanged
+#[test]
+fn test_enroll_argon2_with_custom_params_string() -> Result<()> {
+    let custom: ParamsString = "m=128,t=2,p=1".parse().unwrap();
+    let enrollment = Argon2Enrollment::from_params_string(&custom)?;
+    let (salt, verifier, params) = enrollment.enroll(PASSWORD, USERNAME)?;
+    assert_eq!(params, custom);
+
+    let mut database = SingleUserDatabase::default();
+    database.store_verifier(USERNAME, salt, None, verifier, params);
+
+    let mut base_client: AuCPaceClient<Sha512, Argon2<'static>, OsRng, 16> =
+        AuCPaceClient::new(OsRng);
+    let mut base_server = Server::new(OsRng)?;
+    let (server, server_message) = base_server.begin()?;
+    let (client, client_message) = base_client.begin()?;
+    let ClientMessage::Nonce(client_nonce) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let server = server.agree_ssid(client_nonce);
+    let ServerMessage::Nonce(server_nonce) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let client = client.agree_ssid(server_nonce);
+    let (_, client_message) = client.start_augmentation(USERNAME, PASSWORD);
+    let ClientMessage::Username { username, ssid_tag } = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let (_, server_message) = server.generate_client_info(username, ssid_tag, &database, OsRng)?;
+    let ServerMessage::AugmentationInfo { pbkdf_params, .. } = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    assert_eq!(pbkdf_params, custom);
+
+    // parameters which are missing or rejected by Argon2 are reported
+    let missing: ParamsString = "m=128,t=2".parse().unwrap();
+    let res = Argon2Enrollment::from_params_string(&missing);
+    assert!(matches!(res, Err(Error::PasswordHashing(_))));
+    let invalid: ParamsString = "m=1,t=2,p=1".parse().unwrap();
+    let res = Argon2Enrollment::from_params_string(&invalid);
+    assert!(matches!(res, Err(Error::PasswordHashing(_))));
+
+    Ok(())
+}
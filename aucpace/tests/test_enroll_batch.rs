@@ -0,0 +1,88 @@
+#![cfg(all(feature = "rayon", feature = "scrypt"))]
+use aucpace_conflux::{Error, enroll_batch};
+use curve25519_dalek::{RistrettoPoint, Scalar, constants::RISTRETTO_BASEPOINT_POINT};
+use password_hash::{PasswordHash, PasswordHasher, Salt, SaltString};
+use scrypt::{Params, Scrypt};
+use secret_utils::wrappers::SecretString;
+
+const USERS: [(&str, &str); 5] = [
+    ("jlpicard", "g04tEd_c4pT41N"),
+    ("wriker", "number_one"),
+    ("data", "spot"),
+    ("glaforge", "v1s0r"),
+    ("wcrusher", "sh3t_up_w3sl3y"),
+];
+
+/// weak parameters to keep the test fast, do not use them
+fn params() -> Params {
+    Params::new(1, 8, 1, Params::RECOMMENDED_LEN).unwrap()
+}
+
+/// the verifier `W` computed one user at a time, as the client computes it
+fn serial_verifier(username: &str, password: &str, salt: &SaltString) -> RistrettoPoint {
+    let user_pass = format!("{username}:{password}");
+    let hash = Scrypt
+        .hash_password_customized(user_pass.as_bytes(), None, None, params(), salt)
+        .unwrap()
+        .hash
+        .unwrap();
+    let w = Scalar::from_bytes_mod_order(hash.as_bytes().try_into().unwrap());
+    RISTRETTO_BASEPOINT_POINT * w
+}
+
+#[test]
+fn test_enroll_batch_matches_serial() {
+    let entries: Vec<_> = USERS
+        .iter()
+        .map(|(user, pass)| {
+            (
+                user.as_bytes().to_vec(),
+                SecretString::new(pass.to_string()),
+            )
+        })
+        .collect();
+
+    let results = enroll_batch(&entries, params(), &Scrypt);
+    assert_eq!(results.len(), USERS.len());
+
+    let expected_params = params().try_into().unwrap();
+    for ((user, pass), result) in USERS.iter().zip(results) {
+        let (username, salt, verifier, params) = result.expect("enrolment should succeed");
+        assert_eq!(username, user.as_bytes());
+        assert_eq!(params, expected_params);
+        assert_eq!(verifier, serial_verifier(user, pass, &salt));
+    }
+}
+
+/// A hasher which panics, e.g. on a bug in a PBKDF implementation
+struct PanickingHasher;
+
+impl PasswordHasher for PanickingHasher {
+    type Params = Params;
+
+    fn hash_password_customized<'a>(
+        &self,
+        _password: &[u8],
+        _algorithm: Option<password_hash::Ident<'a>>,
+        _version: Option<password_hash::Decimal>,
+        _params: Self::Params,
+        _salt: impl Into<Salt<'a>>,
+    ) -> password_hash::Result<PasswordHash<'a>> {
+        panic!("hasher failed");
+    }
+}
+
+#[test]
+fn test_enroll_batch_reports_panics_as_errors() {
+    let entries = vec![(
+        b"jlpicard".to_vec(),
+        SecretString::new("g04tEd_c4pT41N".into()),
+    )];
+
+    // the panic stays on the worker thread and its payload is not returned
+    let results = enroll_batch(&entries, params(), &PanickingHasher);
+    assert!(matches!(
+        results.as_slice(),
+        [Err(Error::PasswordHashing(password_hash::Error::Crypto))]
+    ));
+}
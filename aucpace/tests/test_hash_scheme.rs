@@ -0,0 +1,151 @@
+#![cfg(all(
+    feature = "alloc",
+    feature = "scrypt",
+    feature = "sha2",
+    feature = "getrandom"
+))]
+use aucpace_conflux::{
+    AuCPaceClient, AuCPaceServer, ClientMessage, Database, Error, HashScheme, LeU32HashScheme,
+    Result, ServerMessage, SingleByteHashScheme,
+};
+use curve25519_dalek::RistrettoPoint;
+use password_hash::{ParamsString, SaltString};
+use rand::rngs::OsRng;
+use scrypt::{Params, Scrypt};
+use sha2::Sha512;
+
+const USERNAME: &[u8] = b"jlpicard_1701";
+const PASSWORD: &[u8] = b"g04tEd_c4pT41N";
+const CI: &[u8] = b"test_channel_identifier";
+const K1: usize = 16;
+
+/// Password Verifier database which can store the info for one user
+#[derive(Debug, Default)]
+struct SingleUserDatabase {
+    user: Option<Vec<u8>>,
+    data: Option<(RistrettoPoint, SaltString, ParamsString)>,
+}
+
+impl Database for SingleUserDatabase {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = aucpace_conflux::Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        match &self.user {
+            Some(stored_username) if stored_username == username => self.data.clone(),
+            _ => None,
+        }
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        _uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.user = Some(username.to_vec());
+        self.data = Some((verifier, salt, params));
+    }
+}
+
+#[test]
+fn test_key_agreement_le_u32_scheme() -> Result<()> {
+    key_agreement::<LeU32HashScheme, LeU32HashScheme>()
+}
+
+#[test]
+fn test_key_agreement_single_byte_scheme() -> Result<()> {
+    key_agreement::<SingleByteHashScheme, SingleByteHashScheme>()
+}
+
+/// a client and server encoding the domain tags differently hash the nonces into different SSIDs
+#[test]
+fn test_mismatched_schemes_are_an_ssid_mismatch() {
+    let res = key_agreement::<SingleByteHashScheme, LeU32HashScheme>();
+    assert!(matches!(res, Err(Error::SsidMismatch)));
+
+    let res = key_agreement::<LeU32HashScheme, SingleByteHashScheme>();
+    assert!(matches!(res, Err(Error::SsidMismatch)));
+}
+
+/// run the whole protocol with the client using the scheme `CS` and the server using `SS`
+fn key_agreement<CS: HashScheme, SS: HashScheme>() -> Result<()> {
+    let mut base_client: AuCPaceClient<Sha512, Scrypt, OsRng, K1, CS> = AuCPaceClient::new(OsRng);
+    let mut base_server: AuCPaceServer<Sha512, OsRng, K1, SS> = AuCPaceServer::new(OsRng)?;
+    let mut database = SingleUserDatabase::default();
+
+    // weak parameters to keep the test fast, do not use them
+    let params = Params::new(1, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    if let ClientMessage::Registration {
+        username,
+        salt,
+        params,
+        verifier,
+    } = base_client.register_alloc(USERNAME, PASSWORD, params, Scrypt)?
+    {
+        database.store_verifier(username, salt, None, verifier, params);
+    }
+
+    let (server, server_message) = base_server.begin()?;
+    let (client, client_message) = base_client.begin()?;
+    let ClientMessage::Nonce(client_nonce) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let server = server.agree_ssid(client_nonce);
+    let ServerMessage::Nonce(server_nonce) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let client = client.agree_ssid(server_nonce);
+
+    let (client, client_message) = client.start_augmentation(USERNAME, PASSWORD);
+    let ClientMessage::Username { username, ssid_tag } = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let (server, server_message) =
+        server.generate_client_info(username, ssid_tag, &database, OsRng)?;
+    let ServerMessage::AugmentationInfo {
+        x_pub,
+        salt,
+        pbkdf_params,
+        ..
+    } = server_message
+    else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let params = {
+        let log_n = pbkdf_params.get_str("ln").unwrap().parse().unwrap();
+        let r = pbkdf_params.get_str("r").unwrap().parse().unwrap();
+        let p = pbkdf_params.get_str("p").unwrap().parse().unwrap();
+        Params::new(log_n, r, p, Params::RECOMMENDED_LEN).unwrap()
+    };
+    let client = client.generate_cpace_alloc(x_pub.into(), &salt, params, Scrypt)?;
+
+    let (server, server_message) = server.generate_public_key(CI)?;
+    let (client, client_message) = client.generate_public_key(CI, &mut OsRng)?;
+    let ClientMessage::PublicKey(client_pubkey) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let server = server.receive_client_pubkey(client_pubkey)?;
+    let ServerMessage::PublicKey(server_pubkey) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let (client, client_message) = client.receive_server_pubkey(server_pubkey)?;
+
+    let ClientMessage::Authenticator(client_authenticator) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let (server_key, server_message) = server.receive_client_authenticator(client_authenticator)?;
+    let ServerMessage::Authenticator(server_authenticator) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let client_key = client.receive_server_authenticator(server_authenticator)?;
+
+    assert!(client_key.as_ref().iter().any(|&b| b != 0));
+    assert!(client_key.ct_eq(&server_key));
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use aucpace_conflux::client::{AuCPaceClientPreAug, AuCPaceClientRecvServerKey};
 use aucpace_conflux::server::{AuCPaceServerAugLayer, AuCPaceServerRecvClientKey};
-use aucpace_conflux::{Client, ClientMessage, Database, Result, Server, ServerMessage};
+use aucpace_conflux::{Client, ClientMessage, Database, Result, Server, ServerMessage, Ssid};
 use curve25519_dalek::RistrettoPoint;
 use password_hash::{ParamsString, SaltString};
 use rand::rngs::OsRng;
@@ -22,6 +22,7 @@ struct SingleUserDatabase {
 
 impl Database for SingleUserDatabase {
     type PasswordVerifier = RistrettoPoint;
+    type Error = aucpace_conflux::Error;
 
     fn lookup_verifier(
         &self,
@@ -165,8 +166,9 @@ fn test_key_agreement_prestablished_ssid() -> Result<()> {
     let (mut base_client, mut base_server, database) = init()?;
 
     // ===== SSID Establishment =====
-    let server = base_server.begin_prestablished_ssid(PRE_SSID)?;
-    let client = base_client.begin_prestablished_ssid(PRE_SSID)?;
+    let ssid = Ssid::try_new(PRE_SSID)?;
+    let server = base_server.begin_prestablished_ssid(ssid);
+    let client = base_client.begin_prestablished_ssid(ssid);
 
     // do the middle bits
     let (client, server, client_message, server_message) = test_core(client, server, &database)?;
@@ -214,8 +216,9 @@ fn test_key_agreement_prestablished_ssid_implicit_auth() -> Result<()> {
     let (mut base_client, mut base_server, database) = init()?;
 
     // ===== SSID Establishment =====
-    let server = base_server.begin_prestablished_ssid(PRE_SSID)?;
-    let client = base_client.begin_prestablished_ssid(PRE_SSID)?;
+    let ssid = Ssid::try_new(PRE_SSID)?;
+    let server = base_server.begin_prestablished_ssid(ssid);
+    let client = base_client.begin_prestablished_ssid(ssid);
 
     // do the middle bits
     let (client, server, client_message, server_message) = test_core(client, server, &database)?;
@@ -244,6 +247,120 @@ fn test_key_agreement_prestablished_ssid_implicit_auth() -> Result<()> {
     Ok(())
 }
 
+/// the server checks the SSID tag sent with the username, so a corrupted nonce is caught
+/// before any key is derived rather than at key confirmation
+#[test]
+fn test_corrupted_nonce_is_an_ssid_mismatch() -> Result<()> {
+    let (mut base_client, mut base_server, database) = init()?;
+
+    for corrupt in [false, true] {
+        let (server, server_message) = base_server.begin()?;
+        let (client, client_message) = base_client.begin()?;
+
+        let ClientMessage::Nonce(mut client_nonce) = client_message else {
+            panic!("Received invalid client message {:?}", client_message);
+        };
+        if corrupt {
+            // a bit flipped in transit
+            client_nonce[0] ^= 1;
+        }
+        let server = server.agree_ssid(client_nonce);
+        let ServerMessage::Nonce(server_nonce) = server_message else {
+            panic!("Received invalid server message {:?}", server_message);
+        };
+        let client = client.agree_ssid(server_nonce);
+
+        let (_, client_message) = client.start_augmentation(USERNAME, PASSWORD);
+        let ClientMessage::Username { username, ssid_tag } = client_message else {
+            panic!("Received invalid client message {:?}", client_message);
+        };
+        let res = server.generate_client_info(username, ssid_tag, &database, OsRng);
+        if corrupt {
+            assert!(matches!(res, Err(aucpace_conflux::Error::SsidMismatch)));
+        } else {
+            assert!(res.is_ok());
+        }
+    }
+
+    Ok(())
+}
+
+/// pre-established SSIDs are compared through the tag sent with the username, each side
+/// hashing the SSID it was given
+#[test]
+fn test_differing_prestablished_ssids_are_an_ssid_mismatch() -> Result<()> {
+    let (mut base_client, mut base_server, database) = init()?;
+
+    let client_ssid = PRE_SSID.to_vec();
+    for (server_ssid, matching) in [
+        (PRE_SSID, true),
+        (&b"a_different_ssid_for_the_server"[..], false),
+    ] {
+        let server = base_server.begin_prestablished_ssid(Ssid::try_new(server_ssid)?);
+        let client = base_client.begin_prestablished_ssid(Ssid::try_new(&client_ssid)?);
+
+        let (_, client_message) = client.start_augmentation(USERNAME, PASSWORD);
+        let ClientMessage::Username { username, ssid_tag } = client_message else {
+            panic!("Received invalid client message {:?}", client_message);
+        };
+        let res = server.generate_client_info(username, ssid_tag, &database, OsRng);
+        if matching {
+            assert!(res.is_ok());
+        } else {
+            assert!(matches!(res, Err(aucpace_conflux::Error::SsidMismatch)));
+        }
+    }
+
+    Ok(())
+}
+
+/// a client with the wrong password completes the handshake up to key confirmation, which
+/// then fails on both sides
+#[test]
+fn test_wrong_password_fails_key_confirmation() -> Result<()> {
+    let (mut base_client, mut base_server, database) = init()?;
+
+    let (server, server_message) = base_server.begin()?;
+    let (client, client_message) = base_client.begin()?;
+    let ClientMessage::Nonce(client_nonce) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let ServerMessage::Nonce(server_nonce) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let server = server.agree_ssid(client_nonce);
+    let client = client.agree_ssid(server_nonce);
+
+    let (client, server, client_message, server_message) =
+        test_core_with_password(client, server, &database, b"n0t_th3_c4pT41N")?;
+    let ClientMessage::PublicKey(client_pubkey) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let ServerMessage::PublicKey(server_pubkey) = server_message else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    let server = server.receive_client_pubkey(client_pubkey)?;
+    let (client, client_message) = client.receive_server_pubkey(server_pubkey)?;
+
+    let ClientMessage::Authenticator(client_authenticator) = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let res = server.receive_client_authenticator(client_authenticator);
+    assert!(matches!(
+        res,
+        Err(aucpace_conflux::Error::KeyConfirmationFailed)
+    ));
+
+    // the server sends nothing on failure, any authenticator it could send is rejected
+    let res = client.receive_server_authenticator([0u8; 64]);
+    assert!(matches!(
+        res,
+        Err(aucpace_conflux::Error::KeyConfirmationFailed)
+    ));
+
+    Ok(())
+}
+
 /// Perform the initialisation step for all tests
 fn init() -> Result<(Client, Server, SingleUserDatabase)> {
     // Create the client, server and database
@@ -277,17 +394,33 @@ fn test_core(
     AuCPaceServerRecvClientKey<Sha512, K1>,
     ClientMessage<'_, K1>,
     ServerMessage<'_, K1>,
+)> {
+    test_core_with_password(client, server, database, PASSWORD)
+}
+
+/// perform the middle steps with the client using `password`
+fn test_core_with_password<'a>(
+    client: AuCPaceClientPreAug<Sha512, Scrypt, K1>,
+    server: AuCPaceServerAugLayer<Sha512, K1>,
+    database: &'a SingleUserDatabase,
+    password: &[u8],
+) -> Result<(
+    AuCPaceClientRecvServerKey<Sha512, K1>,
+    AuCPaceServerRecvClientKey<Sha512, K1>,
+    ClientMessage<'a, K1>,
+    ServerMessage<'a, K1>,
 )> {
     // ===== Augmentation Layer =====
     // client initiates the augmentation phase
-    let (client, client_message) = client.start_augmentation(USERNAME, PASSWORD);
+    let (client, client_message) = client.start_augmentation(USERNAME, password);
 
     // server generates augmentation info from client's username
-    let (server, server_message) = if let ClientMessage::Username(username) = client_message {
-        server.generate_client_info(username, database, OsRng)?
-    } else {
-        panic!("Received invalid client message {:?}", client_message);
-    };
+    let (server, server_message) =
+        if let ClientMessage::Username { username, ssid_tag } = client_message {
+            server.generate_client_info(username, ssid_tag, database, OsRng)?
+        } else {
+            panic!("Received invalid client message {:?}", client_message);
+        };
 
     // client receives the info and moves into the CPace step
     let client = if let ServerMessage::AugmentationInfo {
@@ -305,7 +438,7 @@ fn test_core(
 
             Params::new(log_n, r, p, Params::RECOMMENDED_LEN).unwrap()
         };
-        client.generate_cpace::<&SaltString, 128>(x_pub, &salt, params, Scrypt)?
+        client.generate_cpace::<&SaltString, 128>(x_pub.into(), &salt, params, Scrypt)?
     } else {
         panic!("Received invalid server message {:?}", server_message);
     };
@@ -1,8 +1,8 @@
-#![cfg(feature = "partial_augmentation")]
+#![cfg(all(feature = "partial_augmentation", feature = "alloc"))]
 use aucpace_conflux::client::{AuCPaceClientPreAug, AuCPaceClientRecvServerKey};
 use aucpace_conflux::server::{AuCPaceServerAugLayer, AuCPaceServerRecvClientKey};
 use aucpace_conflux::{
-    Client, ClientMessage, Database, Error, PartialAugDatabase, Result, Server, ServerMessage,
+    Client, ClientMessage, Database, Error, PartialAugDatabase, Result, Server, ServerMessage, Ssid,
 };
 use curve25519_dalek::{RistrettoPoint, Scalar};
 use password_hash::{ParamsString, SaltString};
@@ -26,6 +26,7 @@ struct SingleUserDatabase {
 
 impl Database for SingleUserDatabase {
     type PasswordVerifier = RistrettoPoint;
+    type Error = Error;
 
     fn lookup_verifier(
         &self,
@@ -134,11 +135,11 @@ fn test_key_agreement() -> Result<()> {
     };
 
     // assert that both parties derived the same, non-zero 64-byte key
-    assert_eq!(client_key.as_slice().len(), 64);
-    assert_eq!(server_key.as_slice().len(), 64);
-    assert!(client_key.as_slice().iter().any(|&b| b != 0));
-    assert!(server_key.as_slice().iter().any(|&b| b != 0));
-    assert_eq!(client_key, server_key);
+    assert_eq!(client_key.as_ref().len(), 64);
+    assert_eq!(server_key.as_ref().len(), 64);
+    assert!(client_key.as_ref().iter().any(|&b| b != 0));
+    assert!(server_key.as_ref().iter().any(|&b| b != 0));
+    assert!(client_key.ct_eq(&server_key));
 
     Ok(())
 }
@@ -184,11 +185,11 @@ fn test_key_agreement_implicit_auth() -> Result<()> {
     };
 
     // assert that both parties derived the same, non-zero 64-byte key
-    assert_eq!(client_key.as_slice().len(), 64);
-    assert_eq!(server_key.as_slice().len(), 64);
-    assert!(client_key.as_slice().iter().any(|&b| b != 0));
-    assert!(server_key.as_slice().iter().any(|&b| b != 0));
-    assert_eq!(client_key, server_key);
+    assert_eq!(client_key.as_ref().len(), 64);
+    assert_eq!(server_key.as_ref().len(), 64);
+    assert!(client_key.as_ref().iter().any(|&b| b != 0));
+    assert!(server_key.as_ref().iter().any(|&b| b != 0));
+    assert!(client_key.ct_eq(&server_key));
 
     Ok(())
 }
@@ -199,8 +200,9 @@ fn test_key_agreement_prestablished_ssid() -> Result<()> {
     let (mut base_client, mut base_server, database) = init()?;
 
     // ===== SSID Establishment =====
-    let server = base_server.begin_prestablished_ssid(PRE_SSID)?;
-    let client = base_client.begin_prestablished_ssid(PRE_SSID)?;
+    let ssid = Ssid::try_new(PRE_SSID)?;
+    let server = base_server.begin_prestablished_ssid(ssid);
+    let client = base_client.begin_prestablished_ssid(ssid);
 
     // do the middle bits
     let (client, server, client_message, server_message) = test_core(client, server, &database)?;
@@ -233,11 +235,11 @@ fn test_key_agreement_prestablished_ssid() -> Result<()> {
     };
 
     // assert that both parties derived the same, non-zero 64-byte key
-    assert_eq!(client_key.as_slice().len(), 64);
-    assert_eq!(server_key.as_slice().len(), 64);
-    assert!(client_key.as_slice().iter().any(|&b| b != 0));
-    assert!(server_key.as_slice().iter().any(|&b| b != 0));
-    assert_eq!(client_key, server_key);
+    assert_eq!(client_key.as_ref().len(), 64);
+    assert_eq!(server_key.as_ref().len(), 64);
+    assert!(client_key.as_ref().iter().any(|&b| b != 0));
+    assert!(server_key.as_ref().iter().any(|&b| b != 0));
+    assert!(client_key.ct_eq(&server_key));
 
     Ok(())
 }
@@ -248,8 +250,9 @@ fn test_key_agreement_prestablished_ssid_implicit_auth() -> Result<()> {
     let (mut base_client, mut base_server, database) = init()?;
 
     // ===== SSID Establishment =====
-    let server = base_server.begin_prestablished_ssid(PRE_SSID)?;
-    let client = base_client.begin_prestablished_ssid(PRE_SSID)?;
+    let ssid = Ssid::try_new(PRE_SSID)?;
+    let server = base_server.begin_prestablished_ssid(ssid);
+    let client = base_client.begin_prestablished_ssid(ssid);
 
     // do the middle bits
     let (client, server, client_message, server_message) = test_core(client, server, &database)?;
@@ -269,11 +272,11 @@ fn test_key_agreement_prestablished_ssid_implicit_auth() -> Result<()> {
     };
 
     // assert that both parties derived the same, non-zero 64-byte key
-    assert_eq!(client_key.as_slice().len(), 64);
-    assert_eq!(server_key.as_slice().len(), 64);
-    assert!(client_key.as_slice().iter().any(|&b| b != 0));
-    assert!(server_key.as_slice().iter().any(|&b| b != 0));
-    assert_eq!(client_key, server_key);
+    assert_eq!(client_key.as_ref().len(), 64);
+    assert_eq!(server_key.as_ref().len(), 64);
+    assert!(client_key.as_ref().iter().any(|&b| b != 0));
+    assert!(server_key.as_ref().iter().any(|&b| b != 0));
+    assert!(client_key.ct_eq(&server_key));
 
     Ok(())
 }
@@ -296,7 +299,7 @@ fn init() -> Result<(Client, Server, SingleUserDatabase)> {
     } = registration
     {
         database.store_verifier(username, salt, None, verifier, params);
-        let (private, public) = base_server.generate_long_term_keypair();
+        let (private, public) = base_server.generate_long_term_keypair()?;
         database.store_long_term_keypair(username, private, public)?;
     }
 
@@ -311,19 +314,20 @@ fn test_core(
 ) -> Result<(
     AuCPaceClientRecvServerKey<Sha512, K1>,
     AuCPaceServerRecvClientKey<Sha512, K1>,
-    ClientMessage<K1>,
-    ServerMessage<K1>,
+    ClientMessage<'_, K1>,
+    ServerMessage<'_, K1>,
 )> {
     // ===== Augmentation Layer =====
     // client initiates the augmentation phase
     let (client, client_message) = client.start_augmentation(USERNAME, PASSWORD);
 
     // server generates augmentation info from client's username
-    let (server, server_message) = if let ClientMessage::Username(username) = client_message {
-        server.generate_client_info_partial_aug(username, database, OsRng)?
-    } else {
-        panic!("Received invalid client message {:?}", client_message);
-    };
+    let (server, server_message) =
+        if let ClientMessage::Username { username, ssid_tag } = client_message {
+            server.generate_client_info_partial_aug(username, ssid_tag, database, OsRng)?
+        } else {
+            panic!("Received invalid client message {:?}", client_message);
+        };
 
     // client receives the info and moves into the CPace step
     let client = if let ServerMessage::AugmentationInfo {
@@ -341,14 +345,14 @@ fn test_core(
 
             Params::new(log_n, r, p, Params::RECOMMENDED_LEN).unwrap()
         };
-        client.generate_cpace_alloc(x_pub, &salt, params, Scrypt)?
+        client.generate_cpace_alloc(x_pub.into(), &salt, params, Scrypt)?
     } else {
         panic!("Received invalid server message {:?}", server_message);
     };
 
     // ===== CPace substep =====
-    let (server, server_message) = server.generate_public_key(CI);
-    let (client, client_message) = client.generate_public_key(CI, &mut OsRng);
+    let (server, server_message) = server.generate_public_key(CI)?;
+    let (client, client_message) = client.generate_public_key(CI, &mut OsRng)?;
 
     Ok((client, server, client_message, server_message))
 }
@@ -1,7 +1,7 @@
-#![cfg(feature = "strong_aucpace")]
+#![cfg(all(feature = "strong_aucpace", feature = "alloc"))]
 use aucpace_conflux::client::{AuCPaceClientPreAug, AuCPaceClientRecvServerKey};
 use aucpace_conflux::server::{AuCPaceServerAugLayer, AuCPaceServerRecvClientKey};
-use aucpace_conflux::{Client, ClientMessage, Result, Server, ServerMessage};
+use aucpace_conflux::{Client, ClientMessage, Result, Server, ServerMessage, Ssid, StrongDatabase};
 use curve25519_dalek::{RistrettoPoint, Scalar};
 use password_hash::ParamsString;
 use rand::rngs::OsRng;
@@ -102,11 +102,11 @@ fn test_key_agreement() -> Result<()> {
     };
 
     // assert that both parties derived the same, non-zero 64-byte key
-    assert_eq!(client_key.as_slice().len(), 64);
-    assert_eq!(server_key.as_slice().len(), 64);
-    assert!(client_key.as_slice().iter().any(|&b| b != 0));
-    assert!(server_key.as_slice().iter().any(|&b| b != 0));
-    assert_eq!(client_key, server_key);
+    assert_eq!(client_key.as_ref().len(), 64);
+    assert_eq!(server_key.as_ref().len(), 64);
+    assert!(client_key.as_ref().iter().any(|&b| b != 0));
+    assert!(server_key.as_ref().iter().any(|&b| b != 0));
+    assert!(client_key.ct_eq(&server_key));
 
     Ok(())
 }
@@ -152,11 +152,11 @@ fn test_key_agreement_implicit_auth() -> Result<()> {
     };
 
     // assert that both parties derived the same, non-zero 64-byte key
-    assert_eq!(client_key.as_slice().len(), 64);
-    assert_eq!(server_key.as_slice().len(), 64);
-    assert!(client_key.as_slice().iter().any(|&b| b != 0));
-    assert!(server_key.as_slice().iter().any(|&b| b != 0));
-    assert_eq!(client_key, server_key);
+    assert_eq!(client_key.as_ref().len(), 64);
+    assert_eq!(server_key.as_ref().len(), 64);
+    assert!(client_key.as_ref().iter().any(|&b| b != 0));
+    assert!(server_key.as_ref().iter().any(|&b| b != 0));
+    assert!(client_key.ct_eq(&server_key));
 
     Ok(())
 }
@@ -167,8 +167,9 @@ fn test_key_agreement_prestablished_ssid() -> Result<()> {
     let (mut base_client, mut base_server, database) = init()?;
 
     // ===== SSID Establishment =====
-    let server = base_server.begin_prestablished_ssid(PRE_SSID)?;
-    let client = base_client.begin_prestablished_ssid(PRE_SSID)?;
+    let ssid = Ssid::try_new(PRE_SSID)?;
+    let server = base_server.begin_prestablished_ssid(ssid);
+    let client = base_client.begin_prestablished_ssid(ssid);
 
     // do the middle bits
     let (client, server, client_message, server_message) = test_core(client, server, &database)?;
@@ -201,11 +202,11 @@ fn test_key_agreement_prestablished_ssid() -> Result<()> {
     };
 
     // assert that both parties derived the same, non-zero 64-byte key
-    assert_eq!(client_key.as_slice().len(), 64);
-    assert_eq!(server_key.as_slice().len(), 64);
-    assert!(client_key.as_slice().iter().any(|&b| b != 0));
-    assert!(server_key.as_slice().iter().any(|&b| b != 0));
-    assert_eq!(client_key, server_key);
+    assert_eq!(client_key.as_ref().len(), 64);
+    assert_eq!(server_key.as_ref().len(), 64);
+    assert!(client_key.as_ref().iter().any(|&b| b != 0));
+    assert!(server_key.as_ref().iter().any(|&b| b != 0));
+    assert!(client_key.ct_eq(&server_key));
 
     Ok(())
 }
@@ -216,8 +217,9 @@ fn test_key_agreement_prestablished_ssid_implicit_auth() -> Result<()> {
     let (mut base_client, mut base_server, database) = init()?;
 
     // ===== SSID Establishment =====
-    let server = base_server.begin_prestablished_ssid(PRE_SSID)?;
-    let client = base_client.begin_prestablished_ssid(PRE_SSID)?;
+    let ssid = Ssid::try_new(PRE_SSID)?;
+    let server = base_server.begin_prestablished_ssid(ssid);
+    let client = base_client.begin_prestablished_ssid(ssid);
 
     // do the middle bits
     let (client, server, client_message, server_message) = test_core(client, server, &database)?;
@@ -237,11 +239,11 @@ fn test_key_agreement_prestablished_ssid_implicit_auth() -> Result<()> {
     };
 
     // assert that both parties derived the same, non-zero 64-byte key
-    assert_eq!(client_key.as_slice().len(), 64);
-    assert_eq!(server_key.as_slice().len(), 64);
-    assert!(client_key.as_slice().iter().any(|&b| b != 0));
-    assert!(server_key.as_slice().iter().any(|&b| b != 0));
-    assert_eq!(client_key, server_key);
+    assert_eq!(client_key.as_ref().len(), 64);
+    assert_eq!(server_key.as_ref().len(), 64);
+    assert!(client_key.as_ref().iter().any(|&b| b != 0));
+    assert!(server_key.as_ref().iter().any(|&b| b != 0));
+    assert!(client_key.ct_eq(&server_key));
 
     Ok(())
 }
@@ -277,8 +279,8 @@ fn test_core(
 ) -> Result<(
     AuCPaceClientRecvServerKey<Sha512, K1>,
     AuCPaceServerRecvClientKey<Sha512, K1>,
-    ClientMessage<K1>,
-    ServerMessage<K1>,
+    ClientMessage<'_, K1>,
+    ServerMessage<'_, K1>,
 )> {
     // ===== Augmentation Layer =====
     // client initiates the augmentation phase
@@ -286,12 +288,16 @@ fn test_core(
         client.start_augmentation_strong(USERNAME, PASSWORD, &mut OsRng)?;
 
     // server generates augmentation info from client's username
-    let (server, server_message) =
-        if let ClientMessage::StrongUsername { username, blinded } = client_message {
-            server.generate_client_info_strong(username, blinded, database, OsRng)?
-        } else {
-            panic!("Received invalid client message {:?}", client_message);
-        };
+    let (server, server_message) = if let ClientMessage::StrongUsername {
+        username,
+        ssid_tag,
+        blinded,
+    } = client_message
+    {
+        server.generate_client_info_strong(username, ssid_tag, blinded, database, OsRng)?
+    } else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
 
     // client receives the info and moves into the CPace step
     let client = if let ServerMessage::StrongAugmentationInfo {
@@ -309,14 +315,58 @@ fn test_core(
 
             Params::new(log_n, r, p, Params::RECOMMENDED_LEN).unwrap()
         };
-        client.generate_cpace_alloc(x_pub, blinded_salt, params, Scrypt)?
+        client.generate_cpace_alloc(x_pub.into(), blinded_salt, params, Scrypt)?
     } else {
         panic!("Received invalid server message {:?}", server_message);
     };
 
     // ===== CPace substep =====
-    let (server, server_message) = server.generate_public_key(CI);
-    let (client, client_message) = client.generate_public_key(CI, &mut OsRng);
+    let (server, server_message) = server.generate_public_key(CI)?;
+    let (client, client_message) = client.generate_public_key(CI, &mut OsRng)?;
 
     Ok((client, server, client_message, server_message))
 }
+
+/// store a strong verifier and secret exponent, then check the blinded salt the server returns
+/// for a real blinded point unblinds to the stored salt point `U^q`
+#[test]
+fn test_strong_store_then_lookup() -> Result<()> {
+    let mut database = SingleUserDatabase::default();
+    let verifier = RistrettoPoint::from_uniform_bytes(&[0x11; 64]);
+    let secret_exponent = Scalar::from(0x5eed_u64);
+    let params: ParamsString = "ln=15,r=8,p=1".parse().unwrap();
+    database.store_verifier_strong(USERNAME, None, verifier, secret_exponent, params.clone());
+
+    // the client's point U and blinding scalar r, blinded as the client sends it
+    let salt_point = RistrettoPoint::from_uniform_bytes(&[0x22; 64]);
+    let blinding = Scalar::from(7u64);
+    let blinded = salt_point * blinding;
+
+    let mut base_server = Server::new(OsRng)?;
+    let ssid = Ssid::try_new(PRE_SSID)?;
+    let server = base_server.begin_prestablished_ssid(ssid);
+    let (_, client_message) = Client::new(OsRng)
+        .begin_prestablished_ssid(ssid)
+        .start_augmentation(USERNAME, PASSWORD);
+    let ClientMessage::Username { ssid_tag, .. } = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    let (_, server_message) =
+        server.generate_client_info_strong(USERNAME, ssid_tag, blinded, &database, OsRng)?;
+
+    let ServerMessage::StrongAugmentationInfo {
+        blinded_salt,
+        pbkdf_params,
+        ..
+    } = server_message
+    else {
+        panic!("Received invalid server message {:?}", server_message);
+    };
+    assert_eq!(
+        blinded_salt * blinding.invert(),
+        salt_point * secret_exponent
+    );
+    assert_eq!(pbkdf_params, params);
+
+    Ok(())
+}
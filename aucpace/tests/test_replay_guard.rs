@@ -0,0 +1,183 @@
+#![cfg(all(
+    feature = "std",
+    feature = "scrypt",
+    feature = "sha2",
+    feature = "getrandom"
+))]
+use aucpace_conflux::server::AuCPaceServerRecvClientKey;
+use aucpace_conflux::{
+    Client, ClientMessage, Error, FixedDatabase, ReplayGuard, Server, SharedReplayGuard, Ssid,
+};
+use curve25519_dalek::{Scalar, constants::RISTRETTO_BASEPOINT_POINT};
+use rand::rngs::OsRng;
+use std::time::Duration;
+
+const SSID: &[u8] = b"0123456789abcdef";
+
+fn ssid(bytes: &[u8]) -> Ssid<'_> {
+    Ssid::try_new(bytes).expect("SSID is long enough")
+}
+
+#[test]
+fn test_replayed_ssid_is_rejected() {
+    let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
+    let mut guard = ReplayGuard::new(Duration::from_secs(60));
+
+    assert!(
+        server
+            .begin_prestablished_ssid_guarded(ssid(SSID), &mut guard)
+            .is_ok()
+    );
+    let res = server.begin_prestablished_ssid_guarded(ssid(SSID), &mut guard);
+    assert!(matches!(res, Err(Error::ReplayDetected)));
+    assert_eq!(guard.len(), 1);
+
+    // a different SSID is unaffected
+    assert!(
+        server
+            .begin_prestablished_ssid_guarded(ssid(b"fedcba9876543210"), &mut guard)
+            .is_ok()
+    );
+    assert_eq!(guard.len(), 2);
+}
+
+#[test]
+fn test_ssids_are_forgotten_after_the_window() {
+    let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
+    let mut guard = ReplayGuard::new(Duration::ZERO);
+
+    // with an empty window nothing is remembered
+    assert!(
+        server
+            .begin_prestablished_ssid_guarded(ssid(SSID), &mut guard)
+            .is_ok()
+    );
+    assert!(
+        server
+            .begin_prestablished_ssid_guarded(ssid(SSID), &mut guard)
+            .is_ok()
+    );
+    assert!(guard.is_empty());
+}
+
+#[test]
+fn test_shared_guard_detects_replays_across_threads() {
+    let guard = SharedReplayGuard::<sha2::Sha512>::new(Duration::from_secs(60));
+
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            let guard = guard.clone();
+            std::thread::spawn(move || {
+                let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
+                server
+                    .begin_prestablished_ssid_guarded(ssid(SSID), &mut guard.lock())
+                    .is_ok()
+            })
+        })
+        .collect();
+    let accepted = handles
+        .into_iter()
+        .map(|handle| handle.join().expect("thread panicked"))
+        .filter(|accepted| *accepted)
+        .count();
+
+    // exactly one of the threads may use the SSID
+    assert_eq!(accepted, 1);
+    assert_eq!(guard.lock().len(), 1);
+}
+
+/// bring a server with the SSID `ssid_bytes` to the point of receiving the client's public key
+fn recv_client_key(
+    server: &mut Server,
+    ssid_bytes: &[u8],
+) -> AuCPaceServerRecvClientKey<sha2::Sha512, 16> {
+    let database = FixedDatabase::<1>::new();
+    let server = server.begin_prestablished_ssid(ssid(ssid_bytes));
+    let (_, client_message) = Client::new(OsRng)
+        .begin_prestablished_ssid(ssid(ssid_bytes))
+        .start_augmentation(b"jlpicard", b"password");
+    let ClientMessage::Username { username, ssid_tag } = client_message else {
+        panic!("Received invalid client message {:?}", client_message);
+    };
+    // the failed lookup path is enough to reach the CPace substep
+    let (server, _) = server
+        .generate_client_info(username, ssid_tag, &database, OsRng)
+        .expect("lookup failure is handled");
+    let (server, _) = server
+        .generate_public_key(b"channel_identifier")
+        .expect("failed to generate the server's public key");
+    server
+}
+
+#[test]
+fn test_reused_client_public_key_is_rejected() {
+    let mut server = Server::new(OsRng).expect("failed to initialize server RNG");
+    let mut guard = ReplayGuard::new(Duration::from_secs(60));
+    let client_pubkey = RISTRETTO_BASEPOINT_POINT * Scalar::from(5u64);
+
+    // the check is opt-in, a reused key is accepted by default
+    assert!(
+        recv_client_key(&mut server, SSID)
+            .receive_client_pubkey(client_pubkey)
+            .is_ok()
+    );
+    assert!(
+        recv_client_key(&mut server, SSID)
+            .receive_client_pubkey(client_pubkey)
+            .is_ok()
+    );
+
+    assert!(
+        recv_client_key(&mut server, SSID)
+            .receive_client_pubkey_guarded(client_pubkey, &mut guard)
+            .is_ok()
+    );
+    let res = recv_client_key(&mut server, b"fedcba9876543210")
+        .receive_client_pubkey_guarded(client_pubkey, &mut guard);
+    assert!(matches!(res, Err(Error::EphemeralReuse)));
+
+    // a fresh key is unaffected
+    let fresh = RISTRETTO_BASEPOINT_POINT * Scalar::from(6u64);
+    assert!(
+        recv_client_key(&mut server, SSID)
+            .receive_client_pubkey_guarded(fresh, &mut guard)
+            .is_ok()
+    );
+    assert_eq!(guard.len(), 2);
+}
+
+fn ssid_hash(byte: u8) -> sha2::digest::Output<sha2::Sha512> {
+    use sha2::Digest;
+    sha2::Sha512::digest([byte; 16])
+}
+
+#[test]
+fn test_full_guard_rejects_new_ssids() {
+    let mut guard = ReplayGuard::<sha2::Sha512>::with_max_entries(Duration::from_secs(60), 2);
+    assert_eq!(guard.max_entries(), 2);
+
+    assert!(guard.check_and_record(&ssid_hash(1)).is_ok());
+    assert!(guard.check_and_record(&ssid_hash(2)).is_ok());
+    let res = guard.check_and_record(&ssid_hash(3));
+    assert!(matches!(res, Err(Error::ReplayGuardFull)));
+    assert_eq!(guard.len(), 2);
+
+    // a replay is still reported as such, and no entry was forgotten to make room
+    let res = guard.check_and_record(&ssid_hash(1));
+    assert!(matches!(res, Err(Error::ReplayDetected)));
+}
+
+#[test]
+fn test_expired_entries_make_room() {
+    let ttl = Duration::from_millis(50);
+    let mut guard = ReplayGuard::<sha2::Sha512>::with_max_entries(ttl, 2);
+
+    assert!(guard.check_and_record(&ssid_hash(1)).is_ok());
+    assert!(guard.check_and_record(&ssid_hash(2)).is_ok());
+    std::thread::sleep(ttl);
+
+    assert!(guard.check_and_record(&ssid_hash(3)).is_ok());
+    assert_eq!(guard.len(), 1);
+    assert!(guard.check_and_record(&ssid_hash(1)).is_ok());
+    assert_eq!(guard.len(), 2);
+}
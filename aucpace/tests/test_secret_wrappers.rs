@@ -0,0 +1,43 @@
+#![cfg(all(feature = "secret-wrappers", feature = "sha2", feature = "getrandom"))]
+use aucpace_conflux::{
+    Result, client_prs, compute_first_session_key_secret, compute_session_key_secret,
+    generate_keypair_secret, generate_server_keypair_secret, server_prs,
+};
+use curve25519_dalek::{RistrettoPoint, Scalar};
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha512};
+
+const CI: &[u8] = b"test_channel_identifier";
+
+/// run the key derivation of both parties with the wrapped variants, from the server's
+/// keypair through to the session key
+#[test]
+fn test_wrapped_key_derivation() -> Result<()> {
+    let ssid = Sha512::digest(b"test ssid, the hash of both nonces");
+
+    // ===== Augmentation Layer =====
+    let w = Scalar::from(0x1234_5678u64);
+    let verifier = RistrettoPoint::mul_base(&w);
+    let (x, x_pub) = generate_server_keypair_secret::<Sha512, _>(&mut OsRng)?;
+    let server_prs = server_prs(&verifier, x.expose());
+    let client_prs = client_prs(&x_pub, &w);
+    assert_eq!(server_prs, client_prs);
+
+    // ===== CPace substep =====
+    let (ya, ya_pub) = generate_keypair_secret::<Sha512, _, _>(&mut OsRng, ssid, &server_prs, CI)?;
+    let (yb, yb_pub) = generate_keypair_secret::<Sha512, _, _>(&mut OsRng, ssid, &client_prs, CI)?;
+    assert_ne!(ya_pub, yb_pub);
+
+    let server_sk1 = compute_first_session_key_secret::<Sha512>(ssid, &ya, yb_pub);
+    let client_sk1 = compute_first_session_key_secret::<Sha512>(ssid, &yb, ya_pub);
+    assert_eq!(server_sk1.len(), 64);
+    assert!(server_sk1.ct_eq(&client_sk1));
+
+    // ===== Session key =====
+    let server_key = compute_session_key_secret::<Sha512>(ssid, &server_sk1);
+    let client_key = compute_session_key_secret::<Sha512>(ssid, &client_sk1);
+    assert!(server_key.ct_eq(&client_key));
+    assert!(!server_key.ct_eq(&server_sk1));
+
+    Ok(())
+}
@@ -0,0 +1,217 @@
+#![cfg(all(
+    feature = "alloc",
+    feature = "scrypt",
+    feature = "sha2",
+    feature = "getrandom"
+))]
+use aucpace_conflux::client::{AuCPaceClientAugLayer, AuCPaceClientExpMutAuth};
+use aucpace_conflux::{
+    Client, ClientMessage, Database, Error, Result, Server, ServerMessage, SessionStore,
+};
+use curve25519_dalek::RistrettoPoint;
+use password_hash::{ParamsString, SaltString};
+use rand::rngs::OsRng;
+use scrypt::{Params, Scrypt};
+use sha2::Sha512;
+
+const CI: &[u8] = b"test_channel_identifier";
+const K1: usize = 16;
+
+/// Password Verifier database which can store the info for many users
+#[derive(Debug, Default)]
+struct MultiUserDatabase {
+    users: Vec<(Vec<u8>, RistrettoPoint, SaltString, ParamsString)>,
+}
+
+impl Database for MultiUserDatabase {
+    type PasswordVerifier = RistrettoPoint;
+    type Error = Error;
+
+    fn lookup_verifier(
+        &self,
+        username: &[u8],
+    ) -> Option<(Self::PasswordVerifier, SaltString, ParamsString)> {
+        self.users
+            .iter()
+            .find(|(user, ..)| user == username)
+            .map(|(_, verifier, salt, params)| (*verifier, salt.clone(), params.clone()))
+    }
+
+    fn store_verifier(
+        &mut self,
+        username: &[u8],
+        salt: SaltString,
+        _uad: Option<&[u8]>,
+        verifier: Self::PasswordVerifier,
+        params: ParamsString,
+    ) {
+        self.users.retain(|(user, ..)| user != username);
+        self.users.push((username.to_vec(), verifier, salt, params));
+    }
+}
+
+/// register `username` with `password` in `database`
+fn register(
+    database: &mut MultiUserDatabase,
+    username: &'static [u8],
+    password: &'static [u8],
+) -> Result<()> {
+    let mut client = Client::new(OsRng);
+    // weak parameters to keep the test fast, do not use them
+    let params = Params::new(1, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+    if let ClientMessage::Registration {
+        username,
+        salt,
+        params,
+        verifier,
+    } = client.register::<&[u8], 128>(username, password, params, Scrypt)?
+    {
+        database.store_verifier(username, salt, None, verifier, params);
+    }
+    Ok(())
+}
+
+/// run two handshakes against one server, interleaving every step
+#[test]
+fn test_interleaved_sessions() -> Result<()> {
+    let mut database = MultiUserDatabase::default();
+    register(&mut database, b"jlpicard", b"g04tEd_c4pT41N")?;
+    register(&mut database, b"wriker", b"number_one")?;
+
+    let mut server = Server::new(OsRng)?;
+    let mut sessions: SessionStore<Sha512, OsRng, K1> = SessionStore::new(8);
+    let mut picard = Client::new(OsRng);
+    let mut riker = Client::new(OsRng);
+
+    // ===== SSID Establishment =====
+    let (picard, picard_message) = picard.begin()?;
+    let (riker, riker_message) = riker.begin()?;
+
+    let ClientMessage::Nonce(picard_nonce) = picard_message else {
+        panic!("Received invalid client message {:?}", picard_message);
+    };
+    let ClientMessage::Nonce(riker_nonce) = riker_message else {
+        panic!("Received invalid client message {:?}", riker_message);
+    };
+    let (picard_ssid, picard_reply) =
+        sessions.begin_session(&mut server, picard_nonce, OsRng, CI)?;
+    let (riker_ssid, riker_reply) = sessions.begin_session(&mut server, riker_nonce, OsRng, CI)?;
+    assert_ne!(picard_ssid, riker_ssid);
+    assert_eq!(sessions.len(), 2);
+
+    let ServerMessage::Nonce(server_nonce) = picard_reply else {
+        panic!("Received invalid server message {:?}", picard_reply);
+    };
+    let picard = picard.agree_ssid(server_nonce);
+    let ServerMessage::Nonce(server_nonce) = riker_reply else {
+        panic!("Received invalid server message {:?}", riker_reply);
+    };
+    let riker = riker.agree_ssid(server_nonce);
+
+    // ===== Augmentation Layer =====
+    let (picard, picard_message) = picard.start_augmentation(b"jlpicard", b"g04tEd_c4pT41N");
+    let (riker, riker_message) = riker.start_augmentation(b"wriker", b"number_one");
+    let riker_replies = sessions.advance_session(&riker_ssid, riker_message, &database)?;
+    let picard_replies = sessions.advance_session(&picard_ssid, picard_message, &database)?;
+
+    // ===== CPace substep =====
+    let (picard, picard_message) = finish_cpace(picard, picard_replies)?;
+    let (riker, riker_message) = finish_cpace(riker, riker_replies)?;
+    let (picard_pubkey, picard_authenticator) = picard_message;
+    let (riker_pubkey, riker_authenticator) = riker_message;
+
+    assert!(
+        sessions
+            .advance_session(&picard_ssid, picard_pubkey, &database)?
+            .is_empty()
+    );
+    assert!(
+        sessions
+            .advance_session(&riker_ssid, riker_pubkey, &database)?
+            .is_empty()
+    );
+
+    // ===== Explicit Mutual Authentication =====
+    let (riker_server_key, riker_reply) =
+        sessions.finish_session(&riker_ssid, riker_authenticator)?;
+    let (picard_server_key, picard_reply) =
+        sessions.finish_session(&picard_ssid, picard_authenticator)?;
+    assert!(sessions.is_empty());
+
+    let ServerMessage::Authenticator(authenticator) = picard_reply else {
+        panic!("Received invalid server message {:?}", picard_reply);
+    };
+    let picard_key = picard.receive_server_authenticator(authenticator)?;
+    let ServerMessage::Authenticator(authenticator) = riker_reply else {
+        panic!("Received invalid server message {:?}", riker_reply);
+    };
+    let riker_key = riker.receive_server_authenticator(authenticator)?;
+
+    assert!(picard_key.ct_eq(&picard_server_key));
+    assert!(riker_key.ct_eq(&riker_server_key));
+    assert!(!picard_key.ct_eq(&riker_key));
+
+    Ok(())
+}
+
+/// process the server's augmentation info and public key, returning the client's public key and
+/// authenticator messages
+#[allow(clippy::type_complexity)]
+fn finish_cpace(
+    client: AuCPaceClientAugLayer<'_, Sha512, Scrypt, K1>,
+    replies: Vec<ServerMessage<'static, K1>>,
+) -> Result<(
+    AuCPaceClientExpMutAuth<Sha512, K1>,
+    (ClientMessage<'static, K1>, ClientMessage<'static, K1>),
+)> {
+    let [info, server_pubkey] = <[_; 2]>::try_from(replies).expect("expected two messages");
+    let ServerMessage::AugmentationInfo {
+        x_pub,
+        salt,
+        pbkdf_params,
+        ..
+    } = info
+    else {
+        panic!("Received invalid server message {:?}", info);
+    };
+    let params = {
+        let log_n = pbkdf_params.get_str("ln").unwrap().parse().unwrap();
+        let r = pbkdf_params.get_str("r").unwrap().parse().unwrap();
+        let p = pbkdf_params.get_str("p").unwrap().parse().unwrap();
+        Params::new(log_n, r, p, Params::RECOMMENDED_LEN).unwrap()
+    };
+    let client = client.generate_cpace::<&SaltString, 128>(x_pub.into(), &salt, params, Scrypt)?;
+    let (client, pubkey_message) = client.generate_public_key(CI, &mut OsRng)?;
+
+    let ServerMessage::PublicKey(server_pubkey) = server_pubkey else {
+        panic!("Received invalid server message {:?}", server_pubkey);
+    };
+    let (client, authenticator_message) = client.receive_server_pubkey(server_pubkey)?;
+    Ok((client, (pubkey_message, authenticator_message)))
+}
+
+#[test]
+fn test_session_limit_and_unknown_sessions() -> Result<()> {
+    let database = MultiUserDatabase::default();
+    let mut server = Server::new(OsRng)?;
+    let mut sessions: SessionStore<Sha512, OsRng, K1> = SessionStore::new(1);
+
+    let (ssid, _) = sessions.begin_session(&mut server, [1u8; K1], OsRng, CI)?;
+    let res = sessions.begin_session(&mut server, [2u8; K1], OsRng, CI);
+    assert!(matches!(res, Err(Error::SessionLimitReached)));
+
+    // a public key is not what the session is waiting for, and the session is dropped
+    let message = ClientMessage::PublicKey(RistrettoPoint::default());
+    let res = sessions.advance_session(&ssid, message, &database);
+    assert!(matches!(res, Err(Error::UnexpectedMessage)));
+    assert!(sessions.is_empty());
+
+    let res = sessions.finish_session(&ssid, ClientMessage::Authenticator([0u8; 64]));
+    assert!(matches!(res, Err(Error::UnknownSession)));
+
+    let (ssid, _) = sessions.begin_session(&mut server, [3u8; K1], OsRng, CI)?;
+    assert!(sessions.abort_session(&ssid));
+    assert!(!sessions.abort_session(&ssid));
+
+    Ok(())
+}
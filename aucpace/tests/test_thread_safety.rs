@@ -0,0 +1,83 @@
+//! Compile-time checks that the server, its messages and session keys can be shared across threads.
+#![cfg(all(feature = "sha2", feature = "getrandom"))]
+use aucpace_conflux::server::{
+    AuCPaceServerAugLayer, AuCPaceServerCPaceSubstep, AuCPaceServerExpMutAuth,
+    AuCPaceServerRecvClientKey, AuCPaceServerSsidEstablish,
+};
+use aucpace_conflux::{ClientMessage, Error, Server, ServerMessage};
+use rand::rngs::OsRng;
+use secret_utils::wrappers::SecretKey;
+use sha2::Sha512;
+
+const K1: usize = 16;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_server_types_are_send_sync() {
+    assert_send_sync::<Server>();
+    assert_send_sync::<AuCPaceServerSsidEstablish<Sha512, K1>>();
+    assert_send_sync::<AuCPaceServerAugLayer<Sha512, K1>>();
+    assert_send_sync::<AuCPaceServerCPaceSubstep<Sha512, OsRng, K1>>();
+    assert_send_sync::<AuCPaceServerRecvClientKey<Sha512, K1>>();
+    assert_send_sync::<AuCPaceServerExpMutAuth<Sha512, K1>>();
+}
+
+#[test]
+fn test_messages_and_keys_are_send_sync() {
+    assert_send_sync::<ServerMessage<'static, K1>>();
+    assert_send_sync::<ClientMessage<'static, K1>>();
+    assert_send_sync::<SecretKey>();
+    assert_send_sync::<Error>();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_shared_stores_are_send_sync() {
+    use aucpace_conflux::{FixedDatabase, SharedReplayGuard};
+    use std::sync::{Arc, Mutex};
+
+    assert_send_sync::<Arc<Mutex<FixedDatabase<4>>>>();
+    assert_send_sync::<SharedReplayGuard<Sha512>>();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_concurrent_lookups_against_a_shared_store() {
+    use aucpace_conflux::{Database, FixedDatabase};
+    use curve25519_dalek::Scalar;
+    use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+    use password_hash::{ParamsString, SaltString};
+    use std::sync::{Arc, Mutex};
+
+    let mut database: Arc<Mutex<FixedDatabase<4>>> = Arc::default();
+    let salt = SaltString::from_b64("c2FsdHNhbHRzYWx0").unwrap();
+    for i in 0..4u8 {
+        let verifier = RISTRETTO_BASEPOINT_POINT * Scalar::from(u64::from(i) + 1);
+        database.store_verifier(&[i], salt.clone(), None, verifier, ParamsString::new());
+    }
+
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            let database = Arc::clone(&database);
+            std::thread::spawn(move || {
+                for _ in 0..100 {
+                    for i in 0..4u8 {
+                        let (verifier, ..) = database
+                            .try_lookup_verifier(&[i])
+                            .expect("the store is not poisoned")
+                            .expect("the user is registered");
+                        assert_eq!(
+                            verifier,
+                            RISTRETTO_BASEPOINT_POINT * Scalar::from(u64::from(i) + 1)
+                        );
+                    }
+                    assert!(database.lookup_verifier(b"unknown").is_none());
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("lookup thread panicked");
+    }
+}
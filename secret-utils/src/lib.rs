@@ -17,15 +17,27 @@
 //! - Provide clear documentation and policies for secret lifecycles.
 //! - Remain no_std-friendly with an `alloc`-based default.
 //!
-//! Scope (initial scaffolding)
-//! - This initial version is documentation-only with module placeholders. There
-//!   are no public APIs yet. Follow-up phases will introduce concrete wrappers,
-//!   traits, and utilities, along with unit and integration tests.
+//! Scope
+//! - [`wrappers`] provides the zeroizing secret types, and [`errors`] the error
+//!   type their fallible operations return. The `traits` and `test_utils` modules
+//!   are still placeholders for later phases.
 //!
 //! Feature flags
 //! - `alloc` (default): Enables heap-backed containers to support secret buffers.
-//! - `std`: Convenience alias that implies `alloc`. Intended for environments
-//!   where the standard library is available.
+//! - `std`: Implies `alloc` and enables `std::io` helpers such as
+//!   `SecretBytes::from_reader`. Intended for environments where the standard
+//!   library is available.
+//! - `curve25519`: Enables `SecretScalar`, wrapping `curve25519_dalek::Scalar`.
+//! - `serde`: Enables binary-only (non-human-readable) `serde` support for the
+//!   secret wrappers.
+//! - `digest`: Implies `alloc` and enables converting digest outputs into a
+//!   `SecretKey`.
+//! - `zeroize-alloc`: Implies `alloc` and enables the [`allocator`] module, a
+//!   global allocator which zeroizes memory when it is freed.
+//! - `drop-trace`: Implies `alloc` and emits a `log` trace event, with only the
+//!   type name and length, when a heap-backed wrapper is dropped.
+//! - `zeroize-debug`: Implies `alloc`. In debug builds, dropped `SecretBytes` and
+//!   `SecretKey` buffers are filled with `wrappers::POISON` after being zeroized.
 //!
 //! Usage policy (to be enforced in subsequent phases)
 //! - All password bytes, ephemeral private scalars, long-lived verifiers, and
@@ -43,36 +55,198 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
-/// Placeholder module for secret wrappers.
+/// Re-exports of the `zeroize` traits (and their derive macros) and the `Zeroizing` wrapper
+/// used by this crate.
 ///
-/// This module will host strongly-typed wrappers (e.g., secret byte buffers,
-/// scalar wrappers) with drop-time zeroization and constrained exposure.
-/// No items are defined yet; content will be added in subsequent phases.
+/// Dependents can import these instead of naming `zeroize` themselves, so that the
+/// traits they implement are guaranteed to be the ones the wrappers here implement:
+///
+/// ```
+/// use secret_utils::{Zeroize, ZeroizeOnDrop};
+///
+/// #[derive(Zeroize, ZeroizeOnDrop)]
+/// struct EphemeralSecret {
+///     scalar: [u8; 32],
+/// }
+///
+/// let mut secret = EphemeralSecret { scalar: [0x42; 32] };
+/// secret.zeroize();
+/// assert_eq!(secret.scalar, [0u8; 32]);
+/// ```
+///
+/// Note that the derive macros expand to paths under `::zeroize`, so a crate deriving
+/// them still needs `zeroize` in its own `Cargo.toml`; any `1.x` requirement unifies
+/// with the version used here.
+pub use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+pub mod errors {
+    //! Error type for fallible operations on secret wrappers.
+
+    use core::fmt;
+
+    /// Errors that can occur when operating on secret wrappers
+    #[non_exhaustive]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub enum SecretError {
+        /// Two secrets (or a secret and a mask) were required to have equal lengths but differ
+        LengthMismatch {
+            /// The length of the secret being operated on
+            expected: usize,
+            /// The length of the other operand
+            got: usize,
+        },
+        /// A tag did not match the expected secret, in content or in length
+        TagMismatch,
+    }
+
+    impl fmt::Display for SecretError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::LengthMismatch { expected, got } => {
+                    write!(f, "length mismatch: expected {expected} bytes, got {got}")
+                }
+                Self::TagMismatch => f.write_str("tag mismatch"),
+            }
+        }
+    }
+
+    impl core::error::Error for SecretError {}
+}
+
+pub use errors::SecretError;
+
 pub mod wrappers {
     //! Zeroizing secret wrappers for byte-oriented secrets.
     //!
     //! Notes:
-    //! - These wrappers are currently behind the `alloc` feature to remain
+    //! - The heap-backed wrappers are behind the `alloc` feature to remain
     //!   compatible with `no_std` builds where `alloc` is unavailable.
-    //! - Introducing these types does not change any public API in dependent
-    //!   crates yet. They are provided here for upcoming incremental adoption.
+    //!   `SecretArray` is stack-allocated and always available.
+    //! - Dependent crates use these types in their public APIs, e.g. `aucpace`
+    //!   returns session keys as `SecretKey`.
     //!
     //! Intended usage:
     //! - `SecretBytes`: for password bytes or other sensitive buffers provided by users.
     //! - `SecretKey`: for derived session keys or key material that must be cleared on drop.
+    //! - `SecretString`: for secret text, such as passwords that have been normalized.
+    //! - `SecretArray<N>`: for fixed-size secrets, such as random values, without allocating.
+    //! - `SecretScalar` (`curve25519` feature): for secret scalars such as private keys.
+    //!
+    //! Threads:
+    //! - Every wrapper is `Send` and `Sync`: each owns its bytes and has no interior
+    //!   mutability, so a secret can be moved to or shared with another thread like the value
+    //!   it wraps. The crate's tests assert this at compile time.
+    //!
+    //! Hashing and collections:
+    //! - Neither wrapper implements `Hash` (nor `PartialEq`/`Eq`). Hashing secret bytes into a
+    //!   map would make bucket placement, iteration order and lookup timing depend on the secret.
+    //! - Nor do they implement `PartialOrd`/`Ord`: an ordering comparison returns at the first
+    //!   differing byte, and sorting or a `BTreeMap` would leak the secrets' relative order.
+    //! - To index secrets, key the map by the public identifier (e.g. the username) and store the
+    //!   secret as the value:
+    //!
+    //! ```
+    //! use std::collections::HashMap;
+    //! use secret_utils::wrappers::SecretKey;
+    //!
+    //! let mut session_keys: HashMap<Vec<u8>, SecretKey> = HashMap::new();
+    //! session_keys.insert(b"jlpicard".to_vec(), SecretKey::new(vec![0x42; 32]));
+    //! assert_eq!(session_keys[b"jlpicard".as_slice()].len(), 32);
+    //! ```
+    //!
+    //! Deriving `Hash` on a struct that contains a secret is rejected at compile time:
+    //!
+    //! ```compile_fail
+    //! use secret_utils::wrappers::SecretKey;
+    //!
+    //! #[derive(Hash)]
+    //! struct Entry {
+    //!     username: Vec<u8>,
+    //!     key: SecretKey,
+    //! }
+    //! ```
+    //!
+    //! Nesting secrets in structs:
+    //! - The wrappers implement `Zeroize`, so structs holding several secrets can derive
+    //!   `Zeroize` and `ZeroizeOnDrop`, skipping the non-secret fields.
+    //! - No guard is needed if a panic unwinds while such a struct is being built: the fields
+    //!   which were already built are dropped, and so zeroized, during unwinding. This relies on
+    //!   `panic = "unwind"`; with `panic = "abort"` nothing is dropped.
+    //!
+    //! ```
+    //! use secret_utils::wrappers::{SecretBytes, SecretKey};
+    //! use secret_utils::{Zeroize, ZeroizeOnDrop};
+    //!
+    //! #[derive(Zeroize, ZeroizeOnDrop)]
+    //! struct Credentials {
+    //!     #[zeroize(skip)]
+    //!     username: String,
+    //!     password: SecretBytes,
+    //!     session_key: SecretKey,
+    //! }
+    //!
+    //! let credentials = Credentials {
+    //!     username: "jlpicard".into(),
+    //!     password: SecretBytes::new(b"g04tEd_c4pT41N".to_vec()),
+    //!     session_key: SecretKey::new(vec![0x42; 32]),
+    //! };
+    //! assert_eq!(credentials.session_key.len(), 32);
+    //! ```
 
     #[cfg(feature = "alloc")]
-    use alloc::vec::Vec;
-    #[cfg(feature = "alloc")]
+    use alloc::{string::String, vec::Vec};
     use core::ops::Deref;
-    #[cfg(feature = "alloc")]
     use zeroize::{Zeroize, ZeroizeOnDrop};
 
+    /// Log that a wrapper of type `tag` holding `len` bytes is being dropped, never its contents
+    #[cfg(feature = "drop-trace")]
+    fn trace_drop(tag: &str, len: usize) {
+        log::trace!(target: "secret_utils::drop", "dropping {tag} len={len}");
+    }
+
+    /// The byte `zeroize-debug` fills dropped secret buffers with in debug builds
+    #[cfg(feature = "zeroize-debug")]
+    pub const POISON: u8 = 0xDE;
+
+    /// Clear a secret buffer which is being dropped
+    ///
+    /// The buffer is always zeroized first. With `zeroize-debug` in a debug build its whole
+    /// allocation is then filled with [`POISON`], which is easier to spot than zeros when
+    /// memory is read after it was freed.
+    #[cfg(any(feature = "drop-trace", feature = "zeroize-debug"))]
+    pub(crate) fn wipe_on_drop(buf: &mut Vec<u8>) {
+        buf.zeroize();
+        #[cfg(all(feature = "zeroize-debug", debug_assertions))]
+        buf.resize(buf.capacity(), POISON);
+    }
+
     /// Zeroizing wrapper for secret byte buffers (e.g., passwords).
+    ///
+    /// Zeroizing clears the whole allocation, including spare capacity left behind by a
+    /// secret that was truncated or built incrementally: the derived impl delegates to
+    /// `zeroize`'s `Vec` impl, which zeroes `spare_capacity_mut` after clearing the elements.
     #[cfg(feature = "alloc")]
-    #[derive(Zeroize, ZeroizeOnDrop)]
+    #[derive(Zeroize)]
+    #[cfg_attr(
+        not(any(feature = "drop-trace", feature = "zeroize-debug")),
+        derive(ZeroizeOnDrop)
+    )]
     pub struct SecretBytes(Vec<u8>);
 
+    // with `drop-trace` or `zeroize-debug` the zeroizing drop is written out so that it can log
+    // first and poison after
+    #[cfg(any(feature = "drop-trace", feature = "zeroize-debug"))]
+    impl Drop for SecretBytes {
+        fn drop(&mut self) {
+            #[cfg(feature = "drop-trace")]
+            trace_drop("SecretBytes", self.0.len());
+            wipe_on_drop(&mut self.0);
+        }
+    }
+
+    #[cfg(any(feature = "drop-trace", feature = "zeroize-debug"))]
+    impl ZeroizeOnDrop for SecretBytes {}
+
     #[cfg(feature = "alloc")]
     impl SecretBytes {
         /// Create a new `SecretBytes` from an owned byte vector.
@@ -80,11 +254,35 @@ pub mod wrappers {
             Self(bytes)
         }
 
+        /// Move the bytes out of `v` into a new `SecretBytes`, leaving `v` empty.
+        ///
+        /// The buffer itself is moved, not copied, and `v` is left as a fresh `Vec` without an
+        /// allocation, so the caller's variable no longer holds any of the secret.
+        pub fn take_from(v: &mut Vec<u8>) -> Self {
+            Self(core::mem::take(v))
+        }
+
         /// Borrow the inner bytes without copying.
         pub fn expose(&self) -> &[u8] {
             &self.0
         }
 
+        /// Borrow the inner bytes only for the duration of `f`, returning its result.
+        ///
+        /// The result cannot borrow from the slice, so unlike [`expose`](Self::expose) the
+        /// borrow cannot outlive the call, e.g. compute a MAC inside `f` and return the tag.
+        pub fn reveal_for<T>(&self, f: impl FnOnce(&[u8]) -> T) -> T {
+            f(&self.0)
+        }
+
+        /// Mutably borrow the inner bytes without copying, e.g. to transform them in place.
+        ///
+        /// The bytes stay in this zeroize-on-drop wrapper. Mutate them through the returned
+        /// slice only; do not copy them out, as any copy is not zeroized.
+        pub fn expose_mut(&mut self) -> &mut [u8] {
+            &mut self.0
+        }
+
         /// Consume and return the inner `Vec<u8>`.
         ///
         /// Note: this transfers ownership of the secret data to the caller.
@@ -92,6 +290,106 @@ pub mod wrappers {
         pub fn into_inner(mut self) -> Vec<u8> {
             core::mem::take(&mut self.0)
         }
+
+        /// Read a secret from `r` until EOF, directly into the wrapper's backing buffer.
+        ///
+        /// At most `max_len` bytes are accepted; if the reader yields more than that an
+        /// [`std::io::ErrorKind::InvalidData`] error is returned. Whenever the buffer has to
+        /// grow, the old allocation is zeroized before it is released, and on any error the
+        /// partially read secret is zeroized when it is dropped.
+        #[cfg(feature = "std")]
+        pub fn from_reader<R: std::io::Read>(
+            r: &mut R,
+            max_len: usize,
+        ) -> std::io::Result<SecretBytes> {
+            // Read up to one byte past the cap so an oversized input can be detected.
+            let limit = max_len.saturating_add(1);
+            let mut secret = Self(Vec::new());
+
+            loop {
+                let len = secret.0.len();
+                if len == secret.0.capacity() {
+                    secret.grow_zeroizing(len.saturating_mul(2).max(32).min(limit));
+                }
+
+                let end = secret.0.capacity().min(limit);
+                secret.0.resize(end, 0);
+                let read = r.read(&mut secret.0[len..]);
+                // Zeroize the unfilled tail rather than trusting the reader left it untouched.
+                let n = *read.as_ref().unwrap_or(&0);
+                secret.0[len + n..].zeroize();
+                secret.0.truncate(len + n);
+
+                match read {
+                    Ok(0) => return Ok(secret),
+                    Ok(_) if secret.0.len() > max_len => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "secret input exceeds max_len",
+                        ));
+                    }
+                    Ok(_) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl SecretBytes {
+        /// Append `bytes` to the secret.
+        ///
+        /// `SecretBytes` deliberately does not implement `Extend<u8>`: growing the inner `Vec`
+        /// reallocates it and leaves the old copy of the secret in freed memory. Instead, when
+        /// the capacity is exhausted this moves the secret into a new buffer of (at least)
+        /// double the capacity, and zeroizes the old buffer before releasing it.
+        pub fn append_zeroizing(&mut self, bytes: &[u8]) {
+            let needed = self.0.len().saturating_add(bytes.len());
+            if needed > self.0.capacity() {
+                self.grow_zeroizing(needed.max(self.0.capacity().saturating_mul(2)));
+            }
+            self.0.extend_from_slice(bytes);
+        }
+
+        /// Compare the secret against `other` in constant time, e.g. a received password-reset
+        /// token against the stored one. This is the supported way to check such tokens.
+        ///
+        /// The comparison is exact: a token which is a prefix of the secret, or which the
+        /// secret is a prefix of, does not match. There is no early return, the loop always
+        /// runs over the longer of the two inputs, so the timing reveals neither whether the
+        /// lengths matched nor the position of the first differing byte.
+        pub fn ct_eq_exact(&self, other: &[u8]) -> bool {
+            ct_compare(&self.0, other).0 == 0
+        }
+
+        /// Split off a fixed-size header, e.g. a 16 byte salt in front of key material.
+        ///
+        /// The first `N` bytes are returned as an array and the rest in a new `SecretBytes`,
+        /// allocated at its exact length; the original buffer is zeroized when it is dropped.
+        /// The header array itself is not zeroized, wrap it in a [`SecretArray`] if it is
+        /// secret. If the secret is shorter than `N` bytes
+        /// [`SecretError::LengthMismatch`](crate::SecretError::LengthMismatch) is returned,
+        /// with `expected: N`, and the secret is zeroized and dropped.
+        pub fn split_first_chunk<const N: usize>(
+            self,
+        ) -> Result<([u8; N], SecretBytes), crate::SecretError> {
+            let Some((header, rest)) = self.0.split_first_chunk::<N>() else {
+                return Err(crate::SecretError::LengthMismatch {
+                    expected: N,
+                    got: self.0.len(),
+                });
+            };
+            Ok((*header, SecretBytes::new(rest.to_vec())))
+        }
+
+        /// Move the secret into a new buffer with capacity `new_cap`, zeroizing the old one.
+        fn grow_zeroizing(&mut self, new_cap: usize) {
+            let mut grown = Vec::with_capacity(new_cap);
+            grown.extend_from_slice(&self.0);
+            // Replacing the buffer drops the old one; zeroize it first.
+            core::mem::replace(&mut self.0, grown).zeroize();
+        }
     }
 
     #[cfg(feature = "alloc")]
@@ -126,9 +424,27 @@ pub mod wrappers {
 
     /// Zeroizing wrapper for derived session keys or other key material.
     #[cfg(feature = "alloc")]
-    #[derive(Zeroize, ZeroizeOnDrop)]
+    #[derive(Zeroize)]
+    #[cfg_attr(
+        not(any(feature = "drop-trace", feature = "zeroize-debug")),
+        derive(ZeroizeOnDrop)
+    )]
     pub struct SecretKey(Vec<u8>);
 
+    // with `drop-trace` or `zeroize-debug` the zeroizing drop is written out so that it can log
+    // first and poison after
+    #[cfg(any(feature = "drop-trace", feature = "zeroize-debug"))]
+    impl Drop for SecretKey {
+        fn drop(&mut self) {
+            #[cfg(feature = "drop-trace")]
+            trace_drop("SecretKey", self.0.len());
+            wipe_on_drop(&mut self.0);
+        }
+    }
+
+    #[cfg(any(feature = "drop-trace", feature = "zeroize-debug"))]
+    impl ZeroizeOnDrop for SecretKey {}
+
     #[cfg(feature = "alloc")]
     impl core::fmt::Debug for SecretKey {
         fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -143,33 +459,110 @@ pub mod wrappers {
             Self(bytes)
         }
 
+        /// Move the key bytes out of `v` into a new `SecretKey`, leaving `v` empty.
+        ///
+        /// The buffer itself is moved, not copied, and `v` is left as a fresh `Vec` without an
+        /// allocation, so the caller's variable no longer holds any of the secret.
+        pub fn take_from(v: &mut Vec<u8>) -> Self {
+            Self(core::mem::take(v))
+        }
+
+        /// Create a `len` byte `SecretKey` by filling a zeroed buffer in place.
+        ///
+        /// The buffer is wrapped before `fill` runs, so key material (e.g. KDF output) can be
+        /// written straight into it without a plaintext copy, and it is zeroized even if `fill`
+        /// panics.
+        pub fn new_with(len: usize, fill: impl FnOnce(&mut [u8])) -> Self {
+            let mut key = Self(alloc::vec![0u8; len]);
+            fill(&mut key.0);
+            key
+        }
+
         /// Borrow the inner key bytes without copying.
         pub fn expose(&self) -> &[u8] {
             &self.0
         }
 
+        /// Borrow the inner key bytes only for the duration of `f`, returning its result.
+        ///
+        /// The result cannot borrow from the slice, so unlike [`expose`](Self::expose) the
+        /// borrow cannot outlive the call, e.g. compute a MAC inside `f` and return the tag.
+        pub fn reveal_for<T>(&self, f: impl FnOnce(&[u8]) -> T) -> T {
+            f(&self.0)
+        }
+
+        /// Mutably borrow the inner key bytes without copying, e.g. to transform the key through several ratchet rounds in place.
+        ///
+        /// The bytes stay in this zeroize-on-drop wrapper. Mutate them through the returned
+        /// slice only; do not copy them out, as any copy is not zeroized.
+        pub fn expose_mut(&mut self) -> &mut [u8] {
+            &mut self.0
+        }
+
         /// Perform a best-effort constant-time equality check against another key.
         ///
         /// Note: This avoids early returns and processes both inputs in full,
         /// but constant-time properties can still be impacted by compiler or platform.
         /// Prefer minimizing comparisons of secret data in application code.
         pub fn ct_eq(&self, other: &Self) -> bool {
-            let a = &self.0;
-            let b = &other.0;
-
-            // Fold length difference into accumulator to avoid short-circuiting on length.
-            let max_len = if a.len() > b.len() { a.len() } else { b.len() };
-            let mut acc: u8 = (a.len() ^ b.len()) as u8;
-
-            let mut i = 0;
-            while i < max_len {
-                // Use get().copied().unwrap_or(0) to avoid panics and avoid data-dependent branching.
-                let av = a.get(i).copied().unwrap_or(0);
-                let bv = b.get(i).copied().unwrap_or(0);
-                acc |= av ^ bv;
-                i += 1;
+            ct_compare(&self.0, &other.0).0 == 0
+        }
+
+        /// Perform a best-effort constant-time equality check against a byte slice, e.g. an
+        /// attacker-controlled input of any length.
+        ///
+        /// Like [`ct_eq`](Self::ct_eq), a length mismatch does not return early: the loop
+        /// always runs over the longer of the two inputs, reading zero past the end of the
+        /// shorter one, so the timing does not reveal whether the lengths matched.
+        pub fn ct_eq_slice(&self, other: &[u8]) -> bool {
+            ct_compare(&self.0, other).0 == 0
+        }
+
+        /// Check a received tag, e.g. a MAC or authenticator, against this key in constant time.
+        ///
+        /// Compares like [`ct_eq_slice`](Self::ct_eq_slice), so a length mismatch is not
+        /// revealed by the timing, and returns
+        /// [`SecretError::TagMismatch`](crate::SecretError::TagMismatch) if the tag differs in
+        /// any byte or in length.
+        pub fn verify_tag(&self, tag: &[u8]) -> Result<(), crate::SecretError> {
+            if self.ct_eq_slice(tag) {
+                Ok(())
+            } else {
+                Err(crate::SecretError::TagMismatch)
+            }
+        }
+
+        /// XOR `mask` into this key in place, e.g. for one-time-pad style key wrapping.
+        ///
+        /// Both keys must have the same length, otherwise
+        /// [`SecretError::LengthMismatch`](crate::SecretError::LengthMismatch) is returned and
+        /// the key is left unchanged. The result stays in this zeroize-on-drop wrapper.
+        pub fn xor_with(&mut self, mask: &SecretKey) -> Result<(), crate::SecretError> {
+            if self.0.len() != mask.0.len() {
+                return Err(crate::SecretError::LengthMismatch {
+                    expected: self.0.len(),
+                    got: mask.0.len(),
+                });
             }
-            acc == 0
+
+            for (byte, mask_byte) in self.0.iter_mut().zip(mask.0.iter()) {
+                *byte ^= mask_byte;
+            }
+            Ok(())
+        }
+
+        /// Consume the key and return it as a fixed-size array, e.g. a 32 byte AES-256 key.
+        ///
+        /// The bytes are copied into the array and the key's buffer is zeroized on drop, so no
+        /// plaintext `Vec` is left behind; the array itself is not zeroized, wrap it in a
+        /// [`SecretArray`] to keep it protected. If the key is not exactly `N` bytes long
+        /// [`SecretError::LengthMismatch`](crate::SecretError::LengthMismatch) is returned, with
+        /// `expected: N`, and the key is zeroized and dropped.
+        pub fn try_into_array<const N: usize>(self) -> Result<[u8; N], crate::SecretError> {
+            <[u8; N]>::try_from(self.0.as_slice()).map_err(|_| crate::SecretError::LengthMismatch {
+                expected: N,
+                got: self.0.len(),
+            })
         }
 
         /// Consume and return the inner `Vec<u8>`.
@@ -180,6 +573,47 @@ pub mod wrappers {
         }
     }
 
+    /// Wrap a digest output, e.g. a derived session key, without a plaintext `Vec` lingering.
+    ///
+    /// `digest::Output<D>` is an alias for a `GenericArray` of the output length, so this is
+    /// implemented for every output length rather than per digest. The bytes are copied into
+    /// the key's buffer and the output is zeroized.
+    #[cfg(feature = "digest")]
+    impl<N> From<digest::generic_array::GenericArray<u8, N>> for SecretKey
+    where
+        N: digest::generic_array::ArrayLength<u8>,
+    {
+        fn from(mut output: digest::generic_array::GenericArray<u8, N>) -> Self {
+            let key = Self(output.to_vec());
+            output.as_mut_slice().zeroize();
+            key
+        }
+    }
+
+    /// Compare `a` and `b` without short-circuiting, returning the accumulated difference
+    /// (zero when they are equal) and the number of byte positions compared.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn ct_compare(a: &[u8], b: &[u8]) -> (u8, usize) {
+        // Fold every byte of the length difference into the accumulator to avoid
+        // short-circuiting on length; truncating to the low byte would let lengths
+        // differing by a multiple of 256 compare equal.
+        let max_len = if a.len() > b.len() { a.len() } else { b.len() };
+        let mut acc: u8 = (a.len() ^ b.len())
+            .to_le_bytes()
+            .iter()
+            .fold(0, |acc, &byte| acc | byte);
+
+        let mut i = 0;
+        while i < max_len {
+            // Use get().copied().unwrap_or(0) to avoid panics and avoid data-dependent branching.
+            let av = a.get(i).copied().unwrap_or(0);
+            let bv = b.get(i).copied().unwrap_or(0);
+            acc |= av ^ bv;
+            i += 1;
+        }
+        (acc, i)
+    }
+
     #[cfg(feature = "alloc")]
     impl AsRef<[u8]> for SecretKey {
         fn as_ref(&self) -> &[u8] {
@@ -204,13 +638,328 @@ pub mod wrappers {
             Self(v)
         }
     }
+
+    /// Zeroizing wrapper for secret text, such as a password after normalization.
+    #[cfg(feature = "alloc")]
+    #[derive(Zeroize, ZeroizeOnDrop)]
+    pub struct SecretString(String);
+
+    #[cfg(feature = "alloc")]
+    impl SecretString {
+        /// Create a new `SecretString` from an owned string.
+        pub fn new(string: String) -> Self {
+            Self(string)
+        }
+
+        /// Borrow the inner string without copying.
+        pub fn expose(&self) -> &str {
+            &self.0
+        }
+
+        /// Borrow the UTF-8 bytes of the inner string without copying.
+        pub fn as_bytes(&self) -> &[u8] {
+            self.0.as_bytes()
+        }
+
+        /// Copy the secret into a zeroizing `String`, for APIs which demand an owned `String`.
+        ///
+        /// This is a deliberate, controlled exposure: the copy is no longer redacted, but it is
+        /// zeroized when the returned [`Zeroizing`](zeroize::Zeroizing) is dropped. Copies the
+        /// called API makes of it are not.
+        pub fn clone_exposed(&self) -> zeroize::Zeroizing<String> {
+            zeroize::Zeroizing::new(self.0.clone())
+        }
+
+        /// Consume and return the inner `String`.
+        ///
+        /// Note: this transfers ownership of the secret data to the caller.
+        pub fn into_inner(mut self) -> String {
+            core::mem::take(&mut self.0)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl AsRef<str> for SecretString {
+        fn as_ref(&self) -> &str {
+            &self.0
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl AsRef<[u8]> for SecretString {
+        fn as_ref(&self) -> &[u8] {
+            self.0.as_bytes()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl From<String> for SecretString {
+        fn from(s: String) -> Self {
+            Self(s)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl core::fmt::Debug for SecretString {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "SecretString([redacted], len={})", self.0.len())
+        }
+    }
+
+    /// Zeroizing wrapper for secret `curve25519-dalek` scalars (e.g. private keys or blinding values).
+    #[cfg(feature = "curve25519")]
+    #[derive(Zeroize, ZeroizeOnDrop)]
+    pub struct SecretScalar(curve25519_dalek::Scalar);
+
+    #[cfg(feature = "curve25519")]
+    impl SecretScalar {
+        /// Create a new `SecretScalar` from a scalar.
+        pub fn new(scalar: curve25519_dalek::Scalar) -> Self {
+            Self(scalar)
+        }
+
+        /// Reduce 32 bytes modulo the group order `l` into a `SecretScalar`, zeroizing `bytes`.
+        ///
+        /// This is the reduction of `Scalar::from_bytes_mod_order`, the input is taken by
+        /// mutable reference so the caller's copy of the secret is cleared.
+        pub fn from_bytes_mod_order(bytes: &mut [u8; 32]) -> Self {
+            let scalar = curve25519_dalek::Scalar::from_bytes_mod_order(*bytes);
+            bytes.zeroize();
+            Self(scalar)
+        }
+
+        /// Reduce 64 bytes, e.g. a hash output, modulo the group order `l` into a
+        /// `SecretScalar`, zeroizing `bytes`.
+        ///
+        /// This is the reduction of `Scalar::from_bytes_mod_order_wide`, the one
+        /// `aucpace`'s `scalar_from_hash` applies to password hashes.
+        pub fn from_bytes_mod_order_wide(bytes: &mut [u8; 64]) -> Self {
+            let scalar = curve25519_dalek::Scalar::from_bytes_mod_order_wide(bytes);
+            bytes.zeroize();
+            Self(scalar)
+        }
+
+        /// Borrow the inner scalar without copying.
+        pub fn expose(&self) -> &curve25519_dalek::Scalar {
+            &self.0
+        }
+
+        /// Borrow the inner scalar only for the duration of `f`, returning its result.
+        ///
+        /// Like `reveal_for` on the byte wrappers, the result cannot borrow the scalar, so the
+        /// borrow cannot outlive the call, e.g. compute a public key inside `f` and return it.
+        pub fn with_scalar<T>(&self, f: impl FnOnce(&curve25519_dalek::Scalar) -> T) -> T {
+            f(&self.0)
+        }
+    }
+
+    #[cfg(feature = "curve25519")]
+    impl From<curve25519_dalek::Scalar> for SecretScalar {
+        fn from(scalar: curve25519_dalek::Scalar) -> Self {
+            Self(scalar)
+        }
+    }
+
+    #[cfg(feature = "curve25519")]
+    impl core::fmt::Debug for SecretScalar {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "SecretScalar([redacted])")
+        }
+    }
+
+    /// Binary-only `serde` support.
+    ///
+    /// Secrets are only serialized to, and deserialized from, formats which are not
+    /// human-readable (e.g. `bincode` or `postcard`); human-readable formats such as JSON are
+    /// rejected with an error, so that secrets do not end up in logs or config files. The
+    /// serialized bytes are still secret and must be protected, e.g. by an encrypted store.
+    #[cfg(feature = "serde")]
+    mod serde_impls {
+        use serde::de::{Error as _, Visitor};
+        use serde::ser::Error as _;
+        use serde::{Deserializer, Serializer};
+
+        const HUMAN_READABLE_ERROR: &str =
+            "secrets can only be serialized in non-human-readable formats";
+
+        #[cfg(feature = "alloc")]
+        mod key {
+            use super::*;
+            use crate::wrappers::SecretKey;
+            use alloc::vec::Vec;
+            use core::fmt;
+
+            impl serde::Serialize for SecretKey {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    if serializer.is_human_readable() {
+                        return Err(S::Error::custom(HUMAN_READABLE_ERROR));
+                    }
+                    serializer.serialize_bytes(self.expose())
+                }
+            }
+
+            struct SecretKeyVisitor;
+
+            impl Visitor<'_> for SecretKeyVisitor {
+                type Value = SecretKey;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(formatter, "secret key bytes")
+                }
+
+                fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                    Ok(SecretKey::new(v.to_vec()))
+                }
+
+                fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                    Ok(SecretKey::new(v))
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for SecretKey {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    if deserializer.is_human_readable() {
+                        return Err(D::Error::custom(HUMAN_READABLE_ERROR));
+                    }
+                    deserializer.deserialize_byte_buf(SecretKeyVisitor)
+                }
+            }
+        }
+
+        #[cfg(feature = "curve25519")]
+        mod scalar {
+            use super::*;
+            use crate::wrappers::SecretScalar;
+            use core::fmt;
+            use curve25519_dalek::Scalar;
+            use zeroize::Zeroizing;
+
+            impl serde::Serialize for SecretScalar {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    if serializer.is_human_readable() {
+                        return Err(S::Error::custom(HUMAN_READABLE_ERROR));
+                    }
+                    let bytes = Zeroizing::new(self.expose().to_bytes());
+                    serializer.serialize_bytes(bytes.as_ref())
+                }
+            }
+
+            struct SecretScalarVisitor;
+
+            impl Visitor<'_> for SecretScalarVisitor {
+                type Value = SecretScalar;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(formatter, "a 32 byte canonical scalar encoding")
+                }
+
+                fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                    let bytes: Zeroizing<[u8; 32]> = Zeroizing::new(
+                        v.try_into()
+                            .map_err(|_| E::invalid_length(v.len(), &self))?,
+                    );
+                    Option::<Scalar>::from(Scalar::from_canonical_bytes(*bytes))
+                        .map(SecretScalar::new)
+                        .ok_or_else(|| E::custom("scalar encoding is not canonical"))
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for SecretScalar {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    if deserializer.is_human_readable() {
+                        return Err(D::Error::custom(HUMAN_READABLE_ERROR));
+                    }
+                    deserializer.deserialize_bytes(SecretScalarVisitor)
+                }
+            }
+        }
+    }
+
+    /// Zeroizing wrapper for fixed-size secrets held on the stack.
+    #[derive(Zeroize, ZeroizeOnDrop)]
+    pub struct SecretArray<const N: usize>([u8; N]);
+
+    impl<const N: usize> SecretArray<N> {
+        /// Create a new `SecretArray` from an owned byte array.
+        pub fn new(bytes: [u8; N]) -> Self {
+            Self(bytes)
+        }
+
+        /// Create an all-zero `SecretArray`, to be filled in place via [`Self::expose_mut`].
+        pub fn zeroed() -> Self {
+            Self([0u8; N])
+        }
+
+        /// Borrow the inner bytes without copying.
+        pub fn expose(&self) -> &[u8; N] {
+            &self.0
+        }
+
+        /// Mutably borrow the inner bytes, e.g. to fill them from an RNG without a
+        /// temporary copy.
+        pub fn expose_mut(&mut self) -> &mut [u8; N] {
+            &mut self.0
+        }
+    }
+
+    impl<const N: usize> AsRef<[u8]> for SecretArray<N> {
+        fn as_ref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    impl<const N: usize> Deref for SecretArray<N> {
+        type Target = [u8];
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl<const N: usize> From<[u8; N]> for SecretArray<N> {
+        fn from(bytes: [u8; N]) -> Self {
+            Self(bytes)
+        }
+    }
+
+    impl<const N: usize> core::fmt::Debug for SecretArray<N> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "SecretArray([redacted], len={})", N)
+        }
+    }
+}
+
+#[cfg(feature = "zeroize-alloc")]
+pub mod allocator {
+    //! A global allocator which zeroizes every allocation when it is freed.
+    //!
+    //! The wrappers zeroize their own buffer, but a `Vec` which grows during a secret's
+    //! lifetime frees its old buffer without clearing it, and other code may free copies this
+    //! crate never sees. Installing [`ZeroAlloc`] as the global allocator clears all of these:
+    //!
+    //! ```
+    //! use secret_utils::allocator::ZeroAlloc;
+    //! use std::alloc::System;
+    //!
+    //! #[global_allocator]
+    //! static ALLOCATOR: ZeroAlloc<System> = ZeroAlloc(System);
+    //! ```
+    //!
+    //! This wraps the [`zeroizing-alloc`](https://docs.rs/zeroizing-alloc) crate, so that this
+    //! crate keeps forbidding `unsafe` code. It works on stable Rust, as it replaces the global
+    //! allocator rather than relying on the unstable `Allocator` trait, and costs a `memset` on
+    //! every deallocation in the process. Reallocation always moves to a new block, so the old
+    //! block is zeroized as it is freed.
+
+    pub use zeroizing_alloc::ZeroAlloc;
 }
 
-/// Placeholder module for secret-related traits and policies.
-///
-/// This module will define shared traits and policy helpers for secret lifecycles,
-/// zeroization semantics, and conversion boundaries.
 pub mod traits {
+    //! Placeholder module for secret-related traits and policies.
+    //!
+    //! This module will define shared traits and policy helpers for secret lifecycles,
+    //! zeroization semantics, and conversion boundaries.
+    //!
     //! Future contents:
     //! - Traits describing zeroization guarantees
     //! - Traits for controlled exposure and borrowing
@@ -219,12 +968,13 @@ pub mod traits {
     //! Intentionally empty in this initial scaffold.
 }
 
-/// Placeholder module for internal test utilities.
-///
-/// This module will eventually include optional test-only helpers to validate
-/// zeroization and to instrument secret lifecycles under controlled conditions.
 #[cfg(any(test, doc))]
 pub mod test_utils {
+    //! Placeholder module for internal test utilities.
+    //!
+    //! This module will eventually include optional test-only helpers to validate
+    //! zeroization and to instrument secret lifecycles under controlled conditions.
+    //!
     //! Future contents:
     //! - Test-only helpers for memory inspections (where viable)
     //! - Utilities to construct scoped secrets for lifecycle tests
@@ -234,11 +984,61 @@ pub mod test_utils {
 
 #[cfg(test)]
 mod tests {
-    use super::wrappers::{SecretBytes, SecretKey};
+    use super::SecretError;
+    use super::wrappers::{SecretArray, SecretBytes, SecretKey, SecretString};
     use alloc::format;
     use alloc::vec;
     use zeroize::Zeroize;
 
+    /// Fails to compile if `$ty` implements `$trait`.
+    ///
+    /// If the trait were implemented, both blanket impls below would apply and the
+    /// call to `some_item` would be ambiguous.
+    macro_rules! assert_not_impl {
+        ($ty:ty: $trait:path) => {
+            const _: fn() = || {
+                trait AmbiguousIfImpl<A> {
+                    fn some_item() {}
+                }
+                impl<T: ?Sized> AmbiguousIfImpl<()> for T {}
+                #[allow(dead_code)]
+                struct Invalid;
+                impl<T: ?Sized + $trait> AmbiguousIfImpl<Invalid> for T {}
+                let _ = <$ty as AmbiguousIfImpl<_>>::some_item;
+            };
+        };
+    }
+
+    // Secrets must never be hashable: hashing would leak secret bytes into map layout.
+    assert_not_impl!(SecretKey: core::hash::Hash);
+    assert_not_impl!(SecretBytes: core::hash::Hash);
+    // Growing via `Extend` would leave the old copy of the secret behind on reallocation.
+    assert_not_impl!(SecretBytes: Extend<u8>);
+    assert_not_impl!(SecretArray<32>: core::hash::Hash);
+    assert_not_impl!(SecretString: core::hash::Hash);
+    #[cfg(feature = "curve25519")]
+    assert_not_impl!(super::wrappers::SecretScalar: core::hash::Hash);
+
+    // Comparing secrets must be explicit, through `ct_eq`: derived equality and ordering return
+    // at the first differing byte, and ordering would also leak through sorting and `BTreeMap`s.
+    assert_not_impl!(SecretKey: PartialEq);
+    assert_not_impl!(SecretBytes: PartialEq);
+    assert_not_impl!(SecretKey: PartialOrd);
+    assert_not_impl!(SecretBytes: PartialOrd);
+    assert_not_impl!(SecretKey: Ord);
+    assert_not_impl!(SecretBytes: Ord);
+
+    // Secrets are moved to and shared with other threads, e.g. session keys held by a server.
+    const _: fn() = || {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SecretKey>();
+        assert_send_sync::<SecretBytes>();
+        assert_send_sync::<SecretString>();
+        assert_send_sync::<SecretArray<32>>();
+        #[cfg(feature = "curve25519")]
+        assert_send_sync::<super::wrappers::SecretScalar>();
+    };
+
     #[test]
     fn secret_key_zeroize_sets_to_zero() {
         let mut key = SecretKey::new(vec![1u8, 2, 3, 4, 5]);
@@ -259,6 +1059,110 @@ mod tests {
         assert!(bytes.expose().iter().all(|&b| b == 0));
     }
 
+    #[test]
+    fn secret_key_expose_mut_keeps_zeroize() {
+        let mut key = SecretKey::new(vec![1u8, 2, 3, 4]);
+        // ratchet the key in place
+        for byte in key.expose_mut() {
+            *byte = byte.wrapping_mul(3) ^ 0x5a;
+        }
+        assert_eq!(key.expose(), &[0x59, 0x5c, 0x53, 0x56]);
+
+        key.zeroize();
+        assert!(key.expose().iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    #[cfg(feature = "digest")]
+    fn secret_key_from_digest_output() {
+        use sha2::{Digest, Sha512};
+
+        let output = Sha512::digest(b"session key material");
+        let expected = output.to_vec();
+        let key = SecretKey::from(output);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key.expose(), expected.as_slice());
+    }
+
+    #[test]
+    fn secret_key_reveal_for_returns_only_the_result() {
+        use hmac::{Hmac, Mac};
+
+        let key = SecretKey::new(vec![0x0b; 32]);
+        let tag = key.reveal_for(|bytes| {
+            let mut mac = <Hmac<sha2::Sha256> as Mac>::new_from_slice(bytes).unwrap();
+            mac.update(b"authenticated message");
+            mac.finalize().into_bytes()
+        });
+
+        let mut mac = <Hmac<sha2::Sha256> as Mac>::new_from_slice(&[0x0b; 32]).unwrap();
+        mac.update(b"authenticated message");
+        mac.verify(&tag).unwrap();
+
+        let bytes = SecretBytes::new(vec![1, 2, 3]);
+        assert_eq!(bytes.reveal_for(|b| b.len()), 3);
+    }
+
+    #[test]
+    fn secret_key_take_from_empties_the_source() {
+        let mut source = vec![1u8, 7, 0, 1];
+        let key = SecretKey::take_from(&mut source);
+        assert_eq!(key.expose(), &[1, 7, 0, 1]);
+        assert!(source.is_empty());
+        assert_eq!(source.capacity(), 0);
+
+        let mut source = vec![4u8, 2];
+        let bytes = SecretBytes::take_from(&mut source);
+        assert_eq!(bytes.expose(), &[4, 2]);
+        assert_eq!(source.capacity(), 0);
+    }
+
+    #[test]
+    fn secret_key_new_with_fills_in_place() {
+        let key = SecretKey::new_with(6, |buf| {
+            assert!(buf.iter().all(|&b| b == 0));
+            for (i, byte) in buf.iter_mut().enumerate() {
+                *byte = 0xa0 | i as u8;
+            }
+        });
+        assert_eq!(key.expose(), &[0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5]);
+
+        let empty = SecretKey::new_with(0, |buf| assert!(buf.is_empty()));
+        assert!(empty.expose().is_empty());
+    }
+
+    #[test]
+    fn secret_bytes_expose_mut_keeps_zeroize() {
+        let mut bytes = SecretBytes::new(vec![0u8; 4]);
+        bytes.expose_mut().copy_from_slice(b"pass");
+        assert_eq!(bytes.expose(), b"pass");
+
+        bytes.zeroize();
+        assert!(bytes.expose().iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn secret_bytes_append_zeroizing_reallocates_and_keeps_contents() {
+        use alloc::vec::Vec;
+
+        let mut secret = SecretBytes::new(Vec::with_capacity(4));
+        secret.append_zeroizing(b"pass");
+        let before = secret.expose().as_ptr();
+
+        // exceeding the capacity forces a move into a new buffer
+        secret.append_zeroizing(b"word-with-a-long-suffix");
+        assert_ne!(secret.expose().as_ptr(), before);
+        assert_eq!(secret.expose(), b"password-with-a-long-suffix");
+
+        // appending within the capacity does not reallocate
+        let mut secret = SecretBytes::new(Vec::with_capacity(16));
+        let before = secret.expose().as_ptr();
+        secret.append_zeroizing(b"abc");
+        secret.append_zeroizing(b"def");
+        assert_eq!(secret.expose().as_ptr(), before);
+        assert_eq!(secret.expose(), b"abcdef");
+    }
+
     #[test]
     fn secret_key_debug_is_redacted() {
         let key = SecretKey::new(vec![9u8, 8, 7]);
@@ -279,6 +1183,31 @@ mod tests {
         assert!(!s.contains("1, 2, 3, 4"));
     }
 
+    #[test]
+    fn secret_string_debug_is_redacted_and_zeroizes() {
+        let mut password = SecretString::new("hunter2".into());
+        let s = format!("{:?}", password);
+        assert!(s.contains("SecretString([redacted]"));
+        assert!(s.contains("len=7"));
+        assert!(!s.contains("hunter2"));
+
+        assert_eq!(password.expose(), "hunter2");
+        password.zeroize();
+        assert!(password.expose().is_empty());
+    }
+
+    #[test]
+    fn secret_string_clone_exposed_is_zeroizing() {
+        let password = SecretString::new("hunter2".into());
+        let mut clone = password.clone_exposed();
+        assert_eq!(clone.as_str(), password.expose());
+
+        // `Zeroizing` runs this on drop
+        clone.zeroize();
+        assert!(clone.is_empty());
+        assert_eq!(password.expose(), "hunter2");
+    }
+
     #[test]
     fn secret_key_into_inner_round_trip() {
         let original = vec![1u8, 2, 3, 4, 5];
@@ -317,4 +1246,364 @@ mod tests {
         assert!(a1.ct_eq(&a2));
         assert!(!a1.ct_eq(&b));
     }
+
+    #[test]
+    fn secret_key_ct_eq_slice_runs_over_the_longer_input() {
+        use super::wrappers::ct_compare;
+
+        let key = SecretKey::new(vec![7u8; 32]);
+        assert!(key.ct_eq_slice(&[7u8; 32]));
+        assert!(!key.ct_eq_slice(&[7u8; 31]));
+
+        // neither a shorter nor a longer input stops the loop early
+        assert!(!key.ct_eq_slice(&[]));
+        assert_eq!(ct_compare(key.expose(), &[]).1, 32);
+        let mut longer = [0u8; 64];
+        longer[..32].copy_from_slice(key.expose());
+        assert!(!key.ct_eq_slice(&longer));
+        assert_eq!(ct_compare(key.expose(), &longer).1, 64);
+    }
+
+    #[test]
+    fn secret_bytes_split_first_chunk() {
+        let blob = SecretBytes::new((0u8..20).collect());
+        let (salt, key) = blob.split_first_chunk::<16>().unwrap();
+        assert_eq!(salt, core::array::from_fn::<u8, 16, _>(|i| i as u8));
+        assert_eq!(key.expose(), &[16u8, 17, 18, 19]);
+
+        // a secret of exactly the header's length leaves an empty remainder
+        let (header, rest) = SecretBytes::new(vec![1u8; 16])
+            .split_first_chunk::<16>()
+            .unwrap();
+        assert_eq!(header, [1u8; 16]);
+        assert!(rest.is_empty());
+
+        let short = SecretBytes::new(vec![1u8; 15]);
+        assert!(matches!(
+            short.split_first_chunk::<16>(),
+            Err(SecretError::LengthMismatch {
+                expected: 16,
+                got: 15
+            })
+        ));
+    }
+
+    #[test]
+    fn secret_bytes_ct_eq_exact() {
+        use super::wrappers::ct_compare;
+
+        let stored = SecretBytes::new(b"reset-token-0123".to_vec());
+
+        // equal
+        assert!(stored.ct_eq_exact(b"reset-token-0123"));
+        assert_eq!(ct_compare(stored.expose(), b"reset-token-0123").1, 16);
+
+        // one is a prefix of the other, in either direction
+        assert!(!stored.ct_eq_exact(b"reset-token"));
+        assert_eq!(ct_compare(stored.expose(), b"reset-token").1, 16);
+        assert!(!stored.ct_eq_exact(b"reset-token-0123-4567"));
+        assert_eq!(ct_compare(stored.expose(), b"reset-token-0123-4567").1, 21);
+
+        // differing contents and lengths
+        assert!(!stored.ct_eq_exact(b"reset-token-0124"));
+        assert!(!stored.ct_eq_exact(b"x"));
+        assert!(!stored.ct_eq_exact(&[]));
+        assert_eq!(ct_compare(stored.expose(), &[]).1, 16);
+    }
+
+    #[test]
+    fn secret_key_ct_eq_detects_length_mismatch_of_256() {
+        // 1 ^ 257 == 256, whose low byte is zero
+        let short = SecretKey::new(vec![0u8; 1]);
+        let long = SecretKey::new(vec![0u8; 257]);
+        assert!(!short.ct_eq(&long));
+        assert!(!long.ct_eq(&short));
+    }
+
+    /// dudect-style timing test: compares the timing of `ct_eq` on equal keys against keys
+    /// differing in their first byte with Welch's t-test, after cropping slow outliers.
+    ///
+    /// Slow and sensitive to machine noise, so it is ignored by default; run it on a quiet
+    /// machine with `cargo test --release --features std -- --ignored ct_eq_timing`.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore]
+    fn secret_key_ct_eq_timing_is_independent_of_contents() {
+        use alloc::vec::Vec;
+        use core::hint::black_box;
+        use std::time::Instant;
+
+        const SAMPLES: usize = 200_000;
+        // dudect's threshold: |t| above this is strong evidence of a timing leak
+        const T_THRESHOLD: f64 = 4.5;
+
+        let reference = SecretKey::new(vec![0x5a; 64]);
+        // both classes are written into the same buffer, so they share a memory location
+        let mut candidate = SecretKey::new(vec![0x5a; 64]);
+
+        // interleave the two classes pseudo-randomly so drift affects both equally
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut timings: [Vec<u64>; 2] = [Vec::new(), Vec::new()];
+        for _ in 0..SAMPLES {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let class = (state & 1) as usize;
+            candidate.expose_mut()[0] = 0x5a ^ class as u8;
+
+            let start = Instant::now();
+            black_box(black_box(&reference).ct_eq(black_box(&candidate)));
+            timings[class].push(start.elapsed().as_nanos() as u64);
+        }
+
+        // crop measurements above the 90th percentile of all samples, as dudect does
+        let mut all: Vec<u64> = timings.iter().flatten().copied().collect();
+        all.sort_unstable();
+        let cutoff = all[all.len() * 9 / 10];
+
+        let stats = |samples: &[u64]| {
+            let cropped: Vec<f64> = samples
+                .iter()
+                .filter(|&&t| t <= cutoff)
+                .map(|&t| t as f64)
+                .collect();
+            let n = cropped.len() as f64;
+            let mean = cropped.iter().sum::<f64>() / n;
+            let var = cropped.iter().map(|t| (t - mean) * (t - mean)).sum::<f64>() / (n - 1.0);
+            (mean, var, n)
+        };
+        let (mean_eq, var_eq, n_eq) = stats(&timings[0]);
+        let (mean_ne, var_ne, n_ne) = stats(&timings[1]);
+        let t = (mean_eq - mean_ne) / (var_eq / n_eq + var_ne / n_ne).sqrt();
+
+        assert!(
+            t.abs() < T_THRESHOLD,
+            "ct_eq timing depends on the key contents: t = {t:.2}"
+        );
+    }
+
+    #[test]
+    fn secret_key_xor_with_masks_and_unmasks() {
+        let mut key = SecretKey::new(vec![0b1010_1010u8, 0x00, 0xff]);
+        let mask = SecretKey::new(vec![0b0101_0101u8, 0x42, 0xff]);
+
+        key.xor_with(&mask).unwrap();
+        assert_eq!(key.expose(), &[0xffu8, 0x42, 0x00]);
+
+        // applying the same mask again restores the original key
+        key.xor_with(&mask).unwrap();
+        assert_eq!(key.expose(), &[0b1010_1010u8, 0x00, 0xff]);
+    }
+
+    #[test]
+    fn secret_key_verify_tag() {
+        let key = SecretKey::new(vec![1u8, 2, 3, 4]);
+        assert_eq!(key.verify_tag(&[1, 2, 3, 4]), Ok(()));
+        assert_eq!(key.verify_tag(&[1, 2, 3, 5]), Err(SecretError::TagMismatch));
+        assert_eq!(key.verify_tag(&[1, 2, 3]), Err(SecretError::TagMismatch));
+        assert_eq!(
+            key.verify_tag(&[1, 2, 3, 4, 0]),
+            Err(SecretError::TagMismatch)
+        );
+    }
+
+    #[test]
+    fn secret_key_try_into_array() {
+        let key = SecretKey::new(vec![7u8; 32]);
+        assert_eq!(key.try_into_array::<32>(), Ok([7u8; 32]));
+
+        let key = SecretKey::new(vec![7u8; 32]);
+        assert_eq!(
+            key.try_into_array::<16>(),
+            Err(SecretError::LengthMismatch {
+                expected: 16,
+                got: 32
+            })
+        );
+    }
+
+    #[test]
+    fn secret_key_xor_with_rejects_length_mismatch() {
+        let mut key = SecretKey::new(vec![1u8, 2, 3]);
+        let mask = SecretKey::new(vec![1u8, 2]);
+
+        assert_eq!(
+            key.xor_with(&mask),
+            Err(SecretError::LengthMismatch {
+                expected: 3,
+                got: 2
+            })
+        );
+        assert_eq!(key.expose(), &[1u8, 2, 3]);
+    }
+
+    #[test]
+    fn secret_array_zeroize_sets_to_zero() {
+        let mut array = SecretArray::new([0x5au8; 16]);
+        assert!(array.expose().iter().any(|&b| b != 0));
+        array.zeroize();
+        assert_eq!(array.expose(), &[0u8; 16]);
+    }
+
+    #[test]
+    fn secret_array_debug_is_redacted() {
+        let array = SecretArray::from([1u8, 2, 3, 4]);
+        let s = format!("{:?}", array);
+        assert!(s.contains("SecretArray([redacted]"));
+        assert!(s.contains("len=4"));
+        assert!(!s.contains("1, 2, 3, 4"));
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn secret_scalar_debug_is_redacted() {
+        use super::wrappers::SecretScalar;
+
+        let scalar = SecretScalar::new(curve25519_dalek::Scalar::from(1234u32));
+        let s = format!("{:?}", scalar);
+        assert_eq!(s, "SecretScalar([redacted])");
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn secret_scalar_from_bytes_reduces_and_clears_input() {
+        use super::wrappers::SecretScalar;
+        use curve25519_dalek::Scalar;
+
+        // all ones is above the group order, so the reduction is exercised
+        let mut bytes = [0xffu8; 32];
+        let scalar = SecretScalar::from_bytes_mod_order(&mut bytes);
+        assert_eq!(*scalar.expose(), Scalar::from_bytes_mod_order([0xffu8; 32]));
+        assert_eq!(bytes, [0u8; 32]);
+
+        let mut wide = [0xffu8; 64];
+        let scalar = SecretScalar::from_bytes_mod_order_wide(&mut wide);
+        assert_eq!(
+            *scalar.expose(),
+            Scalar::from_bytes_mod_order_wide(&[0xffu8; 64])
+        );
+        assert_eq!(wide, [0u8; 64]);
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn secret_scalar_with_scalar_returns_only_the_result() {
+        use super::wrappers::SecretScalar;
+        use curve25519_dalek::Scalar;
+        use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+
+        let scalar = SecretScalar::new(Scalar::from(42u64));
+        let public = scalar.with_scalar(|x| RISTRETTO_BASEPOINT_POINT * x);
+        assert_eq!(public, RISTRETTO_BASEPOINT_POINT * Scalar::from(42u64));
+    }
+
+    #[cfg(feature = "curve25519")]
+    #[test]
+    fn secret_scalar_zeroize_sets_to_zero() {
+        use super::wrappers::SecretScalar;
+        use curve25519_dalek::Scalar;
+
+        // guards the zeroization claim against changes to dalek's internal representation
+        let mut scalar = SecretScalar::new(-Scalar::from(0x0123_4567_89ab_cdefu64));
+        assert_ne!(*scalar.expose(), Scalar::ZERO);
+        scalar.zeroize();
+        assert_eq!(*scalar.expose(), Scalar::ZERO);
+        assert_eq!(scalar.expose().to_bytes(), [0u8; 32]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn secret_key_serde_round_trip_binary_only() {
+        let key = SecretKey::new(vec![7u8; 32]);
+        let config = bincode::config::standard();
+
+        let encoded = bincode::serde::encode_to_vec(&key, config).unwrap();
+        let (decoded, _): (SecretKey, usize) =
+            bincode::serde::decode_from_slice(&encoded, config).unwrap();
+        assert!(decoded.ct_eq(&key));
+
+        assert!(serde_json::to_string(&key).is_err());
+        assert!(serde_json::from_str::<SecretKey>("[7,7,7]").is_err());
+    }
+
+    #[cfg(all(feature = "serde", feature = "curve25519"))]
+    #[test]
+    fn secret_scalar_serde_round_trip_binary_only() {
+        use super::wrappers::SecretScalar;
+        use curve25519_dalek::Scalar;
+
+        let scalar = SecretScalar::new(Scalar::from(0xdead_beefu64));
+        let config = bincode::config::standard();
+
+        let encoded = bincode::serde::encode_to_vec(&scalar, config).unwrap();
+        let (decoded, _): (SecretScalar, usize) =
+            bincode::serde::decode_from_slice(&encoded, config).unwrap();
+        assert_eq!(decoded.expose(), scalar.expose());
+
+        assert!(serde_json::to_string(&scalar).is_err());
+    }
+
+    #[cfg(all(feature = "serde", feature = "curve25519"))]
+    #[test]
+    fn secret_scalar_deserialize_rejects_non_canonical_encoding() {
+        use super::wrappers::SecretScalar;
+
+        let config = bincode::config::standard();
+        // 2^256 - 1 is larger than the group order, so it is not a canonical encoding
+        let encoded = bincode::serde::encode_to_vec(serde_bytes_32([0xff; 32]), config).unwrap();
+        let res: Result<(SecretScalar, usize), _> =
+            bincode::serde::decode_from_slice(&encoded, config);
+        assert!(res.is_err());
+    }
+
+    /// Serialize an array using `serialize_bytes`, matching the wrappers' encoding
+    #[cfg(all(feature = "serde", feature = "curve25519"))]
+    fn serde_bytes_32(bytes: [u8; 32]) -> impl serde::Serialize {
+        struct Bytes([u8; 32]);
+        impl serde::Serialize for Bytes {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+        Bytes(bytes)
+    }
+
+    #[test]
+    fn secrets_as_map_values_keyed_by_public_id() {
+        use alloc::collections::BTreeMap;
+        use alloc::vec::Vec;
+
+        let mut store: BTreeMap<Vec<u8>, SecretKey> = BTreeMap::new();
+        store.insert(b"alice".to_vec(), SecretKey::new(vec![1u8; 32]));
+        store.insert(b"bob".to_vec(), SecretKey::new(vec![2u8; 32]));
+        assert!(store[b"alice".as_slice()].ct_eq(&SecretKey::new(vec![1u8; 32])));
+        assert!(store[b"bob".as_slice()].ct_eq(&SecretKey::new(vec![2u8; 32])));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn secret_bytes_from_reader_reads_to_eof() {
+        let data: Vec<u8> = (0..200u8).collect();
+        let mut cursor = std::io::Cursor::new(data.clone());
+        let bytes = SecretBytes::from_reader(&mut cursor, 256).unwrap();
+        assert_eq!(bytes.expose(), data.as_slice());
+
+        // an input of exactly `max_len` bytes is accepted
+        let mut cursor = std::io::Cursor::new(data.clone());
+        let bytes = SecretBytes::from_reader(&mut cursor, 200).unwrap();
+        assert_eq!(bytes.expose(), data.as_slice());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn secret_bytes_from_reader_rejects_input_over_max_len() {
+        let mut cursor = std::io::Cursor::new(vec![0x42u8; 65]);
+        let err = SecretBytes::from_reader(&mut cursor, 64).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        // caps smaller than the initial allocation are honoured too
+        let mut cursor = std::io::Cursor::new(vec![0x42u8; 5]);
+        let err = SecretBytes::from_reader(&mut cursor, 4).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
 }
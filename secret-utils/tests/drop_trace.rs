@@ -0,0 +1,43 @@
+//! The `drop-trace` events, captured with a logger installed for this binary.
+#![cfg(feature = "drop-trace")]
+
+use log::{LevelFilter, Log, Metadata, Record};
+use secret_utils::wrappers::{SecretBytes, SecretKey};
+use std::sync::Mutex;
+
+struct Capture(Mutex<Vec<String>>);
+
+impl Log for Capture {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "secret_utils::drop"
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+#[test]
+fn drop_emits_length_and_type_only() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    drop(SecretKey::new(b"0123456789abcdef0123456789abcdef".to_vec()));
+    drop(SecretBytes::new(b"hunter2".to_vec()));
+
+    let events = LOGGER.0.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        ["dropping SecretKey len=32", "dropping SecretBytes len=7"]
+    );
+    for event in &events {
+        assert!(!event.contains("0123456789abcdef"));
+        assert!(!event.contains("hunter2"));
+    }
+}
@@ -0,0 +1,150 @@
+//! What secrets leave behind in the memory they release.
+//!
+//! The global allocator of this binary never hands freed blocks back, so a buffer can still be
+//! read through a raw pointer after the secret owning it was dropped, and no later allocation
+//! can reuse it in the meantime. Reading it needs `unsafe`, which the library forbids, so this
+//! lives in an integration test.
+#![cfg(feature = "alloc")]
+
+use secret_utils::wrappers::SecretKey;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::mem::ManuallyDrop;
+
+/// Allocates from `System` and leaks every block it is asked to free
+struct KeepFreed;
+
+// SAFETY: allocations are delegated to `System`; a block is never freed, so it stays valid for
+// as long as the process runs
+unsafe impl GlobalAlloc for KeepFreed {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+}
+
+#[global_allocator]
+static ALLOCATOR: KeepFreed = KeepFreed;
+
+/// Copy out `len` bytes at `ptr`, a buffer released by a dropped secret
+fn released_bytes(ptr: *const u8, len: usize) -> Vec<u8> {
+    // SAFETY: `KeepFreed` never releases the block, so all of it is still mapped, and every
+    // byte of it was written before it was freed; volatile reads keep the compiler from
+    // assuming anything about memory it has seen deallocated
+    (0..len)
+        .map(|i| unsafe { ptr.add(i).read_volatile() })
+        .collect()
+}
+
+/// The bytes a dropped `SecretKey` leaves in its buffer
+fn wiped_byte() -> u8 {
+    #[cfg(all(feature = "zeroize-debug", debug_assertions))]
+    return secret_utils::wrappers::POISON;
+    #[cfg(not(all(feature = "zeroize-debug", debug_assertions)))]
+    return 0;
+}
+
+#[test]
+fn dropping_a_secret_key_wipes_its_buffer() {
+    let mut key = ManuallyDrop::new(SecretKey::new(vec![0x42; 32]));
+    let ptr = key.expose().as_ptr();
+    assert_eq!(released_bytes(ptr, 32), [0x42; 32]);
+
+    // SAFETY: `key` is not used again after being dropped
+    unsafe { ManuallyDrop::drop(&mut key) };
+    assert_eq!(released_bytes(ptr, 32), [wiped_byte(); 32]);
+}
+
+/// A panic part way through building a struct still drops, and so wipes, the secrets built
+/// before it.
+#[test]
+fn secret_fields_are_wiped_when_construction_panics() {
+    use secret_utils::wrappers::SecretBytes;
+    use std::cell::Cell;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    #[allow(dead_code)]
+    struct Credentials {
+        session_key: SecretKey,
+        password: SecretBytes,
+    }
+
+    fn read_password() -> SecretBytes {
+        panic!("password source failed");
+    }
+
+    let key_buffer = Cell::new(std::ptr::null());
+    let result = catch_unwind(AssertUnwindSafe(|| Credentials {
+        session_key: {
+            let key = SecretKey::new(vec![0x42; 32]);
+            key_buffer.set(key.expose().as_ptr());
+            key
+        },
+        password: read_password(),
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(
+        released_bytes(key_buffer.get(), 32),
+        [wiped_byte(); 32],
+        "fields built before the panic must be dropped"
+    );
+}
+
+#[test]
+fn append_zeroizing_wipes_the_buffer_it_outgrows() {
+    use secret_utils::wrappers::SecretBytes;
+
+    let mut secret = SecretBytes::new(Vec::with_capacity(8));
+    secret.append_zeroizing(b"password");
+    let old = secret.expose().as_ptr();
+
+    // exceeding the capacity moves the secret into a new buffer and releases the old one
+    secret.append_zeroizing(b"-with-a-suffix-too-long-for-the-first-buffer");
+    assert_ne!(secret.expose().as_ptr(), old);
+    assert_eq!(
+        secret.expose(),
+        b"password-with-a-suffix-too-long-for-the-first-buffer"
+    );
+    assert_eq!(released_bytes(old, 8), [0; 8]);
+}
+
+/// A secret truncated to 3 of its 8 bytes, with the location and capacity of its buffer
+fn truncated_secret() -> (Vec<u8>, *const u8, usize) {
+    let mut buf = vec![0x42; 8];
+    buf.truncate(3);
+    let (ptr, capacity) = (buf.as_ptr(), buf.capacity());
+    (buf, ptr, capacity)
+}
+
+#[test]
+#[cfg(all(feature = "zeroize-debug", debug_assertions))]
+fn dropping_secret_bytes_poisons_the_whole_allocation() {
+    use secret_utils::Zeroize;
+    use secret_utils::wrappers::{POISON, SecretBytes};
+
+    let (buf, ptr, capacity) = truncated_secret();
+    drop(SecretBytes::new(buf));
+    assert!(released_bytes(ptr, capacity).iter().all(|&b| b == POISON));
+
+    // an explicit zeroize still leaves zeros, only dropped buffers are poisoned
+    let mut key = SecretKey::new(vec![0x42; 8]);
+    key.zeroize();
+    assert!(key.expose().iter().all(|&b| b == 0));
+}
+
+#[test]
+#[cfg(not(feature = "zeroize-debug"))]
+fn dropping_secrets_zeroes_the_whole_allocation() {
+    use secret_utils::wrappers::{SecretBytes, SecretString};
+
+    let (buf, ptr, capacity) = truncated_secret();
+    drop(SecretBytes::new(buf));
+    assert!(released_bytes(ptr, capacity).iter().all(|&b| b == 0));
+
+    let mut text = String::from("correct horse");
+    text.truncate(7);
+    let (ptr, capacity) = (text.as_ptr(), text.capacity());
+    drop(SecretString::new(text));
+    assert!(released_bytes(ptr, capacity).iter().all(|&b| b == 0));
+}
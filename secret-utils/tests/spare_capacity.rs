@@ -0,0 +1,38 @@
+//! Zeroizing a secret clears its spare capacity, not just its live bytes.
+//!
+//! Reading spare capacity needs `unsafe`, which the library forbids, so this lives in an
+//! integration test.
+#![cfg(feature = "alloc")]
+
+use secret_utils::Zeroize;
+use secret_utils::wrappers::SecretBytes;
+
+/// Copy out the bytes in `v`'s spare capacity
+fn spare_bytes(v: &mut Vec<u8>) -> Vec<u8> {
+    v.spare_capacity_mut()
+        .iter()
+        // SAFETY: every byte of the allocation was written before the vec was truncated, and
+        // zeroizing writes the spare capacity rather than deinitializing it
+        .map(|byte| unsafe { byte.assume_init_read() })
+        .collect()
+}
+
+#[test]
+fn secret_bytes_zeroize_clears_spare_capacity() {
+    // grow the buffer, then shrink it, leaving stale secret bytes in the spare capacity
+    let mut buf = Vec::with_capacity(64);
+    buf.extend_from_slice(&[0xaa; 64]);
+    buf.truncate(8);
+    let capacity = buf.capacity();
+    assert!(spare_bytes(&mut buf).iter().all(|&byte| byte == 0xaa));
+
+    let mut secret = SecretBytes::new(buf);
+    secret.zeroize();
+    assert!(secret.is_empty());
+
+    let mut inner = secret.into_inner();
+    assert_eq!(inner.capacity(), capacity);
+    let spare = spare_bytes(&mut inner);
+    assert_eq!(spare.len(), capacity);
+    assert!(spare.iter().all(|&byte| byte == 0));
+}
@@ -0,0 +1,34 @@
+//! `SecretKey` under the zeroizing global allocator, which has to be installed per binary.
+#![cfg(feature = "zeroize-alloc")]
+
+use secret_utils::Zeroize;
+use secret_utils::allocator::ZeroAlloc;
+use secret_utils::wrappers::SecretKey;
+use std::alloc::System;
+
+#[global_allocator]
+static ALLOCATOR: ZeroAlloc<System> = ZeroAlloc(System);
+
+#[test]
+fn secret_key_behaves_identically_under_zeroizing_allocator() {
+    let a = SecretKey::new(vec![1, 2, 3, 4]);
+    let b = SecretKey::new(vec![1, 2, 3, 4]);
+    let c = SecretKey::new(vec![1, 2, 3, 5]);
+    assert!(a.ct_eq(&b));
+    assert!(!a.ct_eq(&c));
+    assert_eq!(a.expose(), &[1, 2, 3, 4]);
+    assert_eq!(format!("{:?}", a), "SecretKey([redacted], len=4)");
+
+    let mut masked = SecretKey::new(vec![0xff; 4]);
+    masked.xor_with(&c).unwrap();
+    assert_eq!(masked.expose(), &[0xfe, 0xfd, 0xfc, 0xfa]);
+
+    let mut zeroized = SecretKey::new(vec![0xaa; 8]);
+    zeroized.zeroize();
+    assert!(zeroized.expose().iter().all(|&byte| byte == 0));
+
+    // growing the extracted buffer reallocates through the zeroizing allocator
+    let mut inner = b.into_inner();
+    inner.extend_from_slice(&[0u8; 1024]);
+    assert_eq!(&inner[..4], &[1, 2, 3, 4]);
+}
@@ -68,7 +68,7 @@ impl Group for Ed25519Group {
     {
         let mut seed = [0u8; 64];
         cspring.try_fill_bytes(&mut seed).map_err(|_| Error::Rng)?;
-        let digest = Sha512::digest(&seed);
+        let digest = Sha512::digest(seed);
         let mut wide = [0u8; 64];
         wide.copy_from_slice(&digest);
         Ok(c2_Scalar::from_bytes_mod_order_wide(&wide))
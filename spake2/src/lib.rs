@@ -352,7 +352,6 @@ impl<G: Group> Spake2<G> {
     }
 
     /// Start with identity `idA` and the provided cryptographically secure RNG.
-    #[must_use]
     pub fn start_a_with_rng<R: TryCryptoRng + TryRngCore>(
         password: &Password,
         id_a: &Identity,
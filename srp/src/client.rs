@@ -8,9 +8,9 @@
 //! password hashing algorithm instead (e.g. PBKDF2, argon2 or scrypt).
 //!
 //! ```rust
-//! use crate::srp::groups::G_2048;
+//! use srp_conflux::groups::G_2048;
 //! use sha2::Sha256; // Note: You should probably use a proper password KDF
-//! # use crate::srp::client::SrpClient;
+//! # use srp_conflux::client::SrpClient;
 //!
 //! let client = SrpClient::<Sha256>::new(&G_2048);
 //! ```
@@ -19,7 +19,7 @@
 //! `salt` and `b_pub`:
 //!
 //! ```rust
-//! # let client = crate::srp::client::SrpClient::<sha2::Sha256>::new(&crate::srp::groups::G_2048);
+//! # let client = srp_conflux::client::SrpClient::<sha2::Sha256>::new(&srp_conflux::groups::G_2048);
 //! # fn server_response()-> (Vec<u8>, Vec<u8>) { (vec![], vec![]) }
 //!
 //! let mut a = [0u8; 64];
@@ -32,7 +32,7 @@
 //! `process_reply` can return error in case of malicious `b_pub`.
 //!
 //! ```rust
-//! # let client = crate::srp::client::SrpClient::<sha2::Sha256>::new(&crate::srp::groups::G_2048);
+//! # let client = srp_conflux::client::SrpClient::<sha2::Sha256>::new(&srp_conflux::groups::G_2048);
 //! # let a = [0u8; 64];
 //! # let username = b"username";
 //! # let password = b"password";
@@ -48,7 +48,7 @@
 //! `verify_server` method will return error in case of incorrect server reply.
 //!
 //! ```rust
-//! # let client = crate::srp::client::SrpClient::<sha2::Sha256>::new(&crate::srp::groups::G_2048);
+//! # let client = srp_conflux::client::SrpClient::<sha2::Sha256>::new(&srp_conflux::groups::G_2048);
 //! # let verifier = client.process_reply(b"", b"", b"", b"", b"1").unwrap();
 //! # fn send_proof(_: &[u8]) -> Vec<u8> { vec![173, 202, 13, 26, 207, 73, 0, 46, 121, 238, 48, 170, 96, 146, 60, 49, 88, 76, 12, 184, 152, 76, 207, 220, 140, 205, 190, 189, 117, 6, 131, 63]   }
 //!
@@ -60,7 +60,7 @@
 //! `key` contains shared secret key between user and the server. You can extract shared secret
 //! key using `key()` method.
 //! ```rust
-//! # let client = crate::srp::client::SrpClient::<sha2::Sha256>::new(&crate::srp::groups::G_2048);
+//! # let client = srp_conflux::client::SrpClient::<sha2::Sha256>::new(&srp_conflux::groups::G_2048);
 //! # let verifier = client.process_reply(b"", b"", b"", b"", b"1").unwrap();
 //!
 //! verifier.key();
@@ -73,7 +73,7 @@
 //! Man-in-the-middle (MITM) attack for registration.
 //!
 //! ```rust
-//! # let client = crate::srp::client::SrpClient::<sha2::Sha256>::new(&crate::srp::groups::G_2048);
+//! # let client = srp_conflux::client::SrpClient::<sha2::Sha256>::new(&srp_conflux::groups::G_2048);
 //! # let username = b"username";
 //! # let password = b"password";
 //! # let salt = b"salt";
@@ -6,9 +6,9 @@
 //!
 //!
 //! ```rust
-//! use crate::srp::groups::G_2048;
+//! use srp_conflux::groups::G_2048;
 //! use sha2::Sha256; // Note: You should probably use a proper password KDF
-//! # use crate::srp::server::SrpServer;
+//! # use srp_conflux::server::SrpServer;
 //! # fn get_client_request()-> (Vec<u8>, Vec<u8>) { (vec![], vec![])}
 //! # fn get_user(_: &[u8])-> (Vec<u8>, Vec<u8>) { (vec![], vec![])}
 //!
@@ -25,7 +25,7 @@
 //! Next process the user response:
 //!
 //! ```rust
-//! # let server = crate::srp::server::SrpServer::<sha2::Sha256>::new(&crate::srp::groups::G_2048);
+//! # let server = srp_conflux::server::SrpServer::<sha2::Sha256>::new(&srp_conflux::groups::G_2048);
 //! # fn get_client_response() -> Vec<u8> { vec![1] }
 //! # let b = [0u8; 64];
 //! # let v = b"";
@@ -39,7 +39,7 @@
 //! reply:
 //!
 //! ```rust
-//! # let server = crate::srp::server::SrpServer::<sha2::Sha256>::new(&crate::srp::groups::G_2048);
+//! # let server = srp_conflux::server::SrpServer::<sha2::Sha256>::new(&srp_conflux::groups::G_2048);
 //! # let verifier = server.process_reply(b"", b"", b"1").unwrap();
 //! # fn get_client_proof()-> Vec<u8> { vec![26, 80, 8, 243, 111, 162, 238, 171, 208, 237, 207, 46, 46, 137, 44, 213, 105, 208, 84, 224, 244, 216, 103, 145, 14, 103, 182, 56, 242, 4, 179, 57] };
 //! # fn send_proof(_: &[u8]) { };
@@ -53,7 +53,7 @@
 //! `key` contains shared secret key between user and the server. You can extract shared secret
 //! key using `key()` method.
 //! ```rust
-//! # let server = crate::srp::server::SrpServer::<sha2::Sha256>::new(&crate::srp::groups::G_2048);
+//! # let server = srp_conflux::server::SrpServer::<sha2::Sha256>::new(&srp_conflux::groups::G_2048);
 //! # let verifier = server.process_reply(b"", b"", b"1").unwrap();
 //!
 //! verifier.key();